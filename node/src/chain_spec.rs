@@ -1,12 +1,16 @@
-use node_subspace_runtime::{AccountId, Signature, WASM_BINARY};
+use node_subspace_runtime::{AccountId, Runtime, Signature, WASM_BINARY};
+use pallet_subspace::{MaxAllowedModules, MaxAllowedSubnets, SubnetChangeset, SubnetParams};
 use pallet_subspace_genesis_config::{ConfigModule, ConfigSubnet};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
 use sc_service::ChainType;
 use serde::Deserialize;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_consensus_grandpa::AuthorityId as GrandpaId;
 use sp_core::{crypto::Ss58Codec, sr25519, Pair, Public};
 use sp_runtime::traits::{IdentifyAccount, Verify};
-use std::fs::File;
+use std::{collections::BTreeMap, fs::File};
+
+use crate::cli::DevNetworkParams;
 
 // Specialized `ChainSpec`. This is a specialization of the general Substrate ChainSpec type.
 pub type ChainSpec = sc_service::GenericChainSpec;
@@ -94,6 +98,7 @@ pub fn generate_config(path: &str) -> Result<ChainSpec, String> {
                 min_allowed_weights: subnet.min_allowed_weights,
                 max_allowed_weights: subnet.max_allowed_weights,
                 max_allowed_uids: subnet.max_allowed_uids,
+                use_weights_encryption: subnet.use_weights_encryption,
 
                 modules,
             }
@@ -106,28 +111,11 @@ pub fn generate_config(path: &str) -> Result<ChainSpec, String> {
         .map(|(key, amount)| (account_id_from_str(&key), amount))
         .collect();
 
-    // Give front-ends necessary data to present to users
-    let mut properties = sc_service::Properties::new();
-    properties.insert("tokenSymbol".into(), "C".into());
-    properties.insert("tokenDecimals".into(), 9.into());
-    properties.insert("ss58Format".into(), 13116.into());
-
     let sudo_key = state.sudo.map_or_else(
         || account_id_from_str("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"),
         |key| account_id_from_str(&key),
     );
 
-    let patch = genesis_patch(
-        &[
-            authority_keys_from_seed("Alice"),
-            authority_keys_from_seed("Bob"),
-        ],
-        sudo_key,
-        processed_balances,
-        subnets,
-        state.block,
-    );
-
     let wasm_binary = state.code.map_or_else(
         || {
             WASM_BINARY
@@ -140,14 +128,159 @@ pub fn generate_config(path: &str) -> Result<ChainSpec, String> {
         },
     )?;
 
-    Ok(ChainSpec::builder(&wasm_binary, None)
+    Ok(build_spec(
+        &wasm_binary,
+        sudo_key,
+        processed_balances,
+        subnets,
+        state.block,
+    ))
+}
+
+/// Balance given to each well-known development account.
+const DEV_ACCOUNT_BALANCE: u64 = 1_000_000_000_000_000;
+
+/// Generates a development chain spec in memory, with Alice as sudo and founder of `subnets`
+/// subnets, each filled with `modules` random modules. Stake comes from the well-known
+/// development accounts and every module sets random weights. Weight encryption is disabled, so
+/// consensus runs without decryption nodes.
+pub fn dev_config(params: &DevNetworkParams) -> Result<ChainSpec, String> {
+    let wasm_binary = WASM_BINARY.ok_or_else(|| "WASM binary not available".to_string())?;
+    let mut rng = params.seed.map_or_else(StdRng::from_entropy, StdRng::seed_from_u64);
+
+    let stakers: Vec<_> = ["Alice", "Bob", "Charlie", "Dave", "Eve", "Ferdie"]
+        .into_iter()
+        .map(get_account_id_from_seed::<sr25519::Public>)
+        .collect();
+    let alice = stakers.first().cloned().ok_or("missing development accounts")?;
+
+    let subnets = (0..params.subnets)
+        .map(|netuid| {
+            let modules = (0..params.modules)
+                .map(|uid| {
+                    let key = get_account_id_from_seed::<sr25519::Public>(&format!(
+                        "Alice//{netuid}//{uid}"
+                    ));
+                    let stake_from = stakers
+                        .choose_multiple(&mut rng, 2)
+                        .map(|staker| {
+                            (
+                                staker.clone(),
+                                rng.gen_range(1_000..100_000u64).saturating_mul(1_000_000_000),
+                            )
+                        })
+                        .collect::<BTreeMap<_, _>>();
+
+                    ModuleData {
+                        key,
+                        name: format!("module{uid}").into_bytes(),
+                        address: format!("127.0.0.1:{}", 30_000u32.saturating_add(u32::from(uid)))
+                            .into_bytes(),
+                        weights: Some(random_weights(&mut rng, uid, params.modules)),
+                        stake_from: Some(stake_from),
+                    }
+                })
+                .collect();
+
+            SubnetData {
+                name: format!("dev{netuid}").into_bytes(),
+                founder: alice.clone(),
+
+                tempo: Some(params.tempo),
+                immunity_period: None,
+                min_allowed_weights: Some(1),
+                max_allowed_weights: Some(params.modules.max(1)),
+                max_allowed_uids: Some(params.modules.saturating_mul(2).max(1)),
+                use_weights_encryption: Some(false),
+
+                modules,
+            }
+        })
+        .collect::<Subnets>();
+    validate_dev_subnets(&subnets)?;
+
+    let balances = stakers.iter().map(|account| (account.clone(), DEV_ACCOUNT_BALANCE)).collect();
+
+    Ok(build_spec(wasm_binary, alice, balances, subnets, 0))
+}
+
+/// Runs the generated subnets through the checks of the genesis build, so invalid `dev`
+/// arguments are reported as errors instead of panicking while building the genesis state.
+fn validate_dev_subnets(subnets: &[SubnetData]) -> Result<(), String> {
+    sp_io::TestExternalities::new_empty().execute_with(|| {
+        let max_subnets = MaxAllowedSubnets::<Runtime>::get();
+        if subnets.len() > usize::from(max_subnets) {
+            return Err(format!("at most {max_subnets} subnets can be created"));
+        }
+
+        let modules = subnets.iter().fold(0usize, |total, subnet| {
+            total.saturating_add(subnet.modules.len())
+        });
+        let max_modules = MaxAllowedModules::<Runtime>::get();
+        if modules > usize::from(max_modules) {
+            return Err(format!("at most {max_modules} modules can be registered"));
+        }
+
+        for subnet in subnets {
+            SubnetParams::<Runtime>::from_genesis(subnet)
+                .and_then(SubnetChangeset::<Runtime>::new)
+                .map_err(|err| {
+                    format!(
+                        "invalid parameters for subnet {}: {err:?}",
+                        String::from_utf8_lossy(&subnet.name)
+                    )
+                })?;
+        }
+
+        Ok(())
+    })
+}
+
+/// Random weights of module `uid` on up to 8 other modules of its subnet.
+fn random_weights(rng: &mut StdRng, uid: u16, modules: u16) -> Vec<(u16, u16)> {
+    let others: Vec<u16> = (0..modules).filter(|other| *other != uid).collect();
+    let count = rng.gen_range(0..=others.len().min(8));
+
+    let mut weights: Vec<_> = others
+        .choose_multiple(rng, count)
+        .map(|other| (*other, rng.gen_range(1..=u16::MAX)))
+        .collect();
+    weights.sort_unstable();
+    weights
+}
+
+fn build_spec(
+    wasm_binary: &[u8],
+    sudo_key: AccountId,
+    balances: Vec<(AccountId, u64)>,
+    subnets: Subnets,
+    block: u32,
+) -> ChainSpec {
+    // Give front-ends necessary data to present to users
+    let mut properties = sc_service::Properties::new();
+    properties.insert("tokenSymbol".into(), "C".into());
+    properties.insert("tokenDecimals".into(), 9.into());
+    properties.insert("ss58Format".into(), 13116.into());
+
+    let patch = genesis_patch(
+        &[
+            authority_keys_from_seed("Alice"),
+            authority_keys_from_seed("Bob"),
+        ],
+        sudo_key,
+        balances,
+        subnets,
+        block,
+    );
+
+    ChainSpec::builder(wasm_binary, None)
         .with_name("commune")
         .with_id("commune")
         .with_protocol_id("commune")
         .with_properties(properties)
         .with_chain_type(ChainType::Development)
         .with_genesis_config_patch(patch)
-        .build())
+        .build()
 }
 
 type SubnetData = ConfigSubnet<Vec<u8>, sp_runtime::AccountId32>;
@@ -181,3 +314,32 @@ fn genesis_patch(
         },
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use sp_runtime::BuildStorage;
+
+    #[derive(Parser)]
+    struct DevArgs {
+        #[command(flatten)]
+        network: DevNetworkParams,
+    }
+
+    fn dev_params(args: &[&str]) -> DevNetworkParams {
+        DevArgs::parse_from(std::iter::once("dev").chain(args.iter().copied())).network
+    }
+
+    #[test]
+    fn dev_chain_builds_from_default_params() {
+        let spec = dev_config(&dev_params(&[])).expect("default dev params are valid");
+        spec.build_storage().expect("dev genesis builds");
+    }
+
+    #[test]
+    fn dev_config_rejects_invalid_subnet_params() {
+        assert!(dev_config(&dev_params(&["--tempo", "10"])).is_err());
+        assert!(dev_config(&dev_params(&["--modules", "1000"])).is_err());
+    }
+}
@@ -1,5 +1,7 @@
 use std::path::PathBuf;
 
+use pallet_subspace::params::subnet::MIN_TEMPO;
+
 #[cfg(feature = "testnet")]
 use crate::service::EthConfiguration;
 
@@ -24,9 +26,9 @@ pub struct Cli {
     #[command(flatten)]
     pub run: sc_cli::RunCmd,
 
-    /// Defines the RSA key path.
-    #[arg(long, value_name = "PATH")]
-    pub rsa_path: Option<PathBuf>,
+    #[allow(missing_docs)]
+    #[command(flatten)]
+    pub decryption: DecryptionKeyParams,
 
     /// Choose sealing method.
     #[arg(long, value_enum, ignore_case = true)]
@@ -37,8 +39,88 @@ pub struct Cli {
     pub eth: EthConfiguration,
 }
 
+/// Where the offchain worker loads its weight decryption key from.
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct DecryptionKeyParams {
+    /// Defines the RSA key path.
+    #[arg(long, value_name = "PATH")]
+    pub rsa_path: Option<PathBuf>,
+
+    /// File holding the password of an encrypted PKCS#8 RSA key.
+    #[arg(long, value_name = "PATH")]
+    pub rsa_password_file: Option<PathBuf>,
+
+    /// Read the PEM encoded RSA key from an environment variable.
+    #[arg(long, value_name = "VAR", conflicts_with_all = ["rsa_path", "rsa_from_keystore"])]
+    pub rsa_key_env: Option<String>,
+
+    /// Read the RSA key from the node keystore, under the `wcs!` key type.
+    #[arg(long, conflicts_with = "rsa_path")]
+    pub rsa_from_keystore: bool,
+
+    /// Abort startup if no decryption key could be loaded.
+    #[arg(long)]
+    pub require_decryption_key: bool,
+}
+
+/// Shape of the chain generated by the `dev` subcommand.
+#[derive(Debug, Clone, clap::Args)]
+pub struct DevNetworkParams {
+    /// Number of subnets to create at genesis.
+    #[arg(long, default_value_t = 2)]
+    pub subnets: u16,
+
+    /// Number of random modules registered on each subnet.
+    #[arg(long, default_value_t = 8)]
+    pub modules: u16,
+
+    /// Tempo of the generated subnets.
+    #[arg(long, default_value_t = MIN_TEMPO)]
+    pub tempo: u16,
+
+    /// Seed for the random modules, stake and weights. Random if not set.
+    #[arg(long)]
+    pub seed: Option<u64>,
+}
+
+/// Runs a single node development chain generated in memory.
+#[derive(Debug, clap::Args)]
+pub struct DevCmd {
+    #[allow(missing_docs)]
+    #[command(flatten)]
+    pub run: sc_cli::RunCmd,
+
+    #[allow(missing_docs)]
+    #[command(flatten)]
+    pub network: DevNetworkParams,
+}
+
+/// Mines a faucet claim and submits it to a node.
+#[cfg(feature = "testnet-faucet")]
+#[derive(Debug, clap::Args)]
+pub struct FaucetCmd {
+    /// SS58 address of the account to fund.
+    #[arg(long)]
+    pub key: String,
+
+    /// HTTP RPC endpoint of the node to mine against and submit to.
+    #[arg(long, default_value = "http://127.0.0.1:9944")]
+    pub rpc: String,
+
+    /// Number of threads grinding nonces. Defaults to the available parallelism.
+    #[arg(long)]
+    pub threads: Option<usize>,
+}
+
 #[derive(Debug, clap::Subcommand)]
 pub enum Subcommand {
+    /// Run an instant sealing development chain with pre-populated subnets.
+    Dev(DevCmd),
+
+    /// Mine and submit a testnet faucet claim.
+    #[cfg(feature = "testnet-faucet")]
+    Faucet(FaucetCmd),
+
     /// Key management cli utilities
     #[command(subcommand)]
     Key(sc_cli::KeySubcommand),
@@ -11,7 +11,7 @@ use fc_db::kv::frontier_database_dir;
 
 use crate::{
     chain_spec,
-    cli::{Cli, Subcommand},
+    cli::{Cli, DecryptionKeyParams, Sealing, Subcommand},
     service::{self, Other},
 };
 
@@ -47,6 +47,10 @@ impl SubstrateCli for Cli {
     }
 
     fn load_spec(&self, id: &str) -> Result<Box<dyn sc_service::ChainSpec>, String> {
+        if let Some(Subcommand::Dev(cmd)) = &self.subcommand {
+            return Ok(Box::new(chain_spec::dev_config(&cmd.network)?));
+        }
+
         Ok(match id {
             "local" | "dev" => Box::new(chain_spec::generate_config("./specs/local.json")?),
             "test" => Box::new(chain_spec::ChainSpec::from_json_bytes(
@@ -78,8 +82,30 @@ pub fn run() -> sc_cli::Result<()> {
         "pallet_subnet_emission=info".to_string(),
     ]);
 
+    if let Some(Subcommand::Dev(cmd)) = &mut cli.subcommand {
+        // Implies `--alice`, `--tmp` and the other single node development defaults
+        cmd.run.shared_params.dev = true;
+    }
+
     match &cli.subcommand {
+        Some(Subcommand::Dev(cmd)) => {
+            let runner = cli.create_runner(&cmd.run)?;
+            runner.run_node_until_exit(|config| async move {
+                service::build_full(
+                    config,
+                    #[cfg(feature = "testnet")]
+                    cli.eth,
+                    Some(Sealing::Instant),
+                    // Dev subnets don't encrypt weights, no decryption key is needed
+                    DecryptionKeyParams::default(),
+                )
+                .map_err(Into::into)
+                .await
+            })
+        }
         Some(Subcommand::Key(cmd)) => cmd.run(&cli),
+        #[cfg(feature = "testnet-faucet")]
+        Some(Subcommand::Faucet(cmd)) => sc_cli::build_runtime()?.block_on(crate::faucet::run(cmd)),
         Some(Subcommand::BuildSpec(cmd)) => {
             let runner = cli.create_runner(cmd)?;
             runner.sync_run(|config| cmd.run(config.chain_spec, config.network))
@@ -326,7 +352,7 @@ pub fn run() -> sc_cli::Result<()> {
                     #[cfg(feature = "testnet")]
                     cli.eth,
                     cli.sealing,
-                    cli.rsa_path,
+                    cli.decryption,
                 )
                 .map_err(Into::into)
                 .await
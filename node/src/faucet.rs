@@ -0,0 +1,114 @@
+//! Proof of work miner for the testnet faucet. Claims are sealed with the hashing code of
+//! `pallet_faucet`, so mined seals always match what the runtime checks.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc,
+    },
+    thread,
+};
+
+use jsonrpsee::{core::client::ClientT, http_client::HttpClientBuilder, rpc_params};
+use node_subspace_runtime::{AccountId, Header, Runtime, RuntimeCall, UncheckedExtrinsic};
+use pallet_faucet::{pow, FaucetConfig, FaucetConfiguration};
+use scale_codec::{Decode, Encode};
+use sp_core::{crypto::Ss58Codec, Bytes, H256, U256};
+use sp_runtime::MultiAddress;
+
+use crate::cli::FaucetCmd;
+
+pub async fn run(cmd: &FaucetCmd) -> sc_cli::Result<()> {
+    let key = AccountId::from_ss58check(&cmd.key)
+        .map_err(|err| format!("invalid key {}: {err:?}", cmd.key))?;
+    let client = HttpClientBuilder::default()
+        .build(&cmd.rpc)
+        .map_err(|err| format!("failed to connect to {}: {err}", cmd.rpc))?;
+
+    let config = match client
+        .request::<Option<Bytes>, _>(
+            "state_getStorage",
+            rpc_params![Bytes(FaucetConfig::<Runtime>::hashed_key().to_vec())],
+        )
+        .await
+        .map_err(|err| format!("failed to fetch the faucet config: {err}"))?
+    {
+        Some(encoded) => FaucetConfiguration::decode(&mut &encoded[..])
+            .map_err(|err| format!("failed to decode the faucet config: {err}"))?,
+        None => FaucetConfiguration::default(),
+    };
+
+    // Seals are checked against a past block, so the best block works as long as the claim is
+    // included within the next couple of blocks.
+    let block_hash: H256 = client
+        .request("chain_getBlockHash", rpc_params![])
+        .await
+        .map_err(|err| format!("failed to fetch the best block hash: {err}"))?;
+    let header: Header = client
+        .request("chain_getHeader", rpc_params![block_hash])
+        .await
+        .map_err(|err| format!("failed to fetch the best block header: {err}"))?;
+
+    let threads = cmd
+        .threads
+        .unwrap_or_else(|| thread::available_parallelism().map_or(1, usize::from))
+        .max(1);
+    println!(
+        "mining faucet claim at block #{} with difficulty {} on {threads} threads",
+        header.number, config.difficulty
+    );
+
+    let (nonce, seal) = mine(&block_hash, &key, U256::from(config.difficulty), threads);
+    println!("found nonce {nonce} with seal {seal:?}");
+
+    let call = RuntimeCall::FaucetModule(pallet_faucet::Call::faucet {
+        block_number: header.number,
+        nonce,
+        work: seal.as_bytes().to_vec(),
+        key: MultiAddress::Id(key),
+    });
+    let extrinsic = UncheckedExtrinsic::new_unsigned(call);
+
+    let tx_hash: H256 = client
+        .request(
+            "author_submitExtrinsic",
+            rpc_params![Bytes(extrinsic.encode())],
+        )
+        .await
+        .map_err(|err| format!("failed to submit the faucet claim: {err}"))?;
+    println!("submitted faucet claim {tx_hash:?}");
+
+    Ok(())
+}
+
+/// Grinds nonces on every thread, each one stepping over the nonces of the others, until one
+/// seals the block and key with enough work.
+fn mine(block_hash: &H256, key: &AccountId, difficulty: U256, threads: usize) -> (u64, H256) {
+    let block_and_key_hash = pow::hash_block_and_key(block_hash.as_fixed_bytes(), key.as_ref());
+    let found = AtomicBool::new(false);
+    let (sender, receiver) = mpsc::channel();
+    let step = threads as u64;
+
+    thread::scope(|scope| {
+        for start in 0..step {
+            let found = &found;
+            let sender = sender.clone();
+            let block_and_key_hash = &block_and_key_hash;
+
+            scope.spawn(move || {
+                let mut nonce = start;
+                while !found.load(Ordering::Relaxed) {
+                    let seal = pow::seal_hash(nonce, block_and_key_hash);
+                    if pow::hash_meets_difficulty(&seal, difficulty) {
+                        found.store(true, Ordering::Relaxed);
+                        let _ = sender.send((nonce, seal));
+                        return;
+                    }
+                    nonce = nonce.wrapping_add(step);
+                }
+            });
+        }
+    });
+
+    receiver.recv().expect("the scope only ends once a seal was found")
+}
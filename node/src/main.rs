@@ -16,6 +16,8 @@ mod client;
 mod command;
 #[cfg(feature = "testnet")]
 mod eth;
+#[cfg(feature = "testnet-faucet")]
+mod faucet;
 mod rpc;
 mod service;
 
@@ -13,10 +13,10 @@ use sc_telemetry::{Telemetry, TelemetryHandle, TelemetryWorker};
 use sc_transaction_pool_api::OffchainTransactionPoolFactory;
 use sp_consensus_aura::sr25519::AuthorityPair as AuraPair;
 use sp_runtime::traits::Block as BlockT;
-use std::{path::PathBuf, sync::Arc, time::Duration};
+use std::{sync::Arc, time::Duration};
 
 use crate::{
-    cli::Sealing,
+    cli::{DecryptionKeyParams, Sealing},
     client::{Client, FullBackend},
 };
 #[cfg(feature = "testnet")]
@@ -343,7 +343,7 @@ pub async fn new_full<N>(
     #[cfg(feature = "testnet")] eth_config: EthConfiguration,
 
     sealing: Option<Sealing>,
-    rsa_key: Option<PathBuf>,
+    decryption: DecryptionKeyParams,
 ) -> Result<TaskManager, ServiceError>
 where
     N: sc_network::NetworkBackend<Block, <Block as BlockT>::Hash>,
@@ -425,6 +425,13 @@ where
             metrics,
         })?;
 
+    let keystore_path = match &other.config.keystore {
+        sc_service::config::KeystoreConfig::Path { path, .. } => Some(path.as_path()),
+        sc_service::config::KeystoreConfig::InMemory => None,
+    };
+    let decrypter =
+        decrypter::Decrypter::new(&decryption, keystore_path).map_err(ServiceError::Other)?;
+
     if other.config.offchain_worker.enabled {
         task_manager.spawn_handle().spawn(
             "offchain-workers-runner",
@@ -440,11 +447,7 @@ where
                 network_provider: Arc::new(network.clone()),
                 enable_http_requests: true,
                 custom_extensions: move |_| {
-                    vec![
-                        Box::new(ow_extensions::OffworkerExt::new(decrypter::Decrypter::new(
-                            rsa_key.clone(),
-                        ))) as Box<_>,
-                    ]
+                    vec![Box::new(ow_extensions::OffworkerExt::new(decrypter.clone())) as Box<_>]
                 },
             })
             .run(client.clone(), task_manager.spawn_handle())
@@ -773,14 +776,14 @@ pub async fn build_full(
     #[cfg(feature = "testnet")] eth_config: EthConfiguration,
 
     sealing: Option<Sealing>,
-    rsa_key: Option<PathBuf>,
+    decryption: DecryptionKeyParams,
 ) -> Result<TaskManager, ServiceError> {
     new_full::<sc_network::NetworkWorker<_, _>>(
         config,
         #[cfg(feature = "testnet")]
         eth_config,
         sealing,
-        rsa_key,
+        decryption,
     )
     .await
 }
@@ -0,0 +1,124 @@
+//! Mines a faucet claim with the `faucet` subcommand against a manual-seal development node.
+#![cfg(feature = "testnet-faucet")]
+
+use std::{
+    net::TcpListener,
+    process::{Child, Command, Stdio},
+    thread,
+    time::Duration,
+};
+
+use jsonrpsee::{
+    core::client::ClientT,
+    http_client::{HttpClient, HttpClientBuilder},
+    rpc_params,
+};
+use node_subspace_runtime::{AccountId, Runtime};
+use pallet_faucet::FaucetConfiguration;
+use scale_codec::Decode;
+use sp_core::{crypto::Ss58Codec, sr25519, Bytes, Pair, H256};
+
+const NODE: &str = env!("CARGO_BIN_EXE_node-subspace");
+const CHAIN_SPEC: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../specs/local.json");
+
+/// Stops the node when the test ends, even if it fails.
+struct NodeProcess(Child);
+
+impl Drop for NodeProcess {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+/// Asks the OS for RPC and p2p ports nothing is listening on, so parallel runs don't collide.
+/// Both listeners are held until the ports are read, so the two ports differ.
+fn free_ports() -> (u16, u16) {
+    let bind = || TcpListener::bind("127.0.0.1:0").expect("failed to find a free port");
+    let port = |listener: &TcpListener| listener.local_addr().expect("unbound listener").port();
+
+    let (rpc, p2p) = (bind(), bind());
+    (port(&rpc), port(&p2p))
+}
+
+async fn wait_until_ready(client: &HttpClient) {
+    for _ in 0..120 {
+        let health = client.request::<serde_json::Value, _>("system_health", rpc_params![]);
+        if health.await.is_ok() {
+            return;
+        }
+        thread::sleep(Duration::from_millis(500));
+    }
+
+    panic!("node didn't start in time");
+}
+
+async fn seal_block(client: &HttpClient) {
+    client
+        .request::<serde_json::Value, _>(
+            "engine_createBlock",
+            rpc_params![true, true, Option::<H256>::None],
+        )
+        .await
+        .expect("failed to seal a block");
+}
+
+async fn free_balance(client: &HttpClient, account: &AccountId) -> u64 {
+    let key = frame_system::Account::<Runtime>::hashed_key_for(account);
+    let encoded: Option<Bytes> = client
+        .request("state_getStorage", rpc_params![Bytes(key)])
+        .await
+        .expect("failed to fetch the account");
+
+    // `AccountInfo` starts with the nonce and the three reference counters, followed by the
+    // free balance.
+    encoded
+        .map(|encoded| {
+            let (_, _, _, _, free) =
+                <(u32, u32, u32, u32, u64)>::decode(&mut &encoded[..]).expect("invalid account");
+            free
+        })
+        .unwrap_or_default()
+}
+
+#[test]
+fn faucet_subcommand_funds_the_key() {
+    let (rpc_port, p2p_port) = free_ports();
+    let rpc_url = format!("http://127.0.0.1:{rpc_port}");
+    let _node = NodeProcess(
+        Command::new(NODE)
+            .args(["--dev", "--chain", CHAIN_SPEC, "--sealing", "manual"])
+            .args(["--rpc-port", &rpc_port.to_string()])
+            .args(["--port", &p2p_port.to_string()])
+            .arg("--no-prometheus")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("failed to start the node"),
+    );
+
+    let key = AccountId::from(
+        sr25519::Pair::from_string("//Charlie", None).expect("valid seed").public(),
+    );
+
+    sc_cli::build_runtime().expect("failed to build the runtime").block_on(async {
+        let client = HttpClientBuilder::default().build(&rpc_url).unwrap();
+        wait_until_ready(&client).await;
+        seal_block(&client).await;
+
+        assert_eq!(free_balance(&client, &key).await, 0);
+
+        let status = Command::new(NODE)
+            .args(["faucet", "--key", &key.to_ss58check(), "--rpc", &rpc_url])
+            .status()
+            .expect("failed to run the faucet subcommand");
+        assert!(status.success());
+
+        seal_block(&client).await;
+
+        assert_eq!(
+            free_balance(&client, &key).await,
+            FaucetConfiguration::default().payout
+        );
+    });
+}
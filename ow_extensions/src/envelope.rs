@@ -0,0 +1,118 @@
+//! Decryption of the weight envelopes submitted by validators:
+//!
+//! - legacy: the payload split into PKCS#1 v1.5 encrypted RSA blocks, with no prefix
+//! - [`HYBRID_ENVELOPE_VERSION`]: `version || RSA-OAEP(aes_key) || nonce || AES-GCM(payload)`
+//!
+//! Threshold envelopes are opened share by share through the runtime.
+
+use std::io::{Cursor, Read};
+
+use aes_gcm::{aead::Aead, Aes256Gcm, KeyInit, Nonce};
+use rsa::{traits::PublicKeyParts, Oaep, Pkcs1v15Encrypt, RsaPrivateKey};
+
+use crate::DecryptedWeights;
+
+/// Leading byte of the hybrid RSA-OAEP + AES-GCM envelope.
+pub const HYBRID_ENVELOPE_VERSION: u8 = 1;
+
+/// Size of the AES-GCM nonce in the hybrid envelope.
+pub const AES_NONCE_SIZE: usize = 12;
+
+/// Size of the AES-GCM authentication tag closing the hybrid envelope.
+pub const AES_TAG_SIZE: usize = 16;
+
+/// Decrypts a weight envelope and decodes its payload,
+/// `u32 length || (u16 uid || u16 weight)* || validator key`.
+pub fn decrypt_weights(key: &RsaPrivateKey, encrypted: &[u8]) -> Result<DecryptedWeights, String> {
+    let decrypted = decrypt_envelope(key, encrypted)?;
+
+    let mut cursor = Cursor::new(&decrypted);
+    let length = read_u32(&mut cursor).ok_or("payload is missing the weight count")?;
+
+    let mut weights = Vec::with_capacity(length.min(u16::MAX as u32) as usize);
+    for _ in 0..length {
+        let uid = read_u16(&mut cursor).ok_or("payload is missing a weight uid")?;
+        let weight = read_u16(&mut cursor).ok_or("payload is missing a weight value")?;
+
+        weights.push((uid, weight));
+    }
+
+    let mut validator_key = Vec::new();
+    cursor
+        .read_to_end(&mut validator_key)
+        .map_err(|err| format!("failed to read the validator key: {err}"))?;
+
+    Ok((weights, validator_key))
+}
+
+/// Decrypts the payload of a weight envelope. Envelopes that don't have the hybrid
+/// layout are the legacy format, which has no version byte.
+pub fn decrypt_envelope(key: &RsaPrivateKey, encrypted: &[u8]) -> Result<Vec<u8>, String> {
+    let (&version, rest) = encrypted.split_first().ok_or("envelope is empty")?;
+
+    let hybrid_size = key
+        .size()
+        .saturating_add(1)
+        .saturating_add(AES_NONCE_SIZE)
+        .saturating_add(AES_TAG_SIZE);
+    if version != HYBRID_ENVELOPE_VERSION || encrypted.len() < hybrid_size {
+        return decrypt_legacy(key, encrypted);
+    }
+
+    // A legacy ciphertext may start with the version byte as well. When it also splits into
+    // whole RSA blocks, the AES-GCM tag tells the two formats apart.
+    match decrypt_hybrid(key, rest) {
+        Err(_) if encrypted.len().checked_rem(key.size()) == Some(0) => {
+            decrypt_legacy(key, encrypted)
+        }
+        result => result,
+    }
+}
+
+/// Opens a hybrid envelope. The AES-256 key is unwrapped with RSA-OAEP
+/// (SHA-256) and authenticates the payload.
+fn decrypt_hybrid(key: &RsaPrivateKey, envelope: &[u8]) -> Result<Vec<u8>, String> {
+    let nonce_start = key.size();
+    let nonce_end = nonce_start.checked_add(AES_NONCE_SIZE).ok_or("envelope is too large")?;
+
+    let wrapped_key = envelope.get(..nonce_start).ok_or("envelope is missing the wrapped key")?;
+    let nonce = envelope.get(nonce_start..nonce_end).ok_or("envelope is missing the nonce")?;
+    let ciphertext = envelope.get(nonce_end..).ok_or("envelope is missing the ciphertext")?;
+
+    let aes_key = key
+        .decrypt(Oaep::new::<sha2::Sha256>(), wrapped_key)
+        .map_err(|err| format!("failed to unwrap the AES key: {err}"))?;
+    let cipher =
+        Aes256Gcm::new_from_slice(&aes_key).map_err(|err| format!("invalid AES key: {err}"))?;
+
+    cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|err| format!("failed to decrypt the payload: {err}"))
+}
+
+/// Decrypts the legacy format, made of RSA-block-sized PKCS#1 v1.5 chunks.
+fn decrypt_legacy(key: &RsaPrivateKey, blocks: &[u8]) -> Result<Vec<u8>, String> {
+    let vec = blocks
+        .chunks(key.size())
+        .map(|chunk| key.decrypt(Pkcs1v15Encrypt, chunk))
+        .collect::<Result<Vec<Vec<u8>>, _>>()
+        .map_err(|err| format!("failed to decrypt a legacy block: {err}"))?;
+
+    Ok(vec.into_iter().flatten().collect())
+}
+
+fn read_u32(cursor: &mut Cursor<&Vec<u8>>) -> Option<u32> {
+    let mut buf: [u8; 4] = [0u8; 4];
+    match cursor.read_exact(&mut buf[..]) {
+        Ok(()) => Some(u32::from_be_bytes(buf)),
+        Err(_) => None,
+    }
+}
+
+fn read_u16(cursor: &mut Cursor<&Vec<u8>>) -> Option<u16> {
+    let mut buf = [0u8; 2];
+    match cursor.read_exact(&mut buf[..]) {
+        Ok(()) => Some(u16::from_be_bytes(buf)),
+        Err(_) => None,
+    }
+}
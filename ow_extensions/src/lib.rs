@@ -1,5 +1,8 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(feature = "std")]
+pub mod envelope;
+
 #[cfg(feature = "std")]
 use sp_externalities::ExternalitiesExt;
 
@@ -36,6 +39,9 @@ pub trait OffworkerExtension: Send + 'static {
     fn is_decryption_node(&self) -> bool;
 
     fn get_encryption_key(&self) -> Option<EncryptionKey>;
+
+    /// Decrypts the share at `index` of a threshold envelope, returning the raw Shamir share.
+    fn decrypt_share(&self, encrypted: Vec<u8>, index: u8) -> Option<Vec<u8>>;
 }
 
 #[sp_runtime_interface::runtime_interface]
@@ -57,4 +63,14 @@ pub trait Offworker {
             .expect("missing offworker ext")
             .get_encryption_key()
     }
+
+    fn decrypt_share(
+        &mut self,
+        encrypted: sp_std::vec::Vec<u8>,
+        index: u8,
+    ) -> Option<sp_std::vec::Vec<u8>> {
+        self.extension::<OffworkerExt>()
+            .expect("missing offworker ext")
+            .decrypt_share(encrypted, index)
+    }
 }
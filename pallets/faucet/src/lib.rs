@@ -3,11 +3,38 @@
 use frame_support::{dispatch::DispatchResult, ensure, LOG_TARGET};
 use frame_system::{self as system, ensure_none, pallet_prelude::BlockNumberFor};
 use pallet_subspace::Pallet as PalletSubspace;
-use sp_core::{keccak_256, sha2_256, Get, H256, U256};
+use sp_core::{Get, H256, U256};
 use sp_runtime::{traits::StaticLookup, DispatchError, MultiAddress};
 
+pub mod pow;
+
 pub use pallet::*;
-use parity_scale_codec::Encode;
+use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+
+/// The faucet parameters testnet operators can tune without a runtime upgrade.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo, MaxEncodedLen)]
+pub struct FaucetConfiguration {
+    /// The proof of work difficulty the seal has to meet.
+    pub difficulty: u64,
+    /// How much a claim credits to the account.
+    pub payout: u64,
+    /// Accounts worth this much or more, counting their balance and stake, can't claim.
+    pub wealth_cap: u64,
+    /// How many blocks an account has to wait between claims.
+    pub cooldown: u64,
+}
+
+impl Default for FaucetConfiguration {
+    fn default() -> Self {
+        Self {
+            difficulty: 1_000_000,
+            payout: 15_000_000_000,         // 15 tokens
+            wealth_cap: 50_000_000_000_000, // 50k tokens
+            cooldown: 10_800,               // ~1 day
+        }
+    }
+}
 
 type AccountIdLookupOf<T> = <<T as frame_system::Config>::Lookup as StaticLookup>::Source;
 
@@ -18,7 +45,6 @@ pub mod pallet {
     use super::*;
     use frame_support::{pallet_prelude::*, traits::Currency};
     use frame_system::pallet_prelude::*;
-    use pallet_subspace::N;
     pub use sp_std::{vec, vec::Vec};
 
     #[cfg(feature = "testnet")]
@@ -65,14 +91,12 @@ pub mod pallet {
 
             let key = T::Lookup::lookup(key.clone())?;
 
-            let key_balance = PalletSubspace::<T>::get_balance_u64(&key);
-            let key_stake: u64 = N::<T>::iter()
-                .map(|_| pallet_subspace::Pallet::<T>::get_owned_stake(&key))
-                .sum();
-            let total_worth = key_balance.saturating_add(key_stake);
-            if total_worth >= 50_000_000_000_000 {
-                // if it's larger than 50k don't allow more funds
-                return InvalidTransaction::Custom(0).into();
+            let current_block_number = PalletSubspace::<T>::get_current_block_number();
+            match Self::check_claim(&key, current_block_number, &FaucetConfig::<T>::get()) {
+                Ok(()) => {}
+                Err(Error::<T>::TooWealthy) => return InvalidTransaction::Custom(0).into(),
+                Err(Error::<T>::ClaimOnCooldown) => return InvalidTransaction::Custom(1).into(),
+                Err(_) => return InvalidTransaction::Call.into(),
             }
 
             ValidTransaction::with_tag_prefix("RunFaucet")
@@ -87,6 +111,17 @@ pub mod pallet {
     pub type BalanceOf<T> =
         <<T as Config>::Currency as Currency<<T as system::Config>::AccountId>>::Balance;
 
+    // ---------------------------------
+    // Storage
+    // ---------------------------------
+
+    #[pallet::storage]
+    pub type FaucetConfig<T: Config> = StorageValue<_, FaucetConfiguration, ValueQuery>;
+
+    /// The block each account last claimed from the faucet at.
+    #[pallet::storage]
+    pub type LastFaucetClaim<T: Config> = StorageMap<_, Identity, T::AccountId, u64>;
+
     #[pallet::call]
     impl<T: Config> Pallet<T> {
         // ---------------------------------
@@ -110,6 +145,30 @@ pub mod pallet {
         ) -> DispatchResult {
             Self::do_faucet(origin, block_number, nonce, work, key)
         }
+
+        /// Replaces the faucet configuration. Only callable by root, either through sudo or a
+        /// governance dispatch.
+        #[pallet::call_index(2)]
+        #[pallet::weight((
+            T::DbWeight::get().writes(1),
+            DispatchClass::Operational,
+            Pays::No
+        ))]
+        pub fn set_faucet_config(
+            origin: OriginFor<T>,
+            config: FaucetConfiguration,
+        ) -> DispatchResult {
+            ensure_root(origin)?;
+
+            ensure!(
+                config.difficulty > 0 && config.payout > 0,
+                Error::<T>::InvalidFaucetConfig
+            );
+
+            FaucetConfig::<T>::set(config.clone());
+            Self::deposit_event(Event::FaucetConfigUpdated(config));
+            Ok(())
+        }
     }
 
     // ---------------------------------
@@ -121,6 +180,8 @@ pub mod pallet {
     pub enum Event<T: Config> {
         // faucet
         Faucet(T::AccountId, u64), // (id, balance_to_add)
+        /// The faucet configuration was replaced.
+        FaucetConfigUpdated(FaucetConfiguration),
     }
 
     // ---------------------------------
@@ -135,6 +196,12 @@ pub mod pallet {
         InvalidDifficulty,
         /// The seal provided is invalid or does not match the expected value.
         InvalidSeal,
+        /// The account's balance and stake already reach the faucet's wealth cap.
+        TooWealthy,
+        /// The account claimed from the faucet too recently.
+        ClaimOnCooldown,
+        /// The faucet configuration has no difficulty or payout.
+        InvalidFaucetConfig,
     }
 }
 
@@ -176,29 +243,56 @@ impl<T: Config> Pallet<T> {
             Error::<T>::InvalidWorkBlock
         );
 
-        // --- 3. Ensure the supplied work passes the difficulty.
-        let difficulty: U256 = U256::from(1_000_000); // Base faucet difficulty.
+        // --- 3. Ensure the account is allowed to claim.
+        let config = FaucetConfig::<T>::get();
+        Self::check_claim(&key, current_block_number, &config)?;
+
+        // --- 4. Ensure the supplied work passes the difficulty.
+        let difficulty: U256 = U256::from(config.difficulty);
         let work_hash: H256 = H256::from_slice(&work);
         ensure!(
             Self::hash_meets_difficulty(&work_hash, difficulty),
             Error::<T>::InvalidDifficulty
         ); // Check that the work meets difficulty.
 
-        // --- 4. Check Work is the product of the nonce, the block number, and hotkey. Add this as
+        // --- 5. Check Work is the product of the nonce, the block number, and hotkey. Add this as
         // used work.
         let seal: H256 = Self::create_seal_hash(block_number, nonce, &key)?;
         ensure!(seal == work_hash, Error::<T>::InvalidSeal);
 
-        // --- 5. Add Balance via faucet. 15 tokens
-        let amount: u64 = 15_000_000_000;
+        // --- 6. Add Balance via faucet.
+        let amount: u64 = config.payout;
         let balance_to_add = PalletSubspace::<T>::u64_to_balance(amount).unwrap();
         PalletSubspace::<T>::add_balance_to_account(&key, balance_to_add);
+        LastFaucetClaim::<T>::insert(&key, current_block_number);
 
-        // --- 6. Deposit successful event.
+        // --- 7. Deposit successful event.
         log::info!("faucet done successfully with key: {key:?} and amount: {balance_to_add:?})");
         Self::deposit_event(Event::Faucet(key, amount));
 
-        // --- 7. Ok and done.
+        // --- 8. Ok and done.
+        Ok(())
+    }
+
+    /// Checks the account is below the wealth cap and out of its claim cooldown.
+    pub fn check_claim(
+        key: &T::AccountId,
+        current_block: u64,
+        config: &FaucetConfiguration,
+    ) -> Result<(), Error<T>> {
+        // Stake is tracked across subnets already, so it's only counted once.
+        let total_worth = PalletSubspace::<T>::get_balance_u64(key)
+            .saturating_add(PalletSubspace::<T>::get_owned_stake(key))
+            .saturating_add(PalletSubspace::<T>::get_pooled_stake(key));
+        ensure!(total_worth < config.wealth_cap, Error::<T>::TooWealthy);
+
+        if let Some(last_claim) = LastFaucetClaim::<T>::get(key) {
+            ensure!(
+                current_block >= last_claim.saturating_add(config.cooldown),
+                Error::<T>::ClaimOnCooldown
+            );
+        }
+
         Ok(())
     }
 
@@ -211,16 +305,13 @@ impl<T: Config> Pallet<T> {
         let binding = key_pubkey.encode();
         // Skip extra 0th byte.
         let key_bytes = binding.get(1..).ok_or(pallet_subspace::Error::<T>::ExtrinsicPanicked)?;
-        let mut full_bytes = [0u8; 64];
-        let (first_half, second_half) = full_bytes.split_at_mut(32);
-        first_half.copy_from_slice(block_hash_bytes);
         // Safe because Substrate guarantees that all AccountId types are at least 32 bytes
-        second_half.copy_from_slice(
-            key_bytes.get(..32).ok_or(pallet_subspace::Error::<T>::ExtrinsicPanicked)?,
-        );
-        let keccak_256_seal_hash_vec: [u8; 32] = keccak_256(&full_bytes[..]);
+        let key_bytes: &[u8; 32] = key_bytes
+            .get(..32)
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or(pallet_subspace::Error::<T>::ExtrinsicPanicked)?;
 
-        Ok(H256::from_slice(&keccak_256_seal_hash_vec))
+        Ok(pow::hash_block_and_key(block_hash_bytes, key_bytes))
     }
 
     pub fn create_seal_hash(
@@ -228,22 +319,13 @@ impl<T: Config> Pallet<T> {
         nonce_u64: u64,
         hotkey: &T::AccountId,
     ) -> Result<H256, DispatchError> {
-        let nonce = nonce_u64.to_le_bytes();
         let block_hash_at_number: H256 = Self::get_block_hash_from_u64(block_number_u64);
-        let block_hash_bytes: &[u8; 32] = block_hash_at_number.as_fixed_bytes();
-        let binding = Self::hash_block_and_key(block_hash_bytes, hotkey)?;
-        let block_and_hotkey_hash_bytes: &[u8; 32] = binding.as_fixed_bytes();
-
-        let mut full_bytes = [0u8; 40];
-        let (first_chunk, second_chunk) = full_bytes.split_at_mut(8);
-        first_chunk.copy_from_slice(&nonce);
-        second_chunk.copy_from_slice(block_and_hotkey_hash_bytes);
-        let sha256_seal_hash_vec: [u8; 32] = sha2_256(&full_bytes[..]);
-        let keccak_256_seal_hash_vec: [u8; 32] = keccak_256(&sha256_seal_hash_vec);
-        let seal_hash: H256 = H256::from_slice(&keccak_256_seal_hash_vec);
+        let block_and_hotkey_hash =
+            Self::hash_block_and_key(block_hash_at_number.as_fixed_bytes(), hotkey)?;
+        let seal_hash: H256 = pow::seal_hash(nonce_u64, &block_and_hotkey_hash);
 
         log::trace!(
-            "hotkey:{hotkey:?} \nblock_number: {block_number_u64:?}, \nnonce_u64: {nonce_u64:?}, \nblock_hash: {block_hash_at_number:?}, \nfull_bytes: {full_bytes:?}, \nsha256_seal_hash_vec: {sha256_seal_hash_vec:?},  \nkeccak_256_seal_hash_vec: {keccak_256_seal_hash_vec:?}, \nseal_hash: {seal_hash:?}",
+            "hotkey:{hotkey:?} \nblock_number: {block_number_u64:?}, \nnonce_u64: {nonce_u64:?}, \nblock_hash: {block_hash_at_number:?}, \nblock_and_hotkey_hash: {block_and_hotkey_hash:?}, \nseal_hash: {seal_hash:?}",
         );
 
         Ok(seal_hash)
@@ -265,19 +347,14 @@ impl<T: Config> Pallet<T> {
         real_hash
     }
 
-    // Determine whether the given hash satisfies the given difficulty.
-    // The test is done by multiplying the two together. If the product
-    // overflows the bounds of U256, then the product (and thus the hash)
-    // was too high.
+    /// Whether the hash satisfies the difficulty, see [`pow::hash_meets_difficulty`].
     pub fn hash_meets_difficulty(hash: &H256, difficulty: U256) -> bool {
-        let bytes: &[u8] = hash.as_bytes();
-        let num_hash: U256 = U256::from(bytes);
-        let (value, overflowed) = num_hash.overflowing_mul(difficulty);
+        let meets_difficulty = pow::hash_meets_difficulty(hash, difficulty);
 
         log::trace!(
             target: LOG_TARGET,
-            "Difficulty: hash: {hash:?}, hash_bytes: {bytes:?}, hash_as_num: {num_hash:?}, difficulty: {difficulty:?}, value: {value:?} overflowed: {overflowed:?}",
+            "Difficulty: hash: {hash:?}, difficulty: {difficulty:?}, meets: {meets_difficulty:?}",
         );
-        !overflowed
+        meets_difficulty
     }
 }
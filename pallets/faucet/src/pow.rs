@@ -0,0 +1,35 @@
+//! The proof of work behind faucet claims. Off-chain miners use these functions too, so they
+//! hash the exact same bytes the pallet checks.
+
+use sp_core::{keccak_256, sha2_256, H256, U256};
+
+/// Hashes the block hash together with the raw bytes of the claiming key.
+pub fn hash_block_and_key(block_hash: &[u8; 32], key: &[u8; 32]) -> H256 {
+    let mut full_bytes = [0u8; 64];
+    let (first_half, second_half) = full_bytes.split_at_mut(32);
+    first_half.copy_from_slice(block_hash);
+    second_half.copy_from_slice(key);
+
+    H256(keccak_256(&full_bytes))
+}
+
+/// The seal of a nonce over the block and key hash: `keccak256(sha256(nonce ++ hash))`, with the
+/// nonce in little endian.
+pub fn seal_hash(nonce: u64, block_and_key_hash: &H256) -> H256 {
+    let mut full_bytes = [0u8; 40];
+    let (first_chunk, second_chunk) = full_bytes.split_at_mut(8);
+    first_chunk.copy_from_slice(&nonce.to_le_bytes());
+    second_chunk.copy_from_slice(block_and_key_hash.as_bytes());
+
+    H256(keccak_256(&sha2_256(&full_bytes)))
+}
+
+// Determine whether the given hash satisfies the given difficulty.
+// The test is done by multiplying the two together. If the product
+// overflows the bounds of U256, then the product (and thus the hash)
+// was too high.
+pub fn hash_meets_difficulty(hash: &H256, difficulty: U256) -> bool {
+    let num_hash = U256::from(hash.as_bytes());
+    let (_, overflowed) = num_hash.overflowing_mul(difficulty);
+    !overflowed
+}
@@ -11,6 +11,54 @@ pub enum VoteMode {
     Vote = 1,
 }
 
+/// What a proposal needs to pass. `quorum` is the share of the total stake that has to vote on
+/// the proposal, and `approval` the share of the voting stake that has to be in favor of it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, TypeInfo, Decode, Encode, MaxEncodedLen)]
+pub struct ProposalThresholds {
+    pub quorum: Percent,
+    pub approval: Percent,
+}
+
+impl ProposalThresholds {
+    pub const fn new(quorum: u8, approval: u8) -> Self {
+        Self {
+            quorum: Percent::from_parts(quorum),
+            approval: Percent::from_parts(approval),
+        }
+    }
+}
+
+/// Where the forfeited part of proposal deposits goes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, TypeInfo, Decode, Encode, MaxEncodedLen)]
+pub enum ForfeitDestination {
+    Treasury,
+    Burn,
+}
+
+/// How much of the proposal cost is given back to the proposer depending on how the proposal
+/// ended, and how many proposals a single account can have open at once.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, TypeInfo, Decode, Encode, MaxEncodedLen)]
+pub struct DepositPolicy {
+    pub accepted_refund: Percent,
+    pub refused_refund: Percent,
+    /// Refund of proposals that expired without reaching the quorum.
+    pub expired_refund: Percent,
+    pub forfeit_destination: ForfeitDestination,
+    pub max_open_proposals_per_account: u32,
+}
+
+impl Default for DepositPolicy {
+    fn default() -> Self {
+        Self {
+            accepted_refund: Percent::from_percent(100),
+            refused_refund: Percent::from_percent(0),
+            expired_refund: Percent::from_percent(0),
+            forfeit_destination: ForfeitDestination::Burn,
+            max_open_proposals_per_account: 10,
+        }
+    }
+}
+
 #[derive(Clone, TypeInfo, Decode, Encode, PartialEq, Eq, DebugNoBound, MaxEncodedLen)]
 pub struct GovernanceConfiguration {
     pub proposal_cost: u64,
@@ -19,6 +67,16 @@ pub struct GovernanceConfiguration {
     pub proposal_reward_treasury_allocation: Percent,
     pub max_proposal_reward_treasury_allocation: u64,
     pub proposal_reward_interval: u64,
+    /// Thresholds of custom proposals.
+    pub custom_thresholds: ProposalThresholds,
+    /// Thresholds of global and subnet params proposals.
+    pub params_thresholds: ProposalThresholds,
+    /// Thresholds of DAO treasury transfers.
+    pub treasury_thresholds: ProposalThresholds,
+    /// Thresholds of subnet consensus type changes.
+    pub consensus_thresholds: ProposalThresholds,
+    /// Refunds of the proposal cost and the open proposal cap.
+    pub deposit_policy: DepositPolicy,
 }
 
 impl Default for GovernanceConfiguration {
@@ -32,6 +90,11 @@ impl Default for GovernanceConfiguration {
             // Maximum reward allocation 10K.
             max_proposal_reward_treasury_allocation: 10_000_000_000_000,
             proposal_reward_interval: 75_600,
+            custom_thresholds: ProposalThresholds::new(50, 50),
+            params_thresholds: ProposalThresholds::new(40, 50),
+            treasury_thresholds: ProposalThresholds::new(50, 50),
+            consensus_thresholds: ProposalThresholds::new(40, 50),
+            deposit_policy: DepositPolicy::default(),
         }
     }
 }
@@ -76,10 +139,9 @@ pub trait GovernanceApi<AccountId> {
 
     fn whitelisted_keys() -> BTreeSet<AccountId>;
 
+    /// The account standing for the curator council.
     fn get_curator() -> AccountId;
 
-    fn set_curator(key: &AccountId);
-
     fn set_general_subnet_application_cost(amount: u64);
 
     fn clear_subnet_includes(netuid: u16);
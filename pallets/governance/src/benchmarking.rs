@@ -92,7 +92,10 @@ benchmarks! {
             params.general_subnet_application_cost,     // general_subnet_application_cost
             params.kappa,
             params.rho,
-            params.subnet_immunity_period
+            params.subnet_immunity_period,
+            params.ceiling_stake_delegation_fee,
+            params.ceiling_validator_weight_fee,
+            params.fee_announcement_period
         )
 
 
@@ -131,7 +134,9 @@ benchmarks! {
             params.max_allowed_validators,
             params.use_weights_encryption,
             params.copier_margin,
-            params.max_encryption_period
+            params.max_encryption_period,
+            params.pruning_policy,
+            params.registration_mode
         )?;
 
         // add balance to submit the proposal
@@ -164,7 +169,9 @@ benchmarks! {
         params.max_allowed_validators,
         params.use_weights_encryption,
         params.copier_margin,
-        params.max_encryption_period
+        params.max_encryption_period,
+        params.pruning_policy,
+        params.registration_mode
     )
 
     // 2
@@ -271,7 +278,7 @@ benchmarks! {
         // First add the application
         submit_dao_application::<T>()?;
         let caller: T::AccountId = account("Alice", 0, 1);
-        Curator::<T>::set(caller.clone());
+        GovernanceMod::<T>::set_curator_council(sp_std::vec![caller.clone()], 1)?;
     }: refuse_dao_application(RawOrigin::Signed(caller), 0)
 
     // 11
@@ -280,7 +287,7 @@ benchmarks! {
         submit_dao_application::<T>()?;
         let caller: T::AccountId = account("Alice", 0, 1);
         let application_key: T::AccountId = account("Bob", 0, 2);
-        Curator::<T>::set(caller.clone());
+        GovernanceMod::<T>::set_curator_council(sp_std::vec![caller.clone()], 1)?;
     }: add_to_whitelist(RawOrigin::Signed(caller), application_key)
 
     // 12
@@ -289,7 +296,7 @@ benchmarks! {
         submit_dao_application::<T>()?;
         let caller: T::AccountId = account("Alice", 0, 1);
         let application_key: T::AccountId = account("Bob", 0, 2);
-        Curator::<T>::set(caller.clone());
+        GovernanceMod::<T>::set_curator_council(sp_std::vec![caller.clone()], 1)?;
         // Now add it to whitelist
         GovernanceMod::<T>::add_to_whitelist(RawOrigin::Signed(caller.clone()).into(),
     application_key.clone())?; }: remove_from_whitelist(RawOrigin::Signed(caller),
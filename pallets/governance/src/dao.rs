@@ -1,9 +1,34 @@
 use crate::*;
-use frame_support::pallet_prelude::{BoundedVec, ConstU32, DispatchResult};
+use frame_support::{
+    pallet_prelude::{BoundedVec, ConstU32, DispatchResult, Get, Weight},
+    BoundedBTreeSet,
+};
 use frame_system::ensure_signed;
 use pallet_subspace::Pallet as PalletSubspace;
 use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
 use scale_info::TypeInfo;
+use sp_runtime::traits::AccountIdConversion;
+use sp_std::collections::btree_set::BTreeSet;
+
+/// The maximum amount of curators in the council.
+pub const MAX_CURATORS: u32 = 16;
+
+pub type CuratorMembers<AccountId> = BoundedBTreeSet<AccountId, ConstU32<MAX_CURATORS>>;
+
+/// The actions the curator council has to approve.
+#[derive(Clone, Debug, PartialEq, Eq, MaxEncodedLen, TypeInfo, Decode, Encode)]
+pub enum CuratorAction<AccountId> {
+    AddToWhitelist(AccountId),
+    RemoveFromWhitelist(AccountId),
+    RefuseApplication(u64),
+}
+
+/// The council members that approved a curator action, until the approvals expire.
+#[derive(Clone, Debug, PartialEq, Eq, MaxEncodedLen, TypeInfo, Decode, Encode)]
+pub struct CuratorApproval<AccountId: Ord> {
+    pub curators: CuratorMembers<AccountId>,
+    pub expires_at: u64,
+}
 
 #[derive(Clone, Default, TypeInfo, Decode, Encode, MaxEncodedLen, frame_support::DebugNoBound)]
 #[scale_info(skip_type_params(T))]
@@ -28,15 +53,23 @@ pub enum ApplicationStatus {
 
 impl<T: Config> Pallet<T> {
     fn get_next_application_id() -> u64 {
-        match CuratorApplications::<T>::iter_keys().max() {
-            Some(id) => id.saturating_add(1),
-            None => 0,
+        let id = NextApplicationId::<T>::get();
+        NextApplicationId::<T>::set(id.saturating_add(1));
+        id
+    }
+
+    /// The application filed for the given key, if any.
+    fn application_of(key: &T::AccountId) -> Option<CuratorApplication<T>> {
+        if Self::indexes_migrating() {
+            return CuratorApplications::<T>::iter_values().find(|app| app.user_id == *key);
         }
+
+        CuratorApplicationsByUser::<T>::get(key).and_then(CuratorApplications::<T>::get)
     }
 
     #[must_use]
     fn can_add_application_status_based(key: &T::AccountId) -> bool {
-        !CuratorApplications::<T>::iter().any(|(_, app)| app.user_id == *key)
+        Self::application_of(key).is_none()
     }
 
     pub fn add_application(
@@ -83,6 +116,7 @@ impl<T: Config> Pallet<T> {
         // 1. b remove the balance from the account
         PalletSubspace::<T>::remove_balance_from_account(&key, removed_balance_as_currency)?;
 
+        CuratorApplicationsByUser::<T>::insert(&application.user_id, application_id);
         CuratorApplications::<T>::insert(application_id, application);
 
         Self::deposit_event(Event::ApplicationCreated(application_id));
@@ -94,18 +128,24 @@ impl<T: Config> Pallet<T> {
         application_id: u64,
     ) -> DispatchResult {
         let key = ensure_signed(origin)?;
+        ensure!(Self::is_curator(&key), Error::<T>::NotCurator);
+
+        let application =
+            CuratorApplications::<T>::get(application_id).ok_or(Error::<T>::ApplicationNotFound)?;
+        ensure!(
+            application.status == ApplicationStatus::Pending,
+            Error::<T>::ApplicationNotPending
+        );
 
-        // Ensure that the key is the curator multisig.
-        ensure!(Curator::<T>::get() == key, Error::<T>::NotCurator);
+        if !Self::approve_curator_action(key, CuratorAction::RefuseApplication(application_id))? {
+            return Ok(());
+        }
 
-        CuratorApplications::<T>::try_mutate(application_id, |application| match application {
-            Some(app) if app.status == ApplicationStatus::Pending => {
-                app.status = ApplicationStatus::Refused;
-                Ok(())
+        CuratorApplications::<T>::mutate(application_id, |application| {
+            if let Some(application) = application {
+                application.status = ApplicationStatus::Refused;
             }
-            Some(_) => Err(Error::<T>::ApplicationNotPending),
-            None => Err(Error::<T>::ApplicationNotFound),
-        })?;
+        });
 
         Ok(())
     }
@@ -132,9 +172,8 @@ impl<T: Config> Pallet<T> {
         // Perform actions based on the application data type
         // The owners will handle the off-chain logic
 
-        let mut application = CuratorApplications::<T>::iter_values()
-            .find(|app| app.user_id == *user_id)
-            .ok_or(Error::<T>::ApplicationNotFound)?;
+        let mut application =
+            Self::application_of(user_id).ok_or(Error::<T>::ApplicationNotFound)?;
 
         // Give the proposer back his tokens, if the application passed
         PalletSubspace::<T>::add_balance_to_account(
@@ -153,7 +192,7 @@ impl<T: Config> Pallet<T> {
         module_key: T::AccountId,
     ) -> DispatchResult {
         let key = ensure_signed(origin)?;
-        ensure!(Curator::<T>::get() == key, Error::<T>::NotCurator);
+        ensure!(Self::is_curator(&key), Error::<T>::NotCurator);
 
         // make sure application isnt already whitelisted
         ensure!(
@@ -161,15 +200,18 @@ impl<T: Config> Pallet<T> {
             Error::<T>::AlreadyWhitelisted
         );
 
-        let application = CuratorApplications::<T>::iter_values()
-            .find(|app| app.user_id == module_key)
-            .ok_or(Error::<T>::ApplicationNotFound)?;
+        let application =
+            Self::application_of(&module_key).ok_or(Error::<T>::ApplicationNotFound)?;
 
         ensure!(
             application.status == ApplicationStatus::Pending,
             Error::<T>::ApplicationNotPending
         );
 
+        if !Self::approve_curator_action(key, CuratorAction::AddToWhitelist(module_key.clone()))? {
+            return Ok(());
+        }
+
         LegitWhitelist::<T>::insert(&module_key, ());
 
         T::execute_application(&module_key)?;
@@ -184,31 +226,136 @@ impl<T: Config> Pallet<T> {
         module_key: T::AccountId,
     ) -> DispatchResult {
         let key = ensure_signed(origin)?;
-        ensure!(Curator::<T>::get() == key, Error::<T>::NotCurator);
+        ensure!(Self::is_curator(&key), Error::<T>::NotCurator);
         ensure!(
             Self::is_in_legit_whitelist(&module_key),
             Error::<T>::NotWhitelisted
         );
 
+        if !Self::approve_curator_action(
+            key,
+            CuratorAction::RemoveFromWhitelist(module_key.clone()),
+        )? {
+            return Ok(());
+        }
+
         LegitWhitelist::<T>::remove(&module_key);
 
-        CuratorApplications::<T>::iter()
-            .filter(|(_, app)| app.user_id == module_key)
-            .for_each(|(id, mut app)| {
-                app.status = ApplicationStatus::Removed;
-                CuratorApplications::<T>::insert(id, app);
-            });
+        if let Some(mut application) = Self::application_of(&module_key) {
+            application.status = ApplicationStatus::Removed;
+            CuratorApplications::<T>::insert(application.id, application);
+        }
 
         Self::deposit_event(Event::WhitelistModuleRemoved(module_key));
 
         Ok(())
     }
 
+    // Curator council
+    // ===============
+
+    pub fn is_curator(key: &T::AccountId) -> bool {
+        CuratorCouncil::<T>::get().contains(key)
+    }
+
+    /// The account standing for the whole council, returned where a single curator used to be.
+    pub fn curator_council_account() -> T::AccountId {
+        <T as Config>::PalletId::get().into_sub_account_truncating(b"curator")
+    }
+
+    /// Replaces the council members and the amount of them that have to approve each action.
+    /// Pending approvals are dropped, as they may come from former members.
+    pub fn set_curator_council(
+        members: Vec<T::AccountId>,
+        approval_threshold: u32,
+    ) -> DispatchResult {
+        let members = Self::validate_curator_council(members, approval_threshold)?;
+
+        CuratorCouncil::<T>::set(members.clone());
+        CuratorApprovalThreshold::<T>::set(approval_threshold);
+        let _ = CuratorApprovals::<T>::clear(u32::MAX, None);
+        let _ = CuratorApprovalExpiries::<T>::clear(u32::MAX, None);
+
+        Self::deposit_event(Event::CuratorCouncilUpdated(
+            members.into_iter().collect(),
+            approval_threshold,
+        ));
+        Ok(())
+    }
+
+    pub fn validate_curator_council(
+        members: Vec<T::AccountId>,
+        approval_threshold: u32,
+    ) -> Result<CuratorMembers<T::AccountId>, DispatchError> {
+        let members: BTreeSet<_> = members.into_iter().collect();
+        ensure!(
+            approval_threshold > 0 && approval_threshold as usize <= members.len(),
+            Error::<T>::InvalidCuratorCouncil
+        );
+
+        CuratorMembers::<T::AccountId>::try_from(members)
+            .map_err(|_| Error::<T>::InvalidCuratorCouncil.into())
+    }
+
+    /// Records the curator's approval of the action, returning whether the action reached the
+    /// council's threshold and should be executed. The first approval starts the expiration
+    /// period, approvals still short of the threshold at its end are dropped.
+    fn approve_curator_action(
+        curator: T::AccountId,
+        action: CuratorAction<T::AccountId>,
+    ) -> Result<bool, DispatchError> {
+        let mut approval = match CuratorApprovals::<T>::get(&action) {
+            Some(approval) => approval,
+            None => {
+                let expires_at = PalletSubspace::<T>::get_current_block_number()
+                    .saturating_add(CuratorApprovalExpiration::<T>::get().max(1));
+                CuratorApprovalExpiries::<T>::insert(expires_at, &action, ());
+
+                CuratorApproval {
+                    curators: Default::default(),
+                    expires_at,
+                }
+            }
+        };
+
+        let inserted = approval
+            .curators
+            .try_insert(curator.clone())
+            .map_err(|_| Error::<T>::InternalError)?;
+        ensure!(inserted, Error::<T>::AlreadyApproved);
+
+        Self::deposit_event(Event::CuratorActionApproved(curator, action.clone()));
+
+        if approval.curators.len() < CuratorApprovalThreshold::<T>::get() as usize {
+            CuratorApprovals::<T>::insert(&action, approval);
+            return Ok(false);
+        }
+
+        CuratorApprovals::<T>::remove(&action);
+        CuratorApprovalExpiries::<T>::remove(approval.expires_at, &action);
+        Ok(true)
+    }
+
+    /// Drops the approvals of the curator actions expiring at the block.
+    pub(crate) fn expire_curator_approvals(block_number: u64) -> Weight {
+        let db_weight = T::DbWeight::get();
+        let mut weight = db_weight.reads(1);
+
+        for (action, ()) in CuratorApprovalExpiries::<T>::drain_prefix(block_number) {
+            weight = weight.saturating_add(db_weight.reads_writes(1, 2));
+
+            CuratorApprovals::<T>::remove(&action);
+            Self::deposit_event(Event::CuratorApprovalsExpired(action));
+        }
+
+        weight
+    }
+
     // Util
     // ====
 
     pub fn curator_application_exists(module_key: &T::AccountId) -> bool {
-        CuratorApplications::<T>::iter().any(|(_, application)| application.user_id == *module_key)
+        Self::application_of(module_key).is_some()
     }
 
     // Whitelist management
@@ -0,0 +1,219 @@
+use crate::*;
+use frame_support::pallet_prelude::DispatchResult;
+use frame_system::ensure_signed;
+use pallet_subspace::Pallet as PalletSubspace;
+use sp_std::collections::{btree_map::BTreeMap, btree_set::BTreeSet};
+
+/// How far voting power travels through a chain of delegations. Delegators further away from
+/// the voter don't count towards their vote.
+pub const MAX_DELEGATION_HOPS: u32 = 3;
+
+/// The vote delegations in effect on proposals of a subnet, or on global proposals.
+pub struct DelegationGraph<T: Config> {
+    /// The account each delegator votes through.
+    delegations: BTreeMap<T::AccountId, T::AccountId>,
+    /// The accounts delegating directly to each representative.
+    delegators: BTreeMap<T::AccountId, Vec<T::AccountId>>,
+}
+
+impl<T: Config> DelegationGraph<T> {
+    /// Builds the graph for the given subnet. Subnet delegations override the global ones.
+    pub fn build(subnet_id: Option<u16>) -> Self {
+        let mut delegations: BTreeMap<_, _> = VoteDelegations::<T>::iter().collect();
+        if let Some(subnet_id) = subnet_id {
+            delegations.extend(SubnetVoteDelegations::<T>::iter_prefix(subnet_id));
+        }
+
+        let mut delegators: BTreeMap<_, Vec<_>> = BTreeMap::new();
+        for (delegator, representative) in &delegations {
+            delegators.entry(representative.clone()).or_default().push(delegator.clone());
+        }
+
+        Self {
+            delegations,
+            delegators,
+        }
+    }
+
+    /// Whether the account gave its voting power to someone else.
+    pub fn is_delegating(&self, account: &T::AccountId) -> bool {
+        self.delegations.contains_key(account)
+    }
+
+    /// The accounts that gave their voting power to someone else.
+    pub fn delegating_accounts(&self) -> impl Iterator<Item = &T::AccountId> {
+        self.delegations.keys()
+    }
+
+    /// The accounts whose voting power goes to the representative, directly or through at most
+    /// [`MAX_DELEGATION_HOPS`] delegations.
+    pub fn delegators_of(&self, representative: &T::AccountId) -> Vec<T::AccountId> {
+        let mut found = Vec::new();
+        let mut visited = BTreeSet::from([representative.clone()]);
+        let mut frontier = sp_std::vec![representative.clone()];
+
+        for _ in 0..MAX_DELEGATION_HOPS {
+            let mut next = Vec::new();
+            for account in &frontier {
+                for delegator in self.delegators.get(account).into_iter().flatten() {
+                    if visited.insert(delegator.clone()) {
+                        found.push(delegator.clone());
+                        next.push(delegator.clone());
+                    }
+                }
+            }
+            frontier = next;
+        }
+
+        found
+    }
+}
+
+impl<T: Config> Pallet<T> {
+    /// The account voting on behalf of the delegator on the given subnet's proposals, or on
+    /// global proposals.
+    pub fn vote_delegation(
+        delegator: &T::AccountId,
+        subnet_id: Option<u16>,
+    ) -> Option<T::AccountId> {
+        subnet_id
+            .and_then(|subnet_id| SubnetVoteDelegations::<T>::get(subnet_id, delegator))
+            .or_else(|| VoteDelegations::<T>::get(delegator))
+    }
+
+    /// Whether someone gave their voting power on the subnet's proposals, or on global
+    /// proposals, to the account.
+    pub fn has_vote_delegators(account: &T::AccountId, subnet_id: Option<u16>) -> bool {
+        match subnet_id {
+            Some(subnet_id) => {
+                SubnetVoteDelegatorCount::<T>::get(subnet_id, account) > 0
+                    || VoteDelegators::<T>::iter_key_prefix(account).any(|delegator| {
+                        !SubnetVoteDelegations::<T>::contains_key(subnet_id, delegator)
+                    })
+            }
+            None => VoteDelegators::<T>::iter_key_prefix(account).next().is_some(),
+        }
+    }
+
+    /// Whether following the delegations from `from` leads to `target`.
+    fn delegation_reaches(
+        from: &T::AccountId,
+        target: &T::AccountId,
+        subnet_id: Option<u16>,
+    ) -> bool {
+        let mut visited = BTreeSet::new();
+        let mut current = from.clone();
+
+        loop {
+            if current == *target {
+                return true;
+            }
+
+            match Self::vote_delegation(&current, subnet_id) {
+                Some(next) if visited.insert(next.clone()) => current = next,
+                _ => return false,
+            }
+        }
+    }
+
+    pub fn do_delegate_votes(
+        origin: T::RuntimeOrigin,
+        delegatee: T::AccountId,
+        subnet_id: Option<u16>,
+    ) -> DispatchResult {
+        let key = ensure_signed(origin)?;
+
+        ensure!(key != delegatee, Error::<T>::SelfVoteDelegation);
+
+        // A global delegation affects every subnet the delegator didn't delegate on.
+        let affected_subnets: Vec<Option<u16>> = match subnet_id {
+            Some(subnet_id) => {
+                ensure!(
+                    pallet_subspace::N::<T>::contains_key(subnet_id),
+                    pallet_subspace::Error::<T>::NetworkDoesNotExist
+                );
+                sp_std::vec![Some(subnet_id)]
+            }
+            None => sp_std::iter::once(None)
+                .chain(
+                    pallet_subspace::N::<T>::iter_keys()
+                        .filter(|subnet_id| {
+                            !SubnetVoteDelegations::<T>::contains_key(subnet_id, &key)
+                        })
+                        .map(Some),
+                )
+                .collect(),
+        };
+
+        for affected in affected_subnets {
+            ensure!(
+                !Self::delegation_reaches(&delegatee, &key, affected),
+                Error::<T>::VoteDelegationCycle
+            );
+        }
+
+        match subnet_id {
+            Some(subnet_id) => {
+                let previous = SubnetVoteDelegations::<T>::mutate(subnet_id, &key, |delegation| {
+                    delegation.replace(delegatee.clone())
+                });
+                if let Some(previous) = previous {
+                    Self::decrease_subnet_vote_delegators(subnet_id, &previous);
+                }
+                SubnetVoteDelegatorCount::<T>::mutate(subnet_id, &delegatee, |count| {
+                    *count = count.saturating_add(1)
+                });
+            }
+            None => {
+                if let Some(previous) = VoteDelegations::<T>::get(&key) {
+                    VoteDelegators::<T>::remove(&previous, &key);
+                }
+                VoteDelegations::<T>::insert(&key, &delegatee);
+                VoteDelegators::<T>::insert(&delegatee, &key, ());
+            }
+        }
+
+        Self::deposit_event(Event::VotesDelegated(key, delegatee, subnet_id));
+        Ok(())
+    }
+
+    pub fn do_undelegate_votes(origin: T::RuntimeOrigin, subnet_id: Option<u16>) -> DispatchResult {
+        let key = ensure_signed(origin)?;
+
+        // The global delegation takes over on the subnet, so it can't lead back to the account.
+        if let (Some(subnet_id), Some(delegatee)) = (subnet_id, VoteDelegations::<T>::get(&key)) {
+            ensure!(
+                !Self::delegation_reaches(&delegatee, &key, Some(subnet_id)),
+                Error::<T>::VoteDelegationCycle
+            );
+        }
+
+        match subnet_id {
+            Some(subnet_id) => {
+                let delegatee = SubnetVoteDelegations::<T>::take(subnet_id, &key)
+                    .ok_or(Error::<T>::VoteDelegationNotFound)?;
+                Self::decrease_subnet_vote_delegators(subnet_id, &delegatee);
+            }
+            None => {
+                let delegatee =
+                    VoteDelegations::<T>::take(&key).ok_or(Error::<T>::VoteDelegationNotFound)?;
+                VoteDelegators::<T>::remove(&delegatee, &key);
+            }
+        }
+
+        Self::deposit_event(Event::VotesUndelegated(key, subnet_id));
+        Ok(())
+    }
+
+    fn decrease_subnet_vote_delegators(subnet_id: u16, delegatee: &T::AccountId) {
+        SubnetVoteDelegatorCount::<T>::mutate_exists(subnet_id, delegatee, |count| {
+            *count = count.map(|count| count.saturating_sub(1)).filter(|count| *count > 0);
+        });
+    }
+
+    /// The stake an account votes with on its own: what it staked and its share of pools.
+    pub(crate) fn own_voting_stake(account: &T::AccountId) -> u64 {
+        PalletSubspace::<T>::get_owned_stake(account)
+            .saturating_add(PalletSubspace::<T>::get_pooled_stake(account))
+    }
+}
@@ -0,0 +1,176 @@
+use crate::*;
+use frame_support::{
+    pallet_prelude::{DispatchResult, Get, Weight},
+    storage::with_storage_layer,
+    DebugNoBound,
+};
+use pallet_subspace::Pallet as PalletSubspace;
+use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+
+pub type GrantId = u64;
+
+/// A treasury payout streamed over equally sized periods, created by an accepted
+/// [`ProposalData::TreasuryGrant`] proposal.
+#[derive(Clone, DebugNoBound, TypeInfo, Decode, Encode, MaxEncodedLen, PartialEq, Eq)]
+#[scale_info(skip_type_params(T))]
+pub struct TreasuryGrant<T: Config> {
+    pub id: GrantId,
+    /// The proposal that created the grant.
+    pub proposal_id: ProposalId,
+    pub recipient: T::AccountId,
+    pub amount_per_period: u64,
+    /// How many blocks apart payouts are.
+    pub period_length: u64,
+    pub periods: u32,
+    /// The block the first period is paid at.
+    pub start_block: u64,
+    pub paid_periods: u32,
+}
+
+impl<T: Config> TreasuryGrant<T> {
+    /// The block the next period is due at.
+    #[must_use]
+    pub fn next_payout_block(&self) -> u64 {
+        self.period_length
+            .saturating_mul(self.paid_periods.into())
+            .saturating_add(self.start_block)
+    }
+
+    /// How much the treasury still owes to the recipient.
+    #[must_use]
+    pub fn remaining_liability(&self) -> u64 {
+        self.amount_per_period
+            .saturating_mul(self.periods.saturating_sub(self.paid_periods).into())
+    }
+
+    /// Pays the due period from the treasury, removing the grant once every period is paid.
+    /// Returns the block the next period is due at, if any.
+    fn pay_period(mut self) -> Result<Option<u64>, DispatchError> {
+        PalletSubspace::<T>::transfer_balance_to_account(
+            &DaoTreasuryAddress::<T>::get(),
+            &self.recipient,
+            self.amount_per_period,
+        )?;
+
+        self.paid_periods = self.paid_periods.saturating_add(1);
+        Pallet::<T>::deposit_event(Event::GrantPaid(
+            self.id,
+            self.recipient.clone(),
+            self.amount_per_period,
+        ));
+
+        if self.paid_periods >= self.periods {
+            TreasuryGrants::<T>::remove(self.id);
+            return Ok(None);
+        }
+
+        let next_payout_block = self.next_payout_block();
+        TreasuryGrants::<T>::insert(self.id, self);
+        Ok(Some(next_payout_block))
+    }
+}
+
+impl<T: Config> Pallet<T> {
+    /// Checks the grant terms before the proposal is created.
+    pub(crate) fn validate_treasury_grant(
+        amount_per_period: u64,
+        period_length: u64,
+        periods: u32,
+    ) -> DispatchResult {
+        ensure!(
+            amount_per_period > 0 && period_length > 0 && periods > 0,
+            Error::<T>::InvalidTreasuryGrant
+        );
+        ensure!(
+            amount_per_period.checked_mul(periods.into()).is_some(),
+            Error::<T>::InvalidTreasuryGrant
+        );
+
+        Ok(())
+    }
+
+    pub(crate) fn create_treasury_grant(
+        proposal_id: ProposalId,
+        recipient: T::AccountId,
+        amount_per_period: u64,
+        period_length: u64,
+        periods: u32,
+        start_block: u64,
+    ) {
+        let id = NextTreasuryGrantId::<T>::get();
+        NextTreasuryGrantId::<T>::set(id.saturating_add(1));
+
+        TreasuryGrants::<T>::insert(
+            id,
+            TreasuryGrant {
+                id,
+                proposal_id,
+                recipient,
+                amount_per_period,
+                period_length,
+                periods,
+                start_block,
+                paid_periods: 0,
+            },
+        );
+        // Grants are created while proposals are ticked, before this block's payouts run
+        let first_payout_block = start_block.max(PalletSubspace::<T>::get_current_block_number());
+        TreasuryGrantPayouts::<T>::insert(first_payout_block, id, ());
+
+        Self::deposit_event(Event::GrantCreated(id, proposal_id));
+    }
+
+    pub(crate) fn cancel_treasury_grant(grant_id: GrantId) -> DispatchResult {
+        ensure!(
+            TreasuryGrants::<T>::take(grant_id).is_some(),
+            Error::<T>::TreasuryGrantNotFound
+        );
+
+        Self::deposit_event(Event::GrantCancelled(grant_id));
+        Ok(())
+    }
+
+    /// The grants that still have periods to pay.
+    pub fn active_treasury_grants() -> Vec<TreasuryGrant<T>> {
+        TreasuryGrants::<T>::iter_values().collect()
+    }
+
+    /// How much the treasury owes over all active grants.
+    pub fn treasury_grant_liabilities() -> u64 {
+        TreasuryGrants::<T>::iter_values()
+            .map(|grant| grant.remaining_liability())
+            .fold(0, u64::saturating_add)
+    }
+}
+
+/// Pays the grants scheduled for the block, and schedules their next period. Grants that fell
+/// behind, for example while the treasury was short on funds, catch up one period per block.
+pub fn tick_treasury_grants<T: Config>(block_number: u64) -> Weight {
+    let db_weight = T::DbWeight::get();
+    let mut weight = db_weight.reads(1);
+
+    let next_block = block_number.saturating_add(1);
+    for (id, ()) in TreasuryGrantPayouts::<T>::drain_prefix(block_number) {
+        weight = weight.saturating_add(db_weight.reads_writes(1, 1));
+
+        let Some(grant) = TreasuryGrants::<T>::get(id) else {
+            continue;
+        };
+
+        weight = weight.saturating_add(db_weight.reads_writes(3, 4));
+        let next_payout_block = match with_storage_layer(|| grant.pay_period()) {
+            Ok(next_payout_block) => next_payout_block,
+            Err(err) => {
+                log::error!("failed to pay treasury grant {id}: {err:?}, retrying next block...");
+                Some(next_block)
+            }
+        };
+
+        if let Some(next_payout_block) = next_payout_block {
+            TreasuryGrantPayouts::<T>::insert(next_payout_block.max(next_block), id, ());
+        }
+    }
+
+    weight
+}
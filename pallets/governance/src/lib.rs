@@ -6,8 +6,12 @@
 mod benchmarking;
 
 pub mod dao;
+pub mod delegation;
+pub mod grant;
 pub mod migrations;
 pub mod proposal;
+#[cfg(feature = "try-runtime")]
+mod try_state;
 pub mod voting;
 pub mod weights; // Weight benchmarks
 
@@ -22,13 +26,17 @@ use substrate_fixed::types::I64F64;
 
 pub use pallet::*;
 pub use pallet_governance_api::*;
+use pallet_subnet_emission_api::SubnetConsensus;
 use pallet_subspace::{
     self, define_subnet_includes,
     params::{burn::GeneralBurnConfiguration, subnet::SubnetChangeset},
-    DefaultKey,
+    PruningPolicy, RegistrationMode,
 };
 
-pub use proposal::{Proposal, ProposalData, ProposalId, ProposalStatus, UnrewardedProposal};
+pub use grant::{GrantId, TreasuryGrant};
+pub use proposal::{
+    Proposal, ProposalData, ProposalId, ProposalStatus, SubnetAction, UnrewardedProposal,
+};
 
 type SubnetId = u16;
 
@@ -37,20 +45,19 @@ pub mod pallet {
     #![allow(clippy::too_many_arguments)]
 
     pub use crate::weights::WeightInfo;
-    use crate::{dao::CuratorApplication, *};
+    use crate::{
+        dao::{CuratorAction, CuratorApplication, CuratorApproval, CuratorMembers},
+        *,
+    };
     use frame_support::{
         pallet_prelude::{ValueQuery, *},
-        traits::{Currency, StorageInstance},
+        traits::{ConstU64, Currency, StorageInstance},
         PalletId,
     };
     use frame_system::pallet_prelude::{ensure_signed, BlockNumberFor};
     use sp_runtime::traits::AccountIdConversion;
 
-    #[cfg(feature = "testnet")]
-    const STORAGE_VERSION: StorageVersion = StorageVersion::new(4);
-
-    #[cfg(not(feature = "testnet"))]
-    const STORAGE_VERSION: StorageVersion = StorageVersion::new(2);
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(6);
 
     #[pallet::pallet]
     #[pallet::storage_version(STORAGE_VERSION)]
@@ -79,10 +86,20 @@ pub mod pallet {
             let block_number: u64 =
                 block_number.try_into().ok().expect("blockchain won't pass 2 ^ 64 blocks");
 
+            let weight = migrations::v3::step::<T>();
+
             proposal::tick_proposals::<T>(block_number);
             proposal::tick_proposal_rewards::<T>(block_number);
+            proposal::prune_proposals::<T>(block_number);
 
-            Weight::zero()
+            weight
+                .saturating_add(grant::tick_treasury_grants::<T>(block_number))
+                .saturating_add(Self::expire_curator_approvals(block_number))
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn try_state(_n: BlockNumberFor<T>) -> Result<(), sp_runtime::TryRuntimeError> {
+            Self::do_try_state()
         }
     }
 
@@ -97,7 +114,7 @@ pub mod pallet {
     // --- Subnet Related Storage ---
 
     define_subnet_includes!(
-        double_maps: {},
+        double_maps: { SubnetVoteDelegations, SubnetVoteDelegatorCount },
         maps: { SubnetGovernanceConfig, }
     );
 
@@ -129,6 +146,54 @@ pub mod pallet {
     pub type UnrewardedProposals<T: Config> =
         StorageMap<_, Identity, ProposalId, UnrewardedProposal<T>>;
 
+    /// The id given to the next proposal. Ids are never reused, even after pruning.
+    #[pallet::storage]
+    pub type NextProposalId<T: Config> = StorageValue<_, ProposalId, ValueQuery>;
+
+    /// The proposals that are still open, so ticking doesn't walk the whole proposal history.
+    #[pallet::storage]
+    pub type ActiveProposals<T: Config> = StorageMap<_, Identity, ProposalId, (), ValueQuery>;
+
+    /// How many open proposals each account has, capped by the deposit policy.
+    #[pallet::storage]
+    pub type OpenProposalCount<T: Config> =
+        StorageMap<_, Identity, T::AccountId, u32, ValueQuery>;
+
+    #[pallet::type_value]
+    pub fn DefaultProposalRetentionPeriod<T: Config>() -> u64 {
+        324_000 // ~30 days
+    }
+
+    /// How many blocks a finished proposal is kept around before being pruned.
+    #[pallet::storage]
+    pub type ProposalRetentionPeriod<T: Config> =
+        StorageValue<_, u64, ValueQuery, DefaultProposalRetentionPeriod<T>>;
+
+    /// The lowest proposal id that wasn't pruned yet. Pruning walks forward from it.
+    #[pallet::storage]
+    pub type ProposalPruneCursor<T: Config> = StorageValue<_, ProposalId, ValueQuery>;
+
+    /// The progress of the migration populating the indexes. While it's set, the indexes are
+    /// incomplete and lookups fall back to scanning the indexed storages.
+    #[pallet::storage]
+    pub type IndexMigrationCursor<T: Config> = StorageValue<_, migrations::v3::MigrationCursor>;
+
+    // --- Treasury Grant Related Storage ---
+
+    /// The treasury grants that still have periods to pay.
+    #[pallet::storage]
+    pub type TreasuryGrants<T: Config> = StorageMap<_, Identity, GrantId, TreasuryGrant<T>>;
+
+    /// The id given to the next treasury grant.
+    #[pallet::storage]
+    pub type NextTreasuryGrantId<T: Config> = StorageValue<_, GrantId, ValueQuery>;
+
+    /// Treasury grants by the block their next period is paid at. Entries of cancelled grants
+    /// are dropped when their block comes.
+    #[pallet::storage]
+    pub type TreasuryGrantPayouts<T: Config> =
+        StorageDoubleMap<_, Identity, u64, Identity, GrantId, ()>;
+
     // --- Storage Items ---
 
     /// A map relating all modules and the stakers that are currently **NOT** delegating their
@@ -140,6 +205,26 @@ pub mod pallet {
     pub type NotDelegatingVotingPower<T: Config> =
         StorageValue<_, BoundedBTreeSet<T::AccountId, ConstU32<{ u32::MAX }>>, ValueQuery>;
 
+    /// The account each delegator gave its voting power to, on every proposal.
+    #[pallet::storage]
+    pub type VoteDelegations<T: Config> = StorageMap<_, Identity, T::AccountId, T::AccountId>;
+
+    /// Delegations restricted to a subnet's proposals. They override the delegator's
+    /// [`VoteDelegations`] entry on that subnet.
+    #[pallet::storage]
+    pub type SubnetVoteDelegations<T: Config> =
+        StorageDoubleMap<_, Identity, SubnetId, Identity, T::AccountId, T::AccountId>;
+
+    /// The delegators of each representative in [`VoteDelegations`], keyed by representative.
+    #[pallet::storage]
+    pub type VoteDelegators<T: Config> =
+        StorageDoubleMap<_, Identity, T::AccountId, Identity, T::AccountId, ()>;
+
+    /// How many accounts delegate to each representative in [`SubnetVoteDelegations`].
+    #[pallet::storage]
+    pub type SubnetVoteDelegatorCount<T: Config> =
+        StorageDoubleMap<_, Identity, SubnetId, Identity, T::AccountId, u32, ValueQuery>;
+
     #[pallet::storage]
     pub type GlobalGovernanceConfig<T: Config> =
         StorageValue<_, GovernanceConfiguration, ValueQuery>;
@@ -171,11 +256,48 @@ pub mod pallet {
     #[pallet::storage]
     pub type CuratorApplications<T: Config> = StorageMap<_, Identity, u64, CuratorApplication<T>>;
 
+    /// The id given to the next curator application.
+    #[pallet::storage]
+    pub type NextApplicationId<T: Config> = StorageValue<_, u64, ValueQuery>;
+
+    /// The application filed for each key, as a key can only be applied for once.
+    #[pallet::storage]
+    pub type CuratorApplicationsByUser<T: Config> = StorageMap<_, Identity, T::AccountId, u64>;
+
     #[pallet::storage]
     pub type LegitWhitelist<T: Config> = StorageMap<_, Identity, T::AccountId, (), ValueQuery>;
 
+    /// The accounts curating the whitelist and the DAO applications.
+    #[pallet::storage]
+    pub type CuratorCouncil<T: Config> = StorageValue<_, CuratorMembers<T::AccountId>, ValueQuery>;
+
+    /// How many council members have to approve a curator action for it to be executed.
+    #[pallet::storage]
+    pub type CuratorApprovalThreshold<T: Config> = StorageValue<_, u32, ValueQuery, ConstU32<1>>;
+
+    /// The council members that approved each pending curator action.
+    #[pallet::storage]
+    pub type CuratorApprovals<T: Config> = StorageMap<
+        _,
+        Blake2_128Concat,
+        CuratorAction<T::AccountId>,
+        CuratorApproval<T::AccountId>,
+    >;
+
+    /// How many blocks the approvals of a curator action are kept before they are dropped.
     #[pallet::storage]
-    pub type Curator<T: Config> = StorageValue<_, T::AccountId, ValueQuery, DefaultKey<T>>;
+    pub type CuratorApprovalExpiration<T> = StorageValue<_, u64, ValueQuery, ConstU64<10_800>>;
+
+    /// Pending curator actions by the block their approvals expire at.
+    #[pallet::storage]
+    pub type CuratorApprovalExpiries<T: Config> = StorageDoubleMap<
+        _,
+        Identity,
+        u64,
+        Blake2_128Concat,
+        CuratorAction<T::AccountId>,
+        (),
+    >;
 
     // --- Extrinsics ---
 
@@ -205,6 +327,9 @@ pub mod pallet {
             kappa: u16,
             rho: u16,
             subnet_immunity_period: u64,
+            ceiling_stake_delegation_fee: Percent,
+            ceiling_validator_weight_fee: Percent,
+            fee_announcement_period: u64,
         ) -> DispatchResult {
             let mut params = pallet_subspace::Pallet::<T>::global_params();
             params.max_name_length = max_name_length;
@@ -224,6 +349,9 @@ pub mod pallet {
             params.kappa = kappa;
             params.rho = rho;
             params.subnet_immunity_period = subnet_immunity_period;
+            params.ceiling_stake_delegation_fee = ceiling_stake_delegation_fee;
+            params.ceiling_validator_weight_fee = ceiling_validator_weight_fee;
+            params.fee_announcement_period = fee_announcement_period;
             Self::do_add_global_params_proposal(origin, data, params)
         }
 
@@ -253,6 +381,8 @@ pub mod pallet {
             use_weights_encryption: bool,
             copier_margin: I64F64,
             max_encryption_period: Option<u64>,
+            pruning_policy: PruningPolicy,
+            registration_mode: RegistrationMode,
         ) -> DispatchResult {
             let mut params = pallet_subspace::Pallet::subnet_params(netuid);
             params.founder = founder;
@@ -275,6 +405,8 @@ pub mod pallet {
             params.use_weights_encryption = use_weights_encryption;
             params.copier_margin = copier_margin;
             params.max_encryption_period = max_encryption_period;
+            params.pruning_policy = pruning_policy;
+            params.registration_mode = registration_mode;
             Self::do_add_subnet_params_proposal(origin, netuid, data, params)
         }
 
@@ -305,6 +437,17 @@ pub mod pallet {
             Self::do_add_transfer_dao_treasury_proposal(origin, data, value, dest)
         }
 
+        #[pallet::call_index(13)]
+        #[pallet::weight((<T as pallet::Config>::WeightInfo::add_subnet_params_proposal(), DispatchClass::Normal, Pays::No))]
+        pub fn add_subnet_consensus_proposal(
+            origin: OriginFor<T>,
+            netuid: u16,
+            data: Vec<u8>,
+            consensus: SubnetConsensus,
+        ) -> DispatchResult {
+            Self::do_add_subnet_consensus_proposal(origin, netuid, data, consensus)
+        }
+
         // --- Voting / Unvoting proposals ---
 
         // This has to pay fee, so very low stake keys don't spam the voting system.
@@ -338,6 +481,76 @@ pub mod pallet {
             Self::update_delegating_voting_power(&key, false)
         }
 
+        #[pallet::call_index(14)]
+        #[pallet::weight((<T as pallet::Config>::WeightInfo::enable_vote_power_delegation(), DispatchClass::Normal, Pays::No))]
+        pub fn delegate_votes(
+            origin: OriginFor<T>,
+            delegatee: T::AccountId,
+            netuid: Option<u16>,
+        ) -> DispatchResult {
+            Self::do_delegate_votes(origin, delegatee, netuid)
+        }
+
+        #[pallet::call_index(15)]
+        #[pallet::weight((<T as pallet::Config>::WeightInfo::disable_vote_power_delegation(), DispatchClass::Normal, Pays::No))]
+        pub fn undelegate_votes(origin: OriginFor<T>, netuid: Option<u16>) -> DispatchResult {
+            Self::do_undelegate_votes(origin, netuid)
+        }
+
+        #[pallet::call_index(16)]
+        #[pallet::weight((<T as pallet::Config>::WeightInfo::add_global_custom_proposal(), DispatchClass::Normal, Pays::No))]
+        pub fn add_curator_council_proposal(
+            origin: OriginFor<T>,
+            data: Vec<u8>,
+            members: Vec<T::AccountId>,
+            approval_threshold: u32,
+        ) -> DispatchResult {
+            Self::do_add_curator_council_proposal(origin, data, members, approval_threshold)
+        }
+
+        #[pallet::call_index(17)]
+        #[pallet::weight((<T as pallet::Config>::WeightInfo::add_transfer_dao_treasury_proposal(), DispatchClass::Normal, Pays::No))]
+        pub fn add_treasury_grant_proposal(
+            origin: OriginFor<T>,
+            data: Vec<u8>,
+            recipient: T::AccountId,
+            amount_per_period: u64,
+            period_length: u64,
+            periods: u32,
+            start_block: u64,
+        ) -> DispatchResult {
+            Self::do_add_treasury_grant_proposal(
+                origin,
+                data,
+                recipient,
+                amount_per_period,
+                period_length,
+                periods,
+                start_block,
+            )
+        }
+
+        #[pallet::call_index(18)]
+        #[pallet::weight((<T as pallet::Config>::WeightInfo::add_transfer_dao_treasury_proposal(), DispatchClass::Normal, Pays::No))]
+        pub fn add_cancel_treasury_grant_proposal(
+            origin: OriginFor<T>,
+            data: Vec<u8>,
+            grant_id: GrantId,
+        ) -> DispatchResult {
+            Self::do_add_cancel_treasury_grant_proposal(origin, data, grant_id)
+        }
+
+        #[pallet::call_index(19)]
+        #[pallet::weight((<T as pallet::Config>::WeightInfo::add_subnet_params_proposal(), DispatchClass::Normal, Pays::No))]
+        pub fn add_subnet_action_proposal(
+            origin: OriginFor<T>,
+            netuid: u16,
+            data: Vec<u8>,
+            action: SubnetAction<T>,
+        ) -> DispatchResult {
+            Self::do_add_subnet_action_proposal(origin, netuid, data, action)
+        }
+
         // --- General Subnet DAO ---
 
         #[pallet::call_index(9)]
@@ -395,6 +608,22 @@ pub mod pallet {
         WhitelistModuleRemoved(T::AccountId),
         /// A new application has been created.
         ApplicationCreated(u64),
+        /// An account gave its voting power to another account, on a subnet or globally.
+        VotesDelegated(T::AccountId, T::AccountId, Option<u16>),
+        /// An account took back its voting power, on a subnet or globally.
+        VotesUndelegated(T::AccountId, Option<u16>),
+        /// A curator approved an action of the council.
+        CuratorActionApproved(T::AccountId, CuratorAction<T::AccountId>),
+        /// The curator council members and approval threshold were replaced.
+        CuratorCouncilUpdated(Vec<T::AccountId>, u32),
+        /// The approvals of a curator action expired before reaching the threshold.
+        CuratorApprovalsExpired(CuratorAction<T::AccountId>),
+        /// An accepted proposal created a treasury grant.
+        GrantCreated(GrantId, ProposalId),
+        /// A period of a treasury grant was paid to its recipient.
+        GrantPaid(GrantId, T::AccountId, u64),
+        /// A treasury grant was cancelled before paying all of its periods.
+        GrantCancelled(GrantId),
     }
 
     // ---  Errors ---
@@ -466,6 +695,30 @@ pub mod pallet {
         NotWhitelisted,
         /// Failed to convert the given value to a balance.
         CouldNotConvertToBalance,
+        /// A proposal kind was configured without a quorum.
+        InvalidProposalQuorum,
+        /// A proposal kind was configured to be approved by less than half of the voting stake.
+        InvalidProposalApproval,
+        /// The deposit policy doesn't allow any open proposal per account.
+        InvalidMaxOpenProposals,
+        /// The account has reached the maximum amount of concurrently open proposals.
+        TooManyOpenProposals,
+        /// Accounts can't delegate their votes to themselves.
+        SelfVoteDelegation,
+        /// The delegation would make the voting power go around in circles.
+        VoteDelegationCycle,
+        /// The account isn't delegating its votes.
+        VoteDelegationNotFound,
+        /// The voter gave its voting power to another account.
+        VotesDelegatedToAccount,
+        /// The curator already approved this action.
+        AlreadyApproved,
+        /// The council has no members, too many of them, or a threshold it can't reach.
+        InvalidCuratorCouncil,
+        /// The grant has no amount, periods or period length, or its total overflows.
+        InvalidTreasuryGrant,
+        /// The treasury grant doesn't exist or already paid all of its periods.
+        TreasuryGrantNotFound,
     }
 }
 
@@ -480,11 +733,39 @@ impl<T: Config> Pallet<T> {
             config.proposal_expiration > 0,
             Error::<T>::InvalidProposalExpiration
         );
+
+        for thresholds in [
+            config.custom_thresholds,
+            config.params_thresholds,
+            config.treasury_thresholds,
+            config.consensus_thresholds,
+        ] {
+            ensure!(
+                !thresholds.quorum.is_zero(),
+                Error::<T>::InvalidProposalQuorum
+            );
+            // Below half, a proposal could pass with most of the voting stake against it.
+            ensure!(
+                thresholds.approval >= Percent::from_parts(50),
+                Error::<T>::InvalidProposalApproval
+            );
+        }
+
+        ensure!(
+            config.deposit_policy.max_open_proposals_per_account > 0,
+            Error::<T>::InvalidMaxOpenProposals
+        );
+
         Ok(config)
     }
 }
 
 impl<T: Config> Pallet<T> {
+    /// Whether the proposal and application indexes are still being populated.
+    pub fn indexes_migrating() -> bool {
+        IndexMigrationCursor::<T>::exists()
+    }
+
     pub fn is_delegating_voting_power(delegator: &T::AccountId) -> bool {
         !NotDelegatingVotingPower::<T>::get().contains(delegator)
     }
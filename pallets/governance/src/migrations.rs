@@ -76,3 +76,502 @@ pub mod v2 {
         }
     }
 }
+
+pub mod v3 {
+    use frame_support::{
+        pallet_prelude::{Decode, Encode, MaxEncodedLen, TypeInfo},
+        traits::OnRuntimeUpgrade,
+        weights::Weight,
+        BoundedVec,
+    };
+
+    use super::*;
+
+    /// How many entries are indexed per block.
+    const STEP_SIZE: u32 = 64;
+
+    /// The storage being indexed and the raw key of the last entry indexed in it.
+    #[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, TypeInfo, MaxEncodedLen)]
+    pub enum MigrationCursor {
+        Proposals(Option<BoundedVec<u8, ConstU32<128>>>),
+        Applications(Option<BoundedVec<u8, ConstU32<128>>>),
+    }
+
+    /// Sets up the id counters and starts populating the active proposal and curator application
+    /// indexes. Populating them reads every proposal and application, so it's done a few entries
+    /// per block by [`step`].
+    pub struct MigrateToV3<T>(sp_std::marker::PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for MigrateToV3<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let on_chain_version = StorageVersion::get::<Pallet<T>>();
+            if on_chain_version != 2 {
+                log::info!("Storage v3 already updated");
+                return Weight::zero();
+            }
+
+            let weight = init_indexes::<T>();
+
+            log::info!("Migrating storage to v3");
+            StorageVersion::new(3).put::<Pallet<T>>();
+
+            weight.saturating_add(T::DbWeight::get().reads_writes(1, 1))
+        }
+    }
+
+    /// Sets the id counters past the stored proposals and applications and starts populating
+    /// the indexes. Finding the highest ids reads every key, which the returned weight counts.
+    pub fn init_indexes<T: Config>() -> Weight {
+        let (next_proposal_id, proposals) = next_id(Proposals::<T>::iter_keys());
+        NextProposalId::<T>::set(next_proposal_id);
+
+        let (next_application_id, applications) = next_id(CuratorApplications::<T>::iter_keys());
+        NextApplicationId::<T>::set(next_application_id);
+
+        IndexMigrationCursor::<T>::set(Some(MigrationCursor::Proposals(None)));
+
+        T::DbWeight::get().reads_writes(proposals.saturating_add(applications), 3)
+    }
+
+    /// The id following the highest one, and how many ids were read.
+    fn next_id(ids: impl Iterator<Item = u64>) -> (u64, u64) {
+        ids.fold((0, 0), |(next, read), id| {
+            (next.max(id.saturating_add(1)), read.saturating_add(1))
+        })
+    }
+
+    /// Indexes the next batch of entries, if the migration is in progress.
+    pub fn step<T: Config>() -> Weight {
+        let Some(cursor) = IndexMigrationCursor::<T>::get() else {
+            return T::DbWeight::get().reads(1);
+        };
+
+        let (indexed, next_cursor) = match cursor {
+            MigrationCursor::Proposals(last_key) => {
+                let mut iter = match last_key {
+                    Some(key) => Proposals::<T>::iter_from(key.into_inner()),
+                    None => Proposals::<T>::iter(),
+                };
+
+                let mut indexed = 0u32;
+                for (id, proposal) in iter.by_ref().take(STEP_SIZE as usize) {
+                    if proposal.is_active() {
+                        ActiveProposals::<T>::insert(id, ());
+                    }
+                    indexed = indexed.saturating_add(1);
+                }
+
+                let next_cursor = if indexed < STEP_SIZE {
+                    MigrationCursor::Applications(None)
+                } else {
+                    MigrationCursor::Proposals(Some(BoundedVec::truncate_from(
+                        iter.last_raw_key().to_vec(),
+                    )))
+                };
+
+                (indexed, Some(next_cursor))
+            }
+            MigrationCursor::Applications(last_key) => {
+                let mut iter = match last_key {
+                    Some(key) => CuratorApplications::<T>::iter_from(key.into_inner()),
+                    None => CuratorApplications::<T>::iter(),
+                };
+
+                let mut indexed = 0u32;
+                for (id, application) in iter.by_ref().take(STEP_SIZE as usize) {
+                    CuratorApplicationsByUser::<T>::insert(application.user_id, id);
+                    indexed = indexed.saturating_add(1);
+                }
+
+                let next_cursor = (indexed >= STEP_SIZE).then(|| {
+                    MigrationCursor::Applications(Some(BoundedVec::truncate_from(
+                        iter.last_raw_key().to_vec(),
+                    )))
+                });
+
+                (indexed, next_cursor)
+            }
+        };
+
+        match next_cursor {
+            Some(cursor) => IndexMigrationCursor::<T>::set(Some(cursor)),
+            None => {
+                IndexMigrationCursor::<T>::kill();
+                log::info!("Governance indexes populated");
+            }
+        }
+
+        T::DbWeight::get().reads_writes(
+            u64::from(indexed).saturating_add(1),
+            u64::from(indexed).saturating_add(1),
+        )
+    }
+}
+
+pub mod v4 {
+    use frame_support::{traits::OnRuntimeUpgrade, weights::Weight};
+
+    use super::*;
+
+    pub mod old_storage {
+        use super::*;
+        use frame_support::pallet_prelude::{Decode, Encode};
+        use sp_runtime::Percent;
+
+        #[derive(Decode, Encode)]
+        pub struct GovernanceConfiguration {
+            pub proposal_cost: u64,
+            pub proposal_expiration: u32,
+            pub vote_mode: VoteMode,
+            pub proposal_reward_treasury_allocation: Percent,
+            pub max_proposal_reward_treasury_allocation: u64,
+            pub proposal_reward_interval: u64,
+        }
+
+        impl GovernanceConfiguration {
+            pub fn migrate(self) -> crate::GovernanceConfiguration {
+                crate::GovernanceConfiguration {
+                    proposal_cost: self.proposal_cost,
+                    proposal_expiration: self.proposal_expiration,
+                    vote_mode: self.vote_mode,
+                    proposal_reward_treasury_allocation: self.proposal_reward_treasury_allocation,
+                    max_proposal_reward_treasury_allocation: self
+                        .max_proposal_reward_treasury_allocation,
+                    proposal_reward_interval: self.proposal_reward_interval,
+                    ..Default::default()
+                }
+            }
+        }
+    }
+
+    /// Adds the proposal thresholds and deposit policies to the governance configurations,
+    /// keeping the previously hardcoded quorums, simple majority approvals and refunds.
+    pub struct MigrateToV4<T>(sp_std::marker::PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for MigrateToV4<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let on_chain_version = StorageVersion::get::<Pallet<T>>();
+            if on_chain_version != 3 {
+                log::info!("Storage v4 already updated");
+                return T::DbWeight::get().reads(1);
+            }
+
+            let weight = translate_governance_configs::<T>();
+
+            log::info!("Migrating storage to v4");
+            StorageVersion::new(4).put::<Pallet<T>>();
+            weight.saturating_add(T::DbWeight::get().reads_writes(1, 1))
+        }
+    }
+
+    /// Re-encodes the global and subnet governance configurations with the thresholds and
+    /// deposit policies.
+    pub fn translate_governance_configs<T: Config>() -> Weight {
+        let mut translated = 1u64;
+        let _ = GlobalGovernanceConfig::<T>::translate(
+            |config: Option<old_storage::GovernanceConfiguration>| {
+                config.map(|config| config.migrate())
+            },
+        );
+        SubnetGovernanceConfig::<T>::translate(
+            |_, config: old_storage::GovernanceConfiguration| {
+                translated = translated.saturating_add(1);
+                Some(config.migrate())
+            },
+        );
+
+        T::DbWeight::get().reads_writes(translated, translated)
+    }
+}
+
+pub mod v5 {
+    use frame_support::{traits::OnRuntimeUpgrade, weights::Weight};
+    use pallet_subspace::DefaultKey;
+
+    use super::*;
+
+    pub mod old_storage {
+        use super::*;
+        use frame_support::storage_alias;
+        use pallet_subspace::AccountIdOf;
+
+        #[storage_alias]
+        pub type Curator<T: Config> = StorageValue<Pallet<T>, AccountIdOf<T>>;
+    }
+
+    /// Moves the single curator into a council of its own, approving actions by itself.
+    pub fn migrate_curator<T: Config>() -> Weight {
+        match old_storage::Curator::<T>::take() {
+            Some(curator) if curator != DefaultKey::<T>::get() => {
+                if let Err(err) = Pallet::<T>::set_curator_council(sp_std::vec![curator], 1) {
+                    log::error!("could not migrate the curator: {err:?}");
+                }
+            }
+            _ => log::info!("No curator to migrate"),
+        }
+
+        T::DbWeight::get().reads_writes(1, 5)
+    }
+
+    pub struct MigrateToV5<T>(sp_std::marker::PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for MigrateToV5<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let on_chain_version = StorageVersion::get::<Pallet<T>>();
+            if on_chain_version != 4 {
+                log::info!("Storage v5 already updated");
+                return Weight::zero();
+            }
+
+            let weight = migrate_curator::<T>();
+
+            log::info!("Migrating storage to v5");
+            StorageVersion::new(5).put::<Pallet<T>>();
+            weight.saturating_add(T::DbWeight::get().reads_writes(1, 1))
+        }
+    }
+}
+
+pub mod v6 {
+    use frame_support::{traits::OnRuntimeUpgrade, weights::Weight};
+    use pallet_subspace::{FeeAnnouncementPeriod, MaxFees, PruningPolicy, RegistrationMode};
+    use sp_std::collections::btree_map::BTreeMap;
+
+    use super::*;
+
+    pub mod old_storage {
+        use super::*;
+        use frame_support::pallet_prelude::{Decode, Encode};
+        use pallet_subspace::migrations::v16::old_storage::GeneralBurnConfiguration;
+        use sp_runtime::{BoundedVec, Percent};
+        use v4::old_storage::GovernanceConfiguration;
+
+        #[derive(Decode, Encode)]
+        pub struct SubnetParams<T: Config> {
+            pub founder: T::AccountId,
+            pub founder_share: u16,
+            pub immunity_period: u16,
+            pub incentive_ratio: u16,
+            pub max_allowed_uids: u16,
+            pub max_allowed_weights: u16,
+            pub min_allowed_weights: u16,
+            pub max_weight_age: u64,
+            pub name: BoundedVec<u8, ConstU32<256>>,
+            pub metadata: Option<BoundedVec<u8, ConstU32<120>>>,
+            pub tempo: u16,
+            pub maximum_set_weight_calls_per_epoch: Option<u16>,
+            pub bonds_ma: u64,
+            pub module_burn_config: GeneralBurnConfiguration,
+            pub min_validator_stake: u64,
+            pub max_allowed_validators: Option<u16>,
+            pub governance_config: GovernanceConfiguration,
+            pub use_weights_encryption: bool,
+            pub copier_margin: I64F64,
+            pub max_encryption_period: Option<u64>,
+        }
+
+        #[derive(Decode, Encode)]
+        pub struct GlobalParams<T: Config> {
+            pub max_name_length: u16,
+            pub min_name_length: u16,
+            pub max_allowed_subnets: u16,
+            pub max_allowed_modules: u16,
+            pub max_registrations_per_block: u16,
+            pub max_allowed_weights: u16,
+            pub floor_stake_delegation_fee: Percent,
+            pub floor_validator_weight_fee: Percent,
+            pub floor_founder_share: u8,
+            pub min_weight_stake: u64,
+            pub curator: T::AccountId,
+            pub general_subnet_application_cost: u64,
+            pub subnet_immunity_period: u64,
+            pub governance_config: GovernanceConfiguration,
+            pub kappa: u16,
+            pub rho: u16,
+        }
+
+        #[derive(Decode, Encode)]
+        pub enum ProposalData<T: Config> {
+            GlobalCustom,
+            GlobalParams(GlobalParams<T>),
+            SubnetCustom {
+                subnet_id: SubnetId,
+            },
+            SubnetParams {
+                subnet_id: SubnetId,
+                params: SubnetParams<T>,
+            },
+            TransferDaoTreasury {
+                account: T::AccountId,
+                amount: u64,
+            },
+        }
+
+        #[derive(Decode, Encode)]
+        pub struct Proposal<T: Config> {
+            pub id: ProposalId,
+            pub proposer: T::AccountId,
+            pub expiration_block: u64,
+            pub data: ProposalData<T>,
+            pub status: ProposalStatus<T>,
+            pub metadata: BoundedVec<u8, ConstU32<256>>,
+            pub proposal_cost: u64,
+            pub creation_block: u64,
+        }
+    }
+
+    impl<T: Config> old_storage::SubnetParams<T> {
+        /// Fills the params added since with their defaults.
+        pub fn migrate(self) -> pallet_subspace::SubnetParams<T> {
+            pallet_subspace::SubnetParams {
+                founder: self.founder,
+                founder_share: self.founder_share,
+                immunity_period: self.immunity_period,
+                incentive_ratio: self.incentive_ratio,
+                max_allowed_uids: self.max_allowed_uids,
+                max_allowed_weights: self.max_allowed_weights,
+                min_allowed_weights: self.min_allowed_weights,
+                max_weight_age: self.max_weight_age,
+                name: self.name,
+                metadata: self.metadata,
+                tempo: self.tempo,
+                maximum_set_weight_calls_per_epoch: self.maximum_set_weight_calls_per_epoch,
+                bonds_ma: self.bonds_ma,
+                module_burn_config: self.module_burn_config.migrate(),
+                min_validator_stake: self.min_validator_stake,
+                max_allowed_validators: self.max_allowed_validators,
+                governance_config: self.governance_config.migrate(),
+                use_weights_encryption: self.use_weights_encryption,
+                copier_margin: self.copier_margin,
+                max_encryption_period: self.max_encryption_period,
+                pruning_policy: PruningPolicy::default(),
+                registration_mode: RegistrationMode::default(),
+            }
+        }
+    }
+
+    impl<T: Config> old_storage::GlobalParams<T> {
+        /// Keeps the current values of the params added since, so executing the proposal
+        /// leaves them untouched.
+        pub fn migrate(self) -> pallet_subspace::GlobalParams<T> {
+            let max_fees = MaxFees::<T>::get();
+
+            pallet_subspace::GlobalParams {
+                max_name_length: self.max_name_length,
+                min_name_length: self.min_name_length,
+                max_allowed_subnets: self.max_allowed_subnets,
+                max_allowed_modules: self.max_allowed_modules,
+                max_registrations_per_block: self.max_registrations_per_block,
+                max_allowed_weights: self.max_allowed_weights,
+                ceiling_stake_delegation_fee: max_fees.stake_delegation_fee,
+                ceiling_validator_weight_fee: max_fees.validator_weight_fee,
+                floor_stake_delegation_fee: self.floor_stake_delegation_fee,
+                floor_validator_weight_fee: self.floor_validator_weight_fee,
+                floor_founder_share: self.floor_founder_share,
+                min_weight_stake: self.min_weight_stake,
+                curator: self.curator,
+                general_subnet_application_cost: self.general_subnet_application_cost,
+                subnet_immunity_period: self.subnet_immunity_period,
+                fee_announcement_period: FeeAnnouncementPeriod::<T>::get(),
+                governance_config: self.governance_config.migrate(),
+                kappa: self.kappa,
+                rho: self.rho,
+            }
+        }
+    }
+
+    impl<T: Config> old_storage::ProposalData<T> {
+        pub fn migrate(self) -> ProposalData<T> {
+            match self {
+                Self::GlobalCustom => ProposalData::GlobalCustom,
+                Self::GlobalParams(params) => ProposalData::GlobalParams(params.migrate()),
+                Self::SubnetCustom { subnet_id } => ProposalData::SubnetCustom { subnet_id },
+                Self::SubnetParams { subnet_id, params } => ProposalData::SubnetParams {
+                    subnet_id,
+                    params: params.migrate(),
+                },
+                Self::TransferDaoTreasury { account, amount } => {
+                    ProposalData::TransferDaoTreasury { account, amount }
+                }
+            }
+        }
+    }
+
+    /// Re-encodes the stored proposals, as the global and subnet params they embed gained the
+    /// fee ceilings, pruning policy, registration mode, registration cost and proposal
+    /// thresholds. Also counts the open proposals of each proposer.
+    pub struct MigrateToV6<T>(sp_std::marker::PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for MigrateToV6<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let on_chain_version = StorageVersion::get::<Pallet<T>>();
+            if on_chain_version != 5 {
+                log::info!("Storage v6 already updated");
+                return T::DbWeight::get().reads(1);
+            }
+
+            let mut translated = 0u64;
+            let mut open_proposals = BTreeMap::<T::AccountId, u32>::new();
+            Proposals::<T>::translate(|_, old: old_storage::Proposal<T>| {
+                translated = translated.saturating_add(1);
+                if matches!(old.status, ProposalStatus::Open { .. }) {
+                    let count = open_proposals.entry(old.proposer.clone()).or_default();
+                    *count = count.saturating_add(1);
+                }
+
+                Some(Proposal {
+                    id: old.id,
+                    proposer: old.proposer,
+                    expiration_block: old.expiration_block,
+                    data: old.data.migrate(),
+                    status: old.status,
+                    metadata: old.metadata,
+                    proposal_cost: old.proposal_cost,
+                    creation_block: old.creation_block,
+                })
+            });
+
+            let proposers = open_proposals.len() as u64;
+            for (proposer, count) in open_proposals {
+                OpenProposalCount::<T>::insert(proposer, count);
+            }
+
+            log::info!("Migrating storage to v6, re-encoded {translated} proposals");
+            StorageVersion::new(6).put::<Pallet<T>>();
+
+            T::DbWeight::get().reads_writes(
+                translated.saturating_add(1),
+                translated.saturating_add(proposers).saturating_add(1),
+            )
+        }
+    }
+}
+
+/// Testnet versioned its governance storage apart from mainnet, and sits at version 4 with the
+/// data mainnet had at version 2. This runs the mainnet steps since then in a single upgrade.
+pub mod testnet {
+    use frame_support::{traits::OnRuntimeUpgrade, weights::Weight};
+
+    use super::*;
+
+    pub struct MigrateToV5<T>(sp_std::marker::PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for MigrateToV5<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let on_chain_version = StorageVersion::get::<Pallet<T>>();
+            if on_chain_version != 4 {
+                log::info!("Testnet storage v5 already updated");
+                return T::DbWeight::get().reads(1);
+            }
+
+            let weight = v3::init_indexes::<T>()
+                .saturating_add(v4::translate_governance_configs::<T>())
+                .saturating_add(v5::migrate_curator::<T>());
+
+            log::info!("Migrating testnet storage to v5");
+            StorageVersion::new(5).put::<Pallet<T>>();
+
+            weight.saturating_add(T::DbWeight::get().reads_writes(1, 1))
+        }
+    }
+}
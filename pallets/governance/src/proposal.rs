@@ -1,4 +1,4 @@
-use crate::*;
+use crate::{dao::CuratorMembers, delegation::DelegationGraph, grant::GrantId, *};
 use frame_support::{
     dispatch::DispatchResult,
     ensure,
@@ -8,12 +8,16 @@ use frame_support::{
     BoundedBTreeMap, BoundedBTreeSet, BoundedVec, DebugNoBound,
 };
 use frame_system::ensure_signed;
+use pallet_subnet_emission_api::{SubnetConsensus, SubnetEmissionApi};
 use pallet_subspace::{
     Event as SubspaceEvent, GlobalParams, Pallet as PalletSubspace, SubnetParams, TotalStake,
 };
 use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
 use scale_info::TypeInfo;
-use sp_std::{collections::btree_set::BTreeSet, vec::Vec};
+use sp_std::{
+    collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+    vec::Vec,
+};
 use substrate_fixed::types::I92F36;
 
 pub type ProposalId = u64;
@@ -38,14 +42,22 @@ impl<T: Config> Proposal<T> {
         matches!(self.status, ProposalStatus::Open { .. })
     }
 
+    /// The block the proposal was closed at, if it's finished.
+    #[must_use]
+    pub fn finished_at(&self) -> Option<u64> {
+        match self.status {
+            ProposalStatus::Open { .. } => None,
+            ProposalStatus::Accepted { block, .. } | ProposalStatus::Refused { block, .. } => {
+                Some(block)
+            }
+            ProposalStatus::Expired => Some(self.expiration_block),
+        }
+    }
+
     /// Returns the subnet ID that this proposal impact.s
     #[must_use]
     pub fn subnet_id(&self) -> Option<u16> {
-        match &self.data {
-            ProposalData::SubnetParams { subnet_id, .. }
-            | ProposalData::SubnetCustom { subnet_id, .. } => Some(*subnet_id),
-            _ => None,
-        }
+        self.data.subnet_id()
     }
 
     /// Marks a proposal as accepted and overrides the storage value.
@@ -59,19 +71,18 @@ impl<T: Config> Proposal<T> {
         };
 
         Proposals::<T>::insert(self.id, &self);
+        ActiveProposals::<T>::remove(self.id);
+        Pallet::<T>::decrease_open_proposals(&self.proposer);
         Pallet::<T>::deposit_event(Event::ProposalAccepted(self.id));
 
+        self.settle_deposit(|policy| policy.accepted_refund);
+
         self.execute_proposal()?;
 
         Ok(())
     }
 
     fn execute_proposal(self) -> DispatchResult {
-        PalletSubspace::<T>::add_balance_to_account(
-            &self.proposer,
-            PalletSubspace::<T>::u64_to_balance(self.proposal_cost).unwrap(),
-        );
-
         match self.data {
             ProposalData::GlobalCustom | ProposalData::SubnetCustom { .. } => {
                 // No specific action needed for custom proposals
@@ -93,6 +104,53 @@ impl<T: Config> Proposal<T> {
                     amount,
                 )?;
             }
+            ProposalData::SubnetConsensusType {
+                subnet_id,
+                consensus,
+            } => {
+                <T as SubnetEmissionApi<T::AccountId>>::schedule_consensus_change(
+                    subnet_id, consensus,
+                )?;
+            }
+            ProposalData::CuratorCouncil {
+                members,
+                approval_threshold,
+            } => {
+                Pallet::<T>::set_curator_council(
+                    members.into_iter().collect(),
+                    approval_threshold,
+                )?;
+            }
+            ProposalData::TreasuryGrant {
+                recipient,
+                amount_per_period,
+                period_length,
+                periods,
+                start_block,
+            } => {
+                Pallet::<T>::create_treasury_grant(
+                    self.id,
+                    recipient,
+                    amount_per_period,
+                    period_length,
+                    periods,
+                    start_block,
+                );
+            }
+            ProposalData::CancelTreasuryGrant { grant_id } => {
+                Pallet::<T>::cancel_treasury_grant(grant_id)?;
+            }
+            ProposalData::SubnetAction { subnet_id, action } => {
+                // The founder may have taken the subnet back to authority mode since
+                ensure!(
+                    matches!(
+                        SubnetGovernanceConfig::<T>::get(subnet_id).vote_mode,
+                        VoteMode::Vote
+                    ),
+                    Error::<T>::NotVoteMode
+                );
+                action.execute(subnet_id)?;
+            }
         }
 
         Ok(())
@@ -109,8 +167,12 @@ impl<T: Config> Proposal<T> {
         };
 
         Proposals::<T>::insert(self.id, &self);
+        ActiveProposals::<T>::remove(self.id);
+        Pallet::<T>::decrease_open_proposals(&self.proposer);
         Pallet::<T>::deposit_event(Event::ProposalRefused(self.id));
 
+        self.settle_deposit(|policy| policy.refused_refund);
+
         Ok(())
     }
 
@@ -125,10 +187,35 @@ impl<T: Config> Proposal<T> {
         self.status = ProposalStatus::Expired;
 
         Proposals::<T>::insert(self.id, &self);
+        ActiveProposals::<T>::remove(self.id);
+        Pallet::<T>::decrease_open_proposals(&self.proposer);
         Pallet::<T>::deposit_event(Event::ProposalExpired(self.id));
 
+        self.settle_deposit(|policy| policy.expired_refund);
+
         Ok(())
     }
+
+    /// Gives the refunded part of the proposal cost back to the proposer, following the deposit
+    /// policy of the proposal's subnet, or the global one. The rest is either sent to the
+    /// treasury or burned.
+    fn settle_deposit(&self, refund: impl FnOnce(&DepositPolicy) -> Percent) {
+        let policy = Pallet::<T>::deposit_policy(self.subnet_id());
+
+        let refunded = refund(&policy).mul_floor(self.proposal_cost);
+        let forfeited = self.proposal_cost.saturating_sub(refunded);
+
+        if let Some(refunded) = PalletSubspace::<T>::u64_to_balance(refunded) {
+            PalletSubspace::<T>::add_balance_to_account(&self.proposer, refunded);
+        }
+
+        if let (ForfeitDestination::Treasury, Some(forfeited)) = (
+            policy.forfeit_destination,
+            PalletSubspace::<T>::u64_to_balance(forfeited),
+        ) {
+            PalletSubspace::<T>::add_balance_to_account(&DaoTreasuryAddress::<T>::get(), forfeited);
+        }
+    }
 }
 
 #[derive(Clone, DebugNoBound, TypeInfo, Decode, Encode, MaxEncodedLen, PartialEq, Eq)]
@@ -169,21 +256,135 @@ pub enum ProposalData<T: Config> {
         account: T::AccountId,
         amount: u64,
     },
+    /// Moves the subnet to another consensus type. Voted globally, as it changes where the
+    /// subnet's emission goes.
+    SubnetConsensusType {
+        subnet_id: SubnetId,
+        consensus: SubnetConsensus,
+    },
+    /// Replaces the curator council and the amount of its members that have to approve actions.
+    CuratorCouncil {
+        members: CuratorMembers<T::AccountId>,
+        approval_threshold: u32,
+    },
+    /// Pays `amount_per_period` from the treasury every `period_length` blocks, `periods` times,
+    /// starting at `start_block`.
+    TreasuryGrant {
+        recipient: T::AccountId,
+        amount_per_period: u64,
+        period_length: u64,
+        periods: u32,
+        start_block: u64,
+    },
+    /// Stops the remaining payouts of a treasury grant.
+    CancelTreasuryGrant {
+        grant_id: GrantId,
+    },
+    /// A single change to a subnet, voted by its stakeholders.
+    SubnetAction {
+        subnet_id: SubnetId,
+        action: SubnetAction<T>,
+    },
 }
 
 impl<T: Config> ProposalData<T> {
-    /// The required amount of stake each of the proposal types requires in order to pass.
+    /// Returns the subnet ID that this proposal impacts.
     #[must_use]
-    pub fn required_stake(&self) -> Percent {
+    pub fn subnet_id(&self) -> Option<u16> {
         match self {
-            Self::GlobalCustom | Self::SubnetCustom { .. } | Self::TransferDaoTreasury { .. } => {
-                Percent::from_parts(50)
-            }
-            Self::GlobalParams(_) | Self::SubnetParams { .. } => Percent::from_parts(40),
+            Self::SubnetParams { subnet_id, .. }
+            | Self::SubnetCustom { subnet_id, .. }
+            | Self::SubnetAction { subnet_id, .. } => Some(*subnet_id),
+            _ => None,
+        }
+    }
+
+    /// The quorum and approval the proposal type requires in order to pass.
+    #[must_use]
+    pub fn thresholds(&self, config: &GovernanceConfiguration) -> ProposalThresholds {
+        match self {
+            Self::GlobalCustom | Self::SubnetCustom { .. } => config.custom_thresholds,
+            Self::GlobalParams(_)
+            | Self::SubnetParams { .. }
+            | Self::CuratorCouncil { .. }
+            | Self::SubnetAction { .. } => config.params_thresholds,
+            Self::TransferDaoTreasury { .. }
+            | Self::TreasuryGrant { .. }
+            | Self::CancelTreasuryGrant { .. } => config.treasury_thresholds,
+            Self::SubnetConsensusType { .. } => config.consensus_thresholds,
+        }
+    }
+}
+
+/// The founder actions a subnet in vote mode can take through its proposals, without replacing
+/// all of its params.
+#[derive(Clone, DebugNoBound, TypeInfo, Decode, Encode, MaxEncodedLen, PartialEq, Eq)]
+#[scale_info(skip_type_params(T))]
+pub enum SubnetAction<T: Config> {
+    /// Hands the subnet over to another founder.
+    ReplaceFounder(T::AccountId),
+    /// Changes the share of the subnet emission going to the founder.
+    SetFounderShare(u16),
+    /// Replaces the burn configuration of module registrations.
+    SetModuleBurnConfig(GeneralBurnConfiguration<T>),
+    /// Turns weight encryption on or off.
+    SetUseWeightsEncryption(bool),
+    /// Removes a misbehaving module from the subnet, forfeiting its registration deposit.
+    DeregisterModule(T::AccountId),
+}
+
+impl<T: Config> SubnetAction<T> {
+    /// The subnet params after the action, validated against the subnet as it is now. Module
+    /// deregistrations don't change the params.
+    fn changeset(&self, subnet_id: SubnetId) -> Result<Option<SubnetChangeset<T>>, DispatchError> {
+        let mut params = PalletSubspace::<T>::subnet_params(subnet_id);
+        match self {
+            Self::ReplaceFounder(founder) => params.founder = founder.clone(),
+            Self::SetFounderShare(founder_share) => params.founder_share = *founder_share,
+            Self::SetModuleBurnConfig(config) => params.module_burn_config = config.clone(),
+            Self::SetUseWeightsEncryption(enabled) => params.use_weights_encryption = *enabled,
+            Self::DeregisterModule(_) => return Ok(None),
+        }
+
+        SubnetChangeset::<T>::update(subnet_id, params).map(Some)
+    }
+
+    fn module_uid(key: &T::AccountId, subnet_id: SubnetId) -> Result<u16, DispatchError> {
+        PalletSubspace::<T>::get_uid_for_key(subnet_id, key)
+            .ok_or_else(|| pallet_subspace::Error::<T>::ModuleDoesNotExist.into())
+    }
+
+    /// Checks the action can be taken on the subnet.
+    fn validate(&self, subnet_id: SubnetId) -> DispatchResult {
+        if let Self::DeregisterModule(key) = self {
+            Self::module_uid(key, subnet_id)?;
+        } else {
+            self.changeset(subnet_id)?;
+        }
+
+        Ok(())
+    }
+
+    fn execute(self, subnet_id: SubnetId) -> DispatchResult {
+        if let Self::DeregisterModule(key) = self {
+            let uid = Self::module_uid(&key, subnet_id)?;
+            PalletSubspace::<T>::forfeit_registration_deposit(subnet_id, &key)?;
+            // The subnet stays around even if the module was its last one.
+            PalletSubspace::<T>::remove_module(subnet_id, uid, false)?;
+            PalletSubspace::<T>::deposit_event(SubspaceEvent::ModuleDeregistered(
+                subnet_id, uid, key,
+            ));
+        } else if let Some(changeset) = self.changeset(subnet_id)? {
+            changeset.apply(subnet_id)?;
+            PalletSubspace::<T>::deposit_event(SubspaceEvent::SubnetParamsUpdated(subnet_id));
         }
+
+        Ok(())
     }
 }
 
+/// The stake of each account that took part in a finished proposal, waiting for rewards. Accounts
+/// that delegated their votes are listed next to the voter that represented them.
 #[derive(DebugNoBound, TypeInfo, Decode, Encode, MaxEncodedLen, PartialEq, Eq)]
 #[scale_info(skip_type_params(T))]
 pub struct UnrewardedProposal<T: Config> {
@@ -195,12 +396,25 @@ pub struct UnrewardedProposal<T: Config> {
 
 impl<T: Config> Pallet<T> {
     fn get_next_proposal_id() -> u64 {
-        match Proposals::<T>::iter_keys().max() {
-            Some(id) => id.saturating_add(1),
-            None => 0,
+        let id = NextProposalId::<T>::get();
+        NextProposalId::<T>::set(id.saturating_add(1));
+        id
+    }
+
+    /// The deposit policy of proposals on the subnet, or the global one.
+    fn deposit_policy(subnet_id: Option<SubnetId>) -> DepositPolicy {
+        match subnet_id {
+            Some(subnet_id) => SubnetGovernanceConfig::<T>::get(subnet_id).deposit_policy,
+            None => GlobalGovernanceConfig::<T>::get().deposit_policy,
         }
     }
 
+    fn decrease_open_proposals(proposer: &T::AccountId) {
+        OpenProposalCount::<T>::mutate_exists(proposer, |count| {
+            *count = count.map(|count| count.saturating_sub(1)).filter(|count| *count > 0);
+        });
+    }
+
     pub fn add_proposal(
         key: T::AccountId,
         metadata: BoundedVec<u8, ConstU32<256>>,
@@ -217,6 +431,12 @@ impl<T: Config> Pallet<T> {
             Error::<T>::NotEnoughBalanceToPropose
         );
 
+        let deposit_policy = Self::deposit_policy(data.subnet_id());
+        ensure!(
+            OpenProposalCount::<T>::get(&key) < deposit_policy.max_open_proposals_per_account,
+            Error::<T>::TooManyOpenProposals
+        );
+
         let Some(removed_balance_as_currency) = PalletSubspace::<T>::u64_to_balance(proposal_cost)
         else {
             return Err(Error::<T>::InvalidCurrencyConversionValue.into());
@@ -255,6 +475,8 @@ impl<T: Config> Pallet<T> {
         PalletSubspace::<T>::remove_balance_from_account(&key, removed_balance_as_currency)?;
 
         Proposals::<T>::insert(proposal_id, proposal);
+        ActiveProposals::<T>::insert(proposal_id, ());
+        OpenProposalCount::<T>::mutate(&key, |count| *count = count.saturating_add(1));
 
         Self::deposit_event(Event::<T>::ProposalCreated(proposal_id));
         Ok(())
@@ -354,25 +576,203 @@ impl<T: Config> Pallet<T> {
         let proposal_data = ProposalData::SubnetParams { subnet_id, params };
         Self::add_proposal(key, BoundedVec::truncate_from(data), proposal_data)
     }
+
+    pub fn do_add_subnet_consensus_proposal(
+        origin: T::RuntimeOrigin,
+        subnet_id: u16,
+        data: Vec<u8>,
+        consensus: SubnetConsensus,
+    ) -> DispatchResult {
+        let key = ensure_signed(origin)?;
+
+        ensure!(!data.is_empty(), Error::<T>::ProposalDataTooSmall);
+        ensure!(data.len() <= 256, Error::<T>::ProposalDataTooLarge);
+
+        <T as SubnetEmissionApi<T::AccountId>>::validate_consensus_change(subnet_id, consensus)?;
+
+        let proposal_data = ProposalData::SubnetConsensusType {
+            subnet_id,
+            consensus,
+        };
+        Self::add_proposal(key, BoundedVec::truncate_from(data), proposal_data)
+    }
+
+    pub fn do_add_curator_council_proposal(
+        origin: T::RuntimeOrigin,
+        data: Vec<u8>,
+        members: Vec<T::AccountId>,
+        approval_threshold: u32,
+    ) -> DispatchResult {
+        let key = ensure_signed(origin)?;
+
+        ensure!(!data.is_empty(), Error::<T>::ProposalDataTooSmall);
+        ensure!(data.len() <= 256, Error::<T>::ProposalDataTooLarge);
+
+        let members = Self::validate_curator_council(members, approval_threshold)?;
+
+        let proposal_data = ProposalData::CuratorCouncil {
+            members,
+            approval_threshold,
+        };
+        Self::add_proposal(key, BoundedVec::truncate_from(data), proposal_data)
+    }
+
+    pub fn do_add_treasury_grant_proposal(
+        origin: T::RuntimeOrigin,
+        data: Vec<u8>,
+        recipient: T::AccountId,
+        amount_per_period: u64,
+        period_length: u64,
+        periods: u32,
+        start_block: u64,
+    ) -> DispatchResult {
+        let key = ensure_signed(origin)?;
+        ensure!(!data.is_empty(), Error::<T>::ProposalDataTooSmall);
+        ensure!(data.len() <= 256, Error::<T>::ProposalDataTooLarge);
+        sp_std::str::from_utf8(&data).map_err(|_| Error::<T>::InvalidProposalData)?;
+
+        Self::validate_treasury_grant(amount_per_period, period_length, periods)?;
+
+        let proposal_data = ProposalData::TreasuryGrant {
+            recipient,
+            amount_per_period,
+            period_length,
+            periods,
+            start_block,
+        };
+        Self::add_proposal(key, BoundedVec::truncate_from(data), proposal_data)
+    }
+
+    pub fn do_add_cancel_treasury_grant_proposal(
+        origin: T::RuntimeOrigin,
+        data: Vec<u8>,
+        grant_id: GrantId,
+    ) -> DispatchResult {
+        let key = ensure_signed(origin)?;
+        ensure!(!data.is_empty(), Error::<T>::ProposalDataTooSmall);
+        ensure!(data.len() <= 256, Error::<T>::ProposalDataTooLarge);
+        sp_std::str::from_utf8(&data).map_err(|_| Error::<T>::InvalidProposalData)?;
+
+        ensure!(
+            TreasuryGrants::<T>::contains_key(grant_id),
+            Error::<T>::TreasuryGrantNotFound
+        );
+
+        let proposal_data = ProposalData::CancelTreasuryGrant { grant_id };
+        Self::add_proposal(key, BoundedVec::truncate_from(data), proposal_data)
+    }
+
+    pub fn do_add_subnet_action_proposal(
+        origin: T::RuntimeOrigin,
+        subnet_id: u16,
+        data: Vec<u8>,
+        action: SubnetAction<T>,
+    ) -> DispatchResult {
+        let key = ensure_signed(origin)?;
+
+        ensure!(
+            pallet_subspace::N::<T>::contains_key(subnet_id),
+            pallet_subspace::Error::<T>::NetworkDoesNotExist
+        );
+        ensure!(
+            matches!(
+                SubnetGovernanceConfig::<T>::get(subnet_id).vote_mode,
+                VoteMode::Vote
+            ),
+            Error::<T>::NotVoteMode
+        );
+
+        ensure!(!data.is_empty(), Error::<T>::ProposalDataTooSmall);
+        ensure!(data.len() <= 256, Error::<T>::ProposalDataTooLarge);
+
+        action.validate(subnet_id)?;
+
+        let proposal_data = ProposalData::SubnetAction { subnet_id, action };
+        Self::add_proposal(key, BoundedVec::truncate_from(data), proposal_data)
+    }
 }
 
-pub fn tick_proposals<T: Config>(block_number: u64) {
-    let not_delegating = NotDelegatingVotingPower::<T>::get().into_inner();
+/// The proposals that are still open.
+fn active_proposals<T: Config>() -> Vec<(ProposalId, Proposal<T>)> {
+    if Pallet::<T>::indexes_migrating() {
+        return Proposals::<T>::iter().filter(|(_, p)| p.is_active()).collect();
+    }
 
-    let proposals = Proposals::<T>::iter().filter(|(_, p)| p.is_active());
+    ActiveProposals::<T>::iter_keys()
+        .filter_map(|id| Proposals::<T>::get(id).map(|proposal| (id, proposal)))
+        .collect()
+}
 
+pub fn tick_proposals<T: Config>(block_number: u64) {
     if block_number % 100 != 0 {
         return;
     }
 
-    for (id, proposal) in proposals {
-        let res = with_storage_layer(|| tick_proposal(&not_delegating, block_number, proposal));
+    let not_delegating = NotDelegatingVotingPower::<T>::get().into_inner();
+    let mut voting_powers = BTreeMap::new();
+
+    for (id, proposal) in active_proposals::<T>() {
+        let subnet_id = proposal.subnet_id();
+        let voting_power = voting_powers
+            .entry(subnet_id)
+            .or_insert_with(|| VotingPower::<T>::build(&not_delegating, subnet_id));
+
+        let res = with_storage_layer(|| {
+            tick_proposal(&not_delegating, voting_power, block_number, proposal)
+        });
         if let Err(err) = res {
             log::error!("failed to tick proposal {id}: {err:?}, skipping...");
         }
     }
 }
 
+/// How many proposals can be pruned in a single tick.
+const MAX_PRUNED_PROPOSALS: u32 = 32;
+
+/// Removes finished proposals older than the retention period, walking ids in creation order.
+/// Stops at the first proposal that has to be kept: still open, recently finished or with
+/// pending rewards.
+pub fn prune_proposals<T: Config>(block_number: u64) {
+    if block_number % 100 != 0 || Pallet::<T>::indexes_migrating() {
+        return;
+    }
+
+    let retention = ProposalRetentionPeriod::<T>::get();
+    let next_id = NextProposalId::<T>::get();
+    let mut cursor = ProposalPruneCursor::<T>::get();
+
+    for _ in 0..MAX_PRUNED_PROPOSALS {
+        if cursor >= next_id {
+            break;
+        }
+
+        if let Some(proposal) = Proposals::<T>::get(cursor) {
+            let Some(finished_at) = proposal.finished_at() else {
+                break;
+            };
+            if finished_at.saturating_add(retention) > block_number {
+                break;
+            }
+
+            if let Some(unrewarded) = UnrewardedProposals::<T>::get(cursor) {
+                // Rewards of removed subnets are never ticked again.
+                match unrewarded.subnet_id {
+                    Some(subnet_id) if !pallet_subspace::N::<T>::contains_key(subnet_id) => {
+                        UnrewardedProposals::<T>::remove(cursor);
+                    }
+                    _ => break,
+                }
+            }
+
+            Proposals::<T>::remove(cursor);
+        }
+
+        cursor = cursor.saturating_add(1);
+    }
+
+    ProposalPruneCursor::<T>::set(cursor);
+}
+
 pub fn get_minimal_stake_to_execute_with_percentage<T: Config>(
     threshold: Percent,
     subnet_id: Option<u16>,
@@ -392,6 +792,7 @@ pub fn get_minimal_stake_to_execute_with_percentage<T: Config>(
 
 fn tick_proposal<T: Config>(
     not_delegating: &BTreeSet<T::AccountId>,
+    voting_power: &VotingPower<T>,
     block_number: u64,
     mut proposal: Proposal<T>,
 ) -> DispatchResult {
@@ -406,22 +807,8 @@ fn tick_proposal<T: Config>(
         return Err(Error::<T>::ProposalIsFinished.into());
     };
 
-    let votes_for: Vec<(T::AccountId, u64)> = votes_for
-        .iter()
-        .cloned()
-        .map(|id| {
-            let stake = calc_stake::<T>(not_delegating, &id);
-            (id, stake)
-        })
-        .collect();
-    let votes_against: Vec<(T::AccountId, u64)> = votes_against
-        .iter()
-        .cloned()
-        .map(|id| {
-            let stake = calc_stake::<T>(not_delegating, &id);
-            (id, stake)
-        })
-        .collect();
+    let votes_for = tally_votes::<T>(not_delegating, voting_power, votes_for);
+    let votes_against = tally_votes::<T>(not_delegating, voting_power, votes_against);
 
     let stake_for_sum: u64 = votes_for.iter().map(|(_, stake)| stake).sum();
     let stake_against_sum: u64 = votes_against.iter().map(|(_, stake)| stake).sum();
@@ -440,11 +827,15 @@ fn tick_proposal<T: Config>(
         return Ok(());
     }
 
+    let governance_config = match subnet_id {
+        Some(subnet_id) => SubnetGovernanceConfig::<T>::get(subnet_id),
+        None => GlobalGovernanceConfig::<T>::get(),
+    };
+    let thresholds = proposal.data.thresholds(&governance_config);
+
     let total_stake = stake_for_sum.saturating_add(stake_against_sum);
-    let minimal_stake_to_execute = get_minimal_stake_to_execute_with_percentage::<T>(
-        proposal.data.required_stake(),
-        subnet_id,
-    );
+    let minimal_stake_to_execute =
+        get_minimal_stake_to_execute_with_percentage::<T>(thresholds.quorum, subnet_id);
 
     let mut reward_votes_for = BoundedBTreeMap::new();
     for (key, value) in votes_for {
@@ -470,16 +861,23 @@ fn tick_proposal<T: Config>(
     );
 
     if total_stake >= minimal_stake_to_execute {
-        if stake_against_sum > stake_for_sum {
-            proposal.refuse(block_number, stake_for_sum, stake_against_sum)
-        } else {
+        if is_approved(thresholds.approval, stake_for_sum, total_stake) {
             proposal.accept(block_number, stake_for_sum, stake_against_sum)
+        } else {
+            proposal.refuse(block_number, stake_for_sum, stake_against_sum)
         }
     } else {
         proposal.expire(block_number)
     }
 }
 
+/// Whether the stake in favor reaches the approval share of the voting stake.
+fn is_approved(approval: Percent, stake_for: u64, total_stake: u64) -> bool {
+    let stake_for = u128::from(stake_for).saturating_mul(100);
+    let required = u128::from(total_stake).saturating_mul(u128::from(approval.deconstruct()));
+    stake_for >= required
+}
+
 pub fn tick_proposal_rewards<T: Config>(block_number: u64) {
     let mut to_tick: Vec<_> = pallet_subspace::N::<T>::iter_keys()
         .map(|subnet_id| (Some(subnet_id), SubnetGovernanceConfig::<T>::get(subnet_id)))
@@ -491,19 +889,90 @@ pub fn tick_proposal_rewards<T: Config>(block_number: u64) {
     });
 }
 
+/// The stake behind each voter of one side. Accounts that delegated their votes to a voter are
+/// listed on their own, so proposal rewards go to whoever owns the stake.
+fn tally_votes<T: Config>(
+    not_delegating: &BTreeSet<T::AccountId>,
+    voting_power: &VotingPower<T>,
+    voters: &BoundedBTreeSet<T::AccountId, ConstU32<{ u32::MAX }>>,
+) -> Vec<(T::AccountId, u64)> {
+    let delegations = &voting_power.delegations;
+    let mut stakes = Vec::new();
+
+    // Voters who delegated after voting are represented by their delegatee.
+    for voter in voters.iter().filter(|voter| !delegations.is_delegating(voter)) {
+        let stake = calc_stake::<T>(not_delegating, voting_power, voter);
+        stakes.push((voter.clone(), stake));
+
+        for delegator in delegations.delegators_of(voter) {
+            let stake = Pallet::<T>::own_voting_stake(&delegator);
+            stakes.push((delegator, stake));
+        }
+    }
+
+    stakes
+}
+
+/// Who votes on the proposals of a subnet, or on the global ones. Built once per tick and
+/// shared by the proposals it covers.
+struct VotingPower<T: Config> {
+    delegations: DelegationGraph<T>,
+    /// The shares of each pool owned by members who vote themselves or delegated their votes
+    /// to another account.
+    pool_voting_shares: BTreeMap<u32, u64>,
+}
+
+impl<T: Config> VotingPower<T> {
+    fn build(not_delegating: &BTreeSet<T::AccountId>, subnet_id: Option<u16>) -> Self {
+        let delegations = DelegationGraph::<T>::build(subnet_id);
+
+        let mut pool_voting_shares = BTreeMap::<u32, u64>::new();
+        let voting_members = not_delegating.iter().chain(
+            delegations
+                .delegating_accounts()
+                .filter(|member| !not_delegating.contains(*member)),
+        );
+        for member in voting_members {
+            for (pool_id, membership) in pallet_subspace::PoolMembers::<T>::iter_prefix(member) {
+                let shares = pool_voting_shares.entry(pool_id).or_default();
+                *shares = shares.saturating_add(membership.shares);
+            }
+        }
+
+        Self {
+            delegations,
+            pool_voting_shares,
+        }
+    }
+}
+
 #[inline]
-fn calc_stake<T: Config>(not_delegating: &BTreeSet<T::AccountId>, voter: &T::AccountId) -> u64 {
+fn calc_stake<T: Config>(
+    not_delegating: &BTreeSet<T::AccountId>,
+    voting_power: &VotingPower<T>,
+    voter: &T::AccountId,
+) -> u64 {
+    let delegations = &voting_power.delegations;
     let own_stake = if !not_delegating.contains(voter) {
         0
     } else {
-        pallet_subspace::Pallet::<T>::get_owned_stake(voter)
+        Pallet::<T>::own_voting_stake(voter)
     };
 
     let calculate_delegated = || -> u64 {
         PalletSubspace::<T>::get_stake_from_vector(voter)
             .into_iter()
-            .filter(|(staker, _)| !not_delegating.contains(staker))
-            .map(|(_, stake)| stake)
+            .filter_map(
+                |(staker, stake)| match PalletSubspace::<T>::get_pool_of_account(&staker) {
+                    Some(pool_id) => {
+                        Some(calc_pool_delegated_stake::<T>(voting_power, pool_id, stake))
+                    }
+                    None if not_delegating.contains(&staker) => None,
+                    // Their stake is counted by the account they delegated their votes to.
+                    None if delegations.is_delegating(&staker) => None,
+                    None => Some(stake),
+                },
+            )
             .sum()
     };
 
@@ -512,6 +981,28 @@ fn calc_stake<T: Config>(not_delegating: &BTreeSet<T::AccountId>, voter: &T::Acc
     own_stake.saturating_add(delegated_stake)
 }
 
+/// Pools vote through their members, so only the part of the pool stake owned by members who
+/// neither vote themselves nor delegated their votes to another account is delegated to the
+/// module.
+fn calc_pool_delegated_stake<T: Config>(
+    voting_power: &VotingPower<T>,
+    pool_id: u32,
+    stake: u64,
+) -> u64 {
+    let Some(pool) = pallet_subspace::NominationPools::<T>::get(pool_id) else {
+        return 0;
+    };
+
+    let voting_shares = voting_power.pool_voting_shares.get(&pool_id).copied().unwrap_or_default();
+    let delegating_shares = pool.total_shares.saturating_sub(voting_shares);
+
+    u128::from(stake)
+        .checked_mul(u128::from(delegating_shares))
+        .and_then(|product| product.checked_div(u128::from(pool.total_shares)))
+        .and_then(|result| u64::try_from(result).ok())
+        .unwrap_or_default()
+}
+
 pub fn execute_proposal_rewards<T: Config>(
     block_number: u64,
     subnet_id: Option<u16>,
@@ -539,6 +1030,8 @@ pub fn execute_proposal_rewards<T: Config>(
             continue;
         }
 
+        // Accounts that delegated their votes are rewarded for their own stake, not through
+        // their representative.
         for (acc_id, stake) in unrewarded_proposal
             .votes_for
             .into_iter()
@@ -0,0 +1,210 @@
+use crate::*;
+use sp_runtime::TryRuntimeError;
+use sp_std::collections::btree_map::BTreeMap;
+
+impl<T: Config> Pallet<T> {
+    /// Checks the storage invariants of the pallet. Ran by `try_state` after every block in
+    /// try-runtime checks and in tests.
+    pub(crate) fn do_try_state() -> Result<(), TryRuntimeError> {
+        Self::check_proposals()?;
+        Self::check_unrewarded_proposals()?;
+        Self::check_curator_applications()?;
+        Self::check_indexes()?;
+        Self::check_curator_council()?;
+        Self::check_treasury_grants()?;
+
+        ensure!(
+            SubnetGovernanceConfig::<T>::iter_keys().all(pallet_subspace::N::<T>::contains_key),
+            "SubnetGovernanceConfig of a removed subnet"
+        );
+        ensure!(
+            VoteDelegations::<T>::iter().all(|(delegator, delegatee)| delegator != delegatee),
+            "account delegating votes to itself"
+        );
+        ensure!(
+            SubnetVoteDelegations::<T>::iter().all(|(subnet_id, delegator, delegatee)| {
+                delegator != delegatee && pallet_subspace::N::<T>::contains_key(subnet_id)
+            }),
+            "invalid subnet vote delegation"
+        );
+        ensure!(
+            VoteDelegators::<T>::iter_keys().count() == VoteDelegations::<T>::iter_keys().count()
+                && VoteDelegations::<T>::iter().all(|(delegator, delegatee)| {
+                    VoteDelegators::<T>::contains_key(delegatee, delegator)
+                }),
+            "VoteDelegators out of sync with VoteDelegations"
+        );
+        let mut subnet_delegators = BTreeMap::<_, u32>::new();
+        for (subnet_id, _, delegatee) in SubnetVoteDelegations::<T>::iter() {
+            let count = subnet_delegators.entry((subnet_id, delegatee)).or_default();
+            *count = count.saturating_add(1);
+        }
+        ensure!(
+            SubnetVoteDelegatorCount::<T>::iter()
+                .map(|(subnet_id, delegatee, count)| ((subnet_id, delegatee), count))
+                .collect::<BTreeMap<_, _>>()
+                == subnet_delegators,
+            "SubnetVoteDelegatorCount out of sync with SubnetVoteDelegations"
+        );
+
+        Ok(())
+    }
+
+    /// Proposals are stored under their own id, and nobody votes both ways.
+    fn check_proposals() -> Result<(), TryRuntimeError> {
+        let next_id = NextProposalId::<T>::get();
+        for (id, proposal) in Proposals::<T>::iter() {
+            ensure!(proposal.id == id, "proposal stored under another id");
+            ensure!(id < next_id, "proposal id wasn't given by the counter");
+            ensure!(
+                proposal.creation_block <= proposal.expiration_block,
+                "proposal expires before being created"
+            );
+
+            if let ProposalStatus::Open {
+                votes_for,
+                votes_against,
+                ..
+            } = &proposal.status
+            {
+                ensure!(
+                    votes_for.iter().all(|voter| !votes_against.contains(voter)),
+                    "open proposal has a voter on both sides"
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Rewards are only pending for proposals that have been closed.
+    fn check_unrewarded_proposals() -> Result<(), TryRuntimeError> {
+        for (id, unrewarded) in UnrewardedProposals::<T>::iter() {
+            let Some(proposal) = Proposals::<T>::get(id) else {
+                return Err("unrewarded proposal doesn't exist".into());
+            };
+            ensure!(!proposal.is_active(), "unrewarded proposal is still open");
+            ensure!(
+                unrewarded
+                    .votes_for
+                    .keys()
+                    .all(|voter| !unrewarded.votes_against.contains_key(voter)),
+                "unrewarded proposal has a voter on both sides"
+            );
+        }
+
+        Ok(())
+    }
+
+    fn check_curator_applications() -> Result<(), TryRuntimeError> {
+        ensure!(
+            CuratorApplications::<T>::iter().all(|(id, application)| application.id == id),
+            "curator application stored under another id"
+        );
+        ensure!(
+            CuratorApplications::<T>::iter_keys().all(|id| id < NextApplicationId::<T>::get()),
+            "curator application id wasn't given by the counter"
+        );
+
+        Ok(())
+    }
+
+    /// The indexes mirror the storages they index, once the migration populating them is done.
+    fn check_indexes() -> Result<(), TryRuntimeError> {
+        if Self::indexes_migrating() {
+            return Ok(());
+        }
+
+        for (id, proposal) in Proposals::<T>::iter() {
+            ensure!(
+                proposal.is_active() == ActiveProposals::<T>::contains_key(id),
+                "active proposal index out of sync"
+            );
+        }
+        ensure!(
+            ActiveProposals::<T>::iter_keys().all(Proposals::<T>::contains_key),
+            "active proposal index has a missing proposal"
+        );
+
+        let mut open_proposals = BTreeMap::<T::AccountId, u32>::new();
+        for (_, proposal) in Proposals::<T>::iter().filter(|(_, p)| p.is_active()) {
+            let count = open_proposals.entry(proposal.proposer).or_default();
+            *count = count.saturating_add(1);
+        }
+        ensure!(
+            OpenProposalCount::<T>::iter().collect::<BTreeMap<_, _>>() == open_proposals,
+            "open proposal count out of sync"
+        );
+
+        for (id, application) in CuratorApplications::<T>::iter() {
+            ensure!(
+                CuratorApplicationsByUser::<T>::get(&application.user_id) == Some(id),
+                "curator application index out of sync"
+            );
+        }
+        ensure!(
+            CuratorApplicationsByUser::<T>::iter_values()
+                .all(CuratorApplications::<T>::contains_key),
+            "curator application index has a missing application"
+        );
+
+        Ok(())
+    }
+
+    /// The council can reach its threshold, only its members have pending approvals, and pending
+    /// approvals are indexed by the block they expire at.
+    fn check_curator_council() -> Result<(), TryRuntimeError> {
+        let council = CuratorCouncil::<T>::get();
+        let threshold = CuratorApprovalThreshold::<T>::get();
+        ensure!(
+            council.is_empty() || (threshold > 0 && threshold as usize <= council.len()),
+            "curator council can't reach its approval threshold"
+        );
+        ensure!(
+            CuratorApprovals::<T>::iter_values()
+                .all(|approval| approval.curators.iter().all(|curator| council.contains(curator))),
+            "curator action approved by a non-member"
+        );
+        ensure!(
+            CuratorApprovals::<T>::iter().all(|(action, approval)| {
+                CuratorApprovalExpiries::<T>::contains_key(approval.expires_at, action)
+            }),
+            "curator approvals without an expiry"
+        );
+        ensure!(
+            CuratorApprovalExpiries::<T>::iter().all(|(expires_at, action, ())| {
+                CuratorApprovals::<T>::get(action)
+                    .is_some_and(|approval| approval.expires_at == expires_at)
+            }),
+            "curator approval expiry without approvals"
+        );
+
+        Ok(())
+    }
+
+    /// Grants are stored under their own id and are removed once every period is paid.
+    fn check_treasury_grants() -> Result<(), TryRuntimeError> {
+        let next_id = NextTreasuryGrantId::<T>::get();
+        let current_block = pallet_subspace::Pallet::<T>::get_current_block_number();
+        let scheduled: BTreeMap<GrantId, u64> =
+            TreasuryGrantPayouts::<T>::iter().map(|(block, id, ())| (id, block)).collect();
+
+        for (id, grant) in TreasuryGrants::<T>::iter() {
+            ensure!(
+                scheduled.get(&id).is_some_and(|block| *block > current_block),
+                "treasury grant isn't scheduled for a payout"
+            );
+            ensure!(grant.id == id, "treasury grant stored under another id");
+            ensure!(
+                id < next_id,
+                "treasury grant id wasn't given by the counter"
+            );
+            ensure!(
+                grant.paid_periods < grant.periods,
+                "finished treasury grant wasn't removed"
+            );
+        }
+
+        Ok(())
+    }
+}
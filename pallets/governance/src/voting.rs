@@ -14,6 +14,7 @@ impl<T: Config> Pallet<T> {
         let Ok(mut proposal) = Proposals::<T>::try_get(proposal_id) else {
             return Err(Error::<T>::ProposalNotFound.into());
         };
+        let subnet_id = proposal.subnet_id();
 
         let ProposalStatus::Open {
             votes_for,
@@ -29,15 +30,26 @@ impl<T: Config> Pallet<T> {
             Error::<T>::AlreadyVoted
         );
 
+        ensure!(
+            Self::vote_delegation(&key, subnet_id).is_none(),
+            Error::<T>::VotesDelegatedToAccount
+        );
+
         let voter_delegated_stake = pallet_subspace::Pallet::<T>::get_delegated_stake(&key);
-        let voter_owned_stake = pallet_subspace::Pallet::<T>::get_owned_stake(&key);
+        let voter_owned_stake = Self::own_voting_stake(&key);
+        // Accounts others delegated their votes to can vote without stake of their own.
+        let represents_delegators =
+            voter_delegated_stake == 0 && Self::has_vote_delegators(&key, subnet_id);
 
         ensure!(
-            voter_delegated_stake > 0 || voter_owned_stake > 0,
+            voter_delegated_stake > 0 || voter_owned_stake > 0 || represents_delegators,
             Error::<T>::InsufficientStake
         );
 
-        if !NotDelegatingVotingPower::<T>::get().contains(&key) && voter_delegated_stake == 0 {
+        if !NotDelegatingVotingPower::<T>::get().contains(&key)
+            && voter_delegated_stake == 0
+            && !represents_delegators
+        {
             return Err(Error::<T>::VoterIsDelegatingVotingPower.into());
         }
 
@@ -17,6 +17,7 @@ pub mod dispatches {
             let DecryptedWeightsPayload {
                 subnet_id,
                 decrypted_weights,
+                decryption_shares,
                 delta: _,
                 block_number,
                 public,
@@ -31,6 +32,21 @@ pub mod dispatches {
 
             let acc_id = public.into_account();
 
+            if SubnetDecryptionCommittee::<T>::contains_key(subnet_id) {
+                pallet_subnet_emission::Pallet::<T>::handle_decryption_shares(
+                    subnet_id,
+                    &acc_id,
+                    decryption_shares,
+                )?;
+
+                Self::deposit_event(Event::DecryptionSharesSent {
+                    subnet_id,
+                    block_number,
+                    worker: acc_id,
+                });
+                return Ok(().into());
+            }
+
             // Modify this section to handle the forced rotation case
             SubnetDecryptionData::<T>::try_mutate(subnet_id, |maybe_data| -> DispatchResult {
                 let decryption_data = maybe_data.as_mut().ok_or(Error::<T>::InvalidSubnetId)?;
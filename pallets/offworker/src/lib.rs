@@ -21,8 +21,8 @@ use pallet_subnet_emission::{
         },
         yuma::YumaEpoch,
     },
-    types::{BlockWeights, PublicKey},
-    Authorities, DecryptionNodeBanQueue, SubnetDecryptionData,
+    types::{BlockDecryptionShares, BlockWeights, PublicKey},
+    Authorities, DecryptionNodeBanQueue, SubnetDecryptionCommittee, SubnetDecryptionData,
 };
 
 use sp_std::collections::btree_map::BTreeMap;
@@ -51,6 +51,7 @@ mod dispatches;
 pub mod migrations;
 mod process;
 mod profitability;
+mod threshold;
 pub mod types;
 mod util;
 
@@ -123,7 +124,15 @@ pub mod pallet {
                 Call::send_decrypted_weights { payload, signature } => {
                     let block_number = payload.block_number;
                     Self::validate_signature_and_authority(&block_number, payload, signature)?;
-                    Self::validate_unsigned_transaction(&block_number, "DecryptedWeights")
+                    if payload.decryption_shares.is_empty() {
+                        Self::validate_unsigned_transaction(&block_number, "DecryptedWeights")
+                    } else {
+                        // Every committee member sends its shares in the same block
+                        Self::validate_unsigned_transaction(
+                            &(block_number, payload.subnet_id, payload.public.clone()),
+                            "DecryptionShares",
+                        )
+                    }
                 }
                 Call::send_ping { payload, signature } => {
                     let block_number = payload.block_number;
@@ -198,6 +207,17 @@ pub mod pallet {
             let acc_id =
                 T::AccountId::decode(&mut sp_runtime::traits::TrailingZeroInput::zeroes()).unwrap();
 
+            // A node whose local key differs from the registered one would fail to decrypt any
+            // weight encrypted to it, so it should not take part in the process at all
+            #[cfg(not(feature = "testing-offworker"))]
+            if !Self::is_registered_encryption_key(&acc_id, &public) {
+                log::error!(
+                    "Local decryption key does not match the one registered in authorities for {:?}",
+                    acc_id
+                );
+                return;
+            }
+
             // The valid subnets are ones that have encrypted weights, with matching key of the
             // offchain worker. The hanging, are potential subnets that have turned off the
             // encryption in the middle of offchain worker process
@@ -219,6 +239,9 @@ pub mod pallet {
             log::info!("Subnets queued for ban: {:?}", queued_for_ban);
             log::info!("Valid subnets: {:?}", remaining_valid_subnets);
 
+            // Committee members of threshold subnets only send their own decryption shares
+            Self::process_committee_subnets(&acc_id, block_number);
+
             let deregistered_subnets =
                 Self::process_subnets(remaining_valid_subnets, acc_id, block_number);
             deregistered_subnets.iter().for_each(|subnet_id| {
@@ -249,6 +272,12 @@ pub mod pallet {
             subnet_id: u16,
             node_id: T::AccountId,
         },
+        /// Committee member sent its decryption shares of a threshold subnet
+        DecryptionSharesSent {
+            subnet_id: u16,
+            block_number: BlockNumberFor<T>,
+            worker: T::AccountId,
+        },
     }
 
     #[pallet::error]
@@ -310,13 +339,21 @@ impl<T: Config> Pallet<T> {
         Ok(())
     }
 
+    /// Checks that the public part of the node's decryption key matches the key registered for
+    /// the account in [`Authorities`].
+    pub fn is_registered_encryption_key(acc_id: &T::AccountId, public_key: &PublicKey) -> bool {
+        Authorities::<T>::get()
+            .iter()
+            .any(|(account, key)| account == acc_id && key == public_key)
+    }
+
     fn validate_unsigned_transaction(
-        block_number: &BlockNumberFor<T>,
+        provides: &impl Encode,
         tag_prefix: &'static str,
     ) -> TransactionValidity {
         ValidTransaction::with_tag_prefix(tag_prefix)
             .priority(T::UnsignedPriority::get())
-            .and_provides(block_number)
+            .and_provides(provides)
             .longevity(5)
             .propagate(true)
             .build()
@@ -393,6 +430,7 @@ impl<T: Config> Pallet<T> {
             |account| DecryptedWeightsPayload {
                 subnet_id,
                 decrypted_weights: decrypted_weights.clone(),
+                decryption_shares: Vec::new(),
                 delta,
                 block_number: <system::Pallet<T>>::block_number(),
                 public: account.public.clone(),
@@ -0,0 +1,95 @@
+use super::*;
+
+impl<T: Config> Pallet<T> {
+    /// Sends the decryption shares of every threshold subnet the node is a committee member of,
+    /// once the oldest encrypted epoch reached the subnet's maximum encryption period.
+    pub fn process_committee_subnets(acc_id: &T::AccountId, current_block: u64) {
+        for (subnet_id, index) in pallet_subnet_emission::Pallet::<T>::get_committee_subnets(acc_id)
+        {
+            let blocks = ConsensusParameters::<T>::iter_key_prefix(subnet_id).collect::<Vec<_>>();
+            let (Some(min_block), Some(max_block)) =
+                (blocks.iter().min().copied(), blocks.iter().max().copied())
+            else {
+                continue;
+            };
+
+            let max_encryption_period =
+                pallet_subnet_emission::Pallet::<T>::get_max_encryption_interval(&subnet_id);
+            if current_block.saturating_sub(min_block) < max_encryption_period {
+                continue;
+            }
+
+            let storage_key = alloc::format!("threshold_sent:{subnet_id}");
+            let storage = StorageValueRef::persistent(storage_key.as_bytes());
+            if storage.get::<u64>().ok().flatten().is_some_and(|sent| sent >= max_block) {
+                log::info!(
+                    "Decryption shares for subnet {} were already sent",
+                    subnet_id
+                );
+                continue;
+            }
+
+            match Self::do_send_shares(subnet_id, index) {
+                Ok(()) => storage.set(&max_block),
+                Err(err) => log::error!(
+                    "Couldn't send decryption shares to runtime for subnet {}: {}",
+                    subnet_id,
+                    err
+                ),
+            }
+        }
+    }
+
+    /// Decrypts the node's share of every pending encrypted weight of the subnet. Weights that
+    /// can't be decrypted are left out, the runtime treats them as empty.
+    pub(crate) fn decrypt_all_subnet_shares(netuid: u16, index: u8) -> Vec<BlockDecryptionShares> {
+        ConsensusParameters::<T>::iter_prefix(netuid)
+            .map(|(block_number, consensus_params)| {
+                let uid_shares = consensus_params
+                    .modules
+                    .values()
+                    .filter(|module_params| !module_params.weight_encrypted.is_empty())
+                    .filter_map(|module_params| {
+                        ow_extensions::offworker::decrypt_share(
+                            module_params.weight_encrypted.clone(),
+                            index,
+                        )
+                        .map(|share| (module_params.uid, share))
+                    })
+                    .collect();
+
+                (block_number, uid_shares)
+            })
+            .collect()
+    }
+
+    fn do_send_shares(subnet_id: u16, index: u8) -> Result<(), &'static str> {
+        let signer = Signer::<T, T::AuthorityId>::all_accounts();
+        if !signer.can_sign() {
+            return Err(
+                "No local accounts available. Consider adding one via `author_insertKey` RPC.",
+            );
+        }
+
+        let decryption_shares = Self::decrypt_all_subnet_shares(subnet_id, index);
+
+        log::info!("Sending decryption shares to subnet {}", subnet_id);
+
+        let results = signer.send_unsigned_transaction(
+            |account| DecryptedWeightsPayload {
+                subnet_id,
+                decrypted_weights: Vec::new(),
+                decryption_shares: decryption_shares.clone(),
+                delta: I64F64::from_num(0),
+                block_number: <system::Pallet<T>>::block_number(),
+                public: account.public.clone(),
+                forced_send_by_rotation: false,
+            },
+            |payload, signature| Call::send_decrypted_weights { payload, signature },
+        );
+
+        results.into_iter().try_for_each(|(_, result)| {
+            result.map_err(|_| "Failed to send decryption shares transaction")
+        })
+    }
+}
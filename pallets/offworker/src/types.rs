@@ -81,6 +81,8 @@ pub struct SimulationYumaParams<T: Config> {
 pub struct DecryptedWeightsPayload<Public, BlockNumber> {
     pub subnet_id: u16,
     pub decrypted_weights: Vec<BlockWeights>,
+    /// Shares of committee members on subnets using threshold decryption
+    pub decryption_shares: Vec<BlockDecryptionShares>,
     pub delta: I64F64,
     pub block_number: BlockNumber,
     pub public: Public,
@@ -15,6 +15,8 @@ pub enum SubnetConsensus {
     Treasury,
     // Pricing
     Root,
+    // Founder defined emission shares
+    FixedAllocation,
 }
 
 pub type SubnetWeights = Vec<(u16, Vec<(u16, u16)>)>;
@@ -36,6 +38,12 @@ pub trait SubnetEmissionApi<AccountId> {
 
     fn set_subnet_consensus_type(netuid: u16, subnet_consensus: Option<SubnetConsensus>);
 
+    /// Checks whether the subnet can be moved to the consensus type.
+    fn validate_consensus_change(netuid: u16, subnet_consensus: SubnetConsensus) -> DispatchResult;
+
+    /// Moves the subnet to the consensus type at its next epoch boundary.
+    fn schedule_consensus_change(netuid: u16, subnet_consensus: SubnetConsensus) -> DispatchResult;
+
     fn get_weights(netuid: u16, uid: u16) -> Option<Vec<(u16, u16)>>;
 
     fn set_weights(
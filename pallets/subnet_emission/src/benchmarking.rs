@@ -4,6 +4,7 @@ use crate::*;
 use frame_benchmarking::{account, benchmarks};
 use frame_system::RawOrigin;
 pub use pallet::*;
+use pallet_subnet_emission_api::SubnetConsensus;
 use pallet_subspace::{vec, MinValidatorStake, Pallet as SubspaceMod, SubnetBurn};
 use sp_std::vec::Vec;
 
@@ -89,4 +90,30 @@ benchmarks! {
         let _ = Pallet::<T>::delegate_weight_control(RawOrigin::Signed(module_key.clone()).into(), netuid, module_key2.clone());
 
     }: remove_weight_control(RawOrigin::Signed(module_key), netuid)
+
+    run_yuma_consensus {
+        let m in 1 .. 420;
+
+        let validator: T::AccountId = account("Validator", 0, 1);
+        register_mock::<T>(validator.clone(), validator.clone(), "validator".as_bytes().to_vec())?;
+        let netuid = SubspaceMod::<T>::get_netuid_for_name("testnet".as_bytes()).unwrap();
+        pallet_subspace::MaxAllowedUids::<T>::set(netuid, m.saturating_add(1) as u16);
+
+        for i in 0..m {
+            pallet_subspace::RegistrationsPerBlock::<T>::set(0);
+            pallet_subspace::RegistrationsThisInterval::<T>::set(netuid, 0);
+
+            let module_key: T::AccountId = account("ModuleKey", i, 2);
+            let mut name = "module".as_bytes().to_vec();
+            name.extend(i.to_le_bytes());
+            register_mock::<T>(module_key.clone(), module_key, name)?;
+        }
+
+        let weights = (1..=m as u16).map(|uid| (uid, 1)).collect::<Vec<_>>();
+        Weights::<T>::insert(netuid, 0, weights);
+        pallet_subspace::UseWeightsEncryption::<T>::set(netuid, false);
+        SubnetConsensusType::<T>::set(netuid, Some(SubnetConsensus::Yuma));
+    }: {
+        crate::distribute_emission::run_yuma_consensus::<T>(netuid, 1_000_000_000)?
+    }
 }
@@ -0,0 +1,93 @@
+//! Moving subnets between consensus types.
+//!
+//! Changes are scheduled by governance and applied once the subnet's current epoch ran, so the
+//! emission accumulated under the old consensus is still distributed by it.
+
+use super::*;
+use frame_support::{ensure, pallet_prelude::DispatchResult, traits::Get, weights::Weight};
+use pallet_subnet_emission_api::SubnetConsensus;
+use pallet_subspace::{Bonds, Error, ValidatorPermits, N};
+
+impl<T: Config> Pallet<T> {
+    /// Root consensus prices the other subnets and can't be switched from or to. Linear and
+    /// Treasury consensus are held by a single subnet.
+    pub fn validate_consensus_change(netuid: u16, consensus: SubnetConsensus) -> DispatchResult {
+        ensure!(
+            N::<T>::contains_key(netuid),
+            Error::<T>::NetworkDoesNotExist
+        );
+
+        let current = SubnetConsensusType::<T>::get(netuid);
+        ensure!(
+            current.is_some_and(|current| current != consensus && current != SubnetConsensus::Root)
+                && consensus != SubnetConsensus::Root,
+            Error::<T>::InvalidConsensusChange
+        );
+        ensure!(
+            matches!(
+                consensus,
+                SubnetConsensus::Yuma | SubnetConsensus::FixedAllocation
+            ) || Self::get_consensus_netuid(consensus).is_none(),
+            Error::<T>::InvalidConsensusChange
+        );
+
+        Ok(())
+    }
+
+    /// Schedules the subnet to move to the consensus type at its next epoch boundary,
+    /// replacing any change scheduled before.
+    pub fn schedule_consensus_change(netuid: u16, consensus: SubnetConsensus) -> DispatchResult {
+        Self::validate_consensus_change(netuid, consensus)?;
+
+        PendingConsensusChange::<T>::insert(netuid, consensus);
+        Self::deposit_event(Event::<T>::ConsensusChangeScheduled {
+            subnet_id: netuid,
+            consensus,
+        });
+
+        Ok(())
+    }
+
+    /// Moves the subnet to its scheduled consensus type. Bonds and validator permits are built
+    /// by each consensus differently, so they start over. Leaving Yuma drops the weight
+    /// encryption state, returning the emission of pending encrypted epochs to the subnet.
+    pub(crate) fn apply_pending_consensus_change(netuid: u16) -> Weight {
+        let db_weight = T::DbWeight::get();
+        let Some(consensus) = PendingConsensusChange::<T>::take(netuid) else {
+            return db_weight.reads(1);
+        };
+
+        // Another subnet might have taken the consensus type since the change was scheduled
+        if let Err(err) = Self::validate_consensus_change(netuid, consensus) {
+            log::warn!("dropping consensus change of subnet {netuid}: {err:?}");
+            return db_weight.reads_writes(3, 1);
+        }
+        let Some(previous) = SubnetConsensusType::<T>::get(netuid) else {
+            return db_weight.reads_writes(3, 1);
+        };
+
+        let reset_weight = if previous == SubnetConsensus::Yuma {
+            Self::reset_encrypted_subnet_state(netuid)
+        } else {
+            Weight::zero()
+        };
+
+        let _ = Bonds::<T>::clear_prefix(netuid, u32::MAX, None);
+        ValidatorPermits::<T>::insert(
+            netuid,
+            sp_std::vec![false; usize::from(N::<T>::get(netuid))],
+        );
+        SubnetConsensusType::<T>::insert(netuid, consensus);
+
+        Self::deposit_event(Event::<T>::ConsensusChanged {
+            subnet_id: netuid,
+            previous,
+            consensus,
+        });
+
+        let n = u64::from(N::<T>::get(netuid));
+        db_weight
+            .reads_writes(n.saturating_add(8), n.saturating_add(8))
+            .saturating_add(reset_weight)
+    }
+}
@@ -2,6 +2,7 @@ use crate::{
     distribute_emission::update_pending_emission,
     subnet_consensus::{util::params::ConsensusParams, yuma::YumaEpoch},
 };
+use frame_support::weights::Weight;
 use pallet_subspace::{MaxEncryptionPeriod, MaxEncryptionPeriodDefaultValue, UseWeightsEncryption};
 use sp_runtime::traits::Get;
 use sp_std::collections::btree_map::BTreeMap;
@@ -49,25 +50,39 @@ impl<T: Config> Pallet<T> {
         }
     }
 
-    pub fn distribute_subnets_to_nodes(block: u64) {
+    /// Assigns a decryption node, or a committee, to every encrypted subnet that has none.
+    /// Returns the consumed weight.
+    pub fn distribute_subnets_to_nodes(block: u64) -> Weight {
+        let db_weight = T::DbWeight::get();
+        // The node list, and the ban of every node in it
+        let mut weight = db_weight.reads(u64::from(T::MaxAuthorities::get()).saturating_add(1));
+
         // Filter out nodes that haven't sent a ping within required interval
         log::info!("running distribution to nodes at block {block}");
         let active_nodes = match Self::get_active_nodes(block) {
             Some(nodes) => nodes,
             None => {
                 log::info!("no active nodes found in distributing to nodes");
-                return;
+                return weight;
             }
         };
         log::info!("active nodes are: {active_nodes:?}");
 
         for netuid in pallet_subspace::N::<T>::iter_keys() {
             log::info!("decryption process for subnet {netuid:?}");
+            weight = weight.saturating_add(db_weight.reads(2));
             if !UseWeightsEncryption::<T>::get(netuid) {
                 log::info!("there is no use weights encryption for subnet {netuid:?}");
                 continue;
             }
 
+            // Both assignments read the current one and the cursor, and write them back
+            weight = weight.saturating_add(db_weight.reads_writes(3, 2));
+            if let Some(config) = ThresholdDecryptionConfig::<T>::get(netuid) {
+                Self::assign_decryption_committee(netuid, config, &active_nodes, block);
+                continue;
+            }
+
             let data = SubnetDecryptionData::<T>::get(netuid);
             if data.is_some_and(|_data| true) {
                 log::info!("subnet {netuid:?} has some decryption data");
@@ -100,9 +115,22 @@ impl<T: Config> Pallet<T> {
                 log::info!("no node info found at cursor position {current}");
             }
         }
+
+        weight
     }
 
+    /// Runs consensus on the decrypted epochs and drops every pending epoch of the subnet, as
+    /// the decryption node reports all of them at once.
     pub fn handle_decrypted_weights(netuid: u16, weights: Vec<BlockWeights>) {
+        Self::receive_decrypted_weights(netuid, weights, true);
+    }
+
+    /// Runs consensus on the decrypted epochs, keeping the other pending epochs of the subnet.
+    pub(crate) fn handle_decrypted_epochs(netuid: u16, weights: Vec<BlockWeights>) {
+        Self::receive_decrypted_weights(netuid, weights, false);
+    }
+
+    fn receive_decrypted_weights(netuid: u16, weights: Vec<BlockWeights>, clear_pending: bool) {
         log::info!(
             "Received decrypted weights: {:?}, for subnet {netuid}",
             weights
@@ -154,7 +182,7 @@ impl<T: Config> Pallet<T> {
             "Updated decrypted weights for subnet {netuid} are {:?}",
             weights
         );
-        match Self::process_decrypted_weights(netuid, weights) {
+        match Self::process_decrypted_weights(netuid, weights, clear_pending) {
             Ok(()) => {
                 log::info!("decrypted weights have been processed for {netuid}")
             }
@@ -167,6 +195,7 @@ impl<T: Config> Pallet<T> {
     fn process_decrypted_weights(
         netuid: u16,
         weights: Option<Vec<KeylessBlockWeights>>,
+        clear_pending: bool,
     ) -> Result<(), &'static str> {
         let weights = match weights {
             Some(w) => w,
@@ -185,6 +214,7 @@ impl<T: Config> Pallet<T> {
         // Process weights in chronological order
         let mut sorted_weights = weights;
         sorted_weights.sort_by_key(|(block, _)| *block);
+        let blocks: Vec<u64> = sorted_weights.iter().map(|(block, _)| *block).collect();
 
         let final_emission = Self::process_weights_sequence(netuid, sorted_weights)?;
 
@@ -194,7 +224,13 @@ impl<T: Config> Pallet<T> {
         }
 
         // Cleanup subnet state (excluding decryption data)
-        Self::cleanup_subnet_wc_state(netuid, false);
+        if clear_pending {
+            Self::cleanup_subnet_wc_state(netuid, false);
+        } else {
+            for block in blocks {
+                ConsensusParameters::<T>::remove(netuid, block);
+            }
+        }
 
         Ok(())
     }
@@ -417,28 +453,46 @@ impl<T: Config> Pallet<T> {
     /// - Second vector contains subnets that don't use encryption but still have matching keys (if
     ///   acc_id is Some). Both require the subnet to have existing encrypted weights.
     pub fn get_valid_subnets(acc_id: Option<&T::AccountId>) -> (Vec<u16>, Vec<u16>) {
-        let (with_encryption, without_encryption): (Vec<_>, Vec<_>) =
-            SubnetDecryptionData::<T>::iter()
-                .filter(|(netuid, data)| {
-                    let is_rotating_from = acc_id
-                        .and_then(|id| {
-                            data.rotating_from.as_ref().map(|rotating_from| rotating_from == id)
-                        })
-                        .unwrap_or(false);
-
-                    let has_encrypted_weights = WeightEncryptionData::<T>::iter_prefix(*netuid)
-                        .any(|(_, value)| !value.encrypted.is_empty());
+        Self::weigh_valid_subnets(acc_id).0
+    }
 
-                    let is_current_node = acc_id
-                        .map(|id| &data.node_id == id && has_encrypted_weights)
-                        .unwrap_or(has_encrypted_weights);
+    /// [`Self::get_valid_subnets`], along with the weight of the lookup.
+    fn weigh_valid_subnets(acc_id: Option<&T::AccountId>) -> ((Vec<u16>, Vec<u16>), Weight) {
+        let mut reads = 0u64;
+        let mut with_encryption = Vec::new();
+        let mut without_encryption = Vec::new();
+
+        for (netuid, data) in SubnetDecryptionData::<T>::iter() {
+            let is_rotating_from = acc_id
+                .and_then(|id| data.rotating_from.as_ref().map(|rotating_from| rotating_from == id))
+                .unwrap_or(false);
+
+            let mut probed = 0u64;
+            let has_encrypted_weights = WeightEncryptionData::<T>::iter_prefix(netuid)
+                .inspect(|_| probed = probed.saturating_add(1))
+                .any(|(_, value)| !value.encrypted.is_empty());
+            // The decryption data, the encrypted weights probed and the end of the prefix
+            reads = reads.saturating_add(probed.saturating_add(2));
+
+            let is_current_node = acc_id
+                .map(|id| data.node_id == *id && has_encrypted_weights)
+                .unwrap_or(has_encrypted_weights);
+            if !is_rotating_from && !is_current_node {
+                continue;
+            }
 
-                    is_rotating_from || is_current_node
-                })
-                .map(|(netuid, _)| netuid)
-                .partition(|netuid| pallet_subspace::UseWeightsEncryption::<T>::get(*netuid));
+            reads = reads.saturating_add(1);
+            if pallet_subspace::UseWeightsEncryption::<T>::get(netuid) {
+                with_encryption.push(netuid);
+            } else {
+                without_encryption.push(netuid);
+            }
+        }
 
-        (with_encryption, without_encryption)
+        (
+            (with_encryption, without_encryption),
+            T::DbWeight::get().reads(reads),
+        )
     }
 
     pub fn get_max_encryption_interval(netuid: &u16) -> u64 {
@@ -479,103 +533,138 @@ impl<T: Config> Pallet<T> {
         );
     }
 
-    pub(crate) fn process_ban_queue(block_number: u64) {
-        DecryptionNodeBanQueue::<T>::iter()
-            .filter(|(_, _, ban_block)| *ban_block <= block_number)
-            .for_each(|(subnet_id, node_id, _ban_block)| {
-                // Get the node info before removing from queue
-                if let Some(info) = SubnetDecryptionData::<T>::get(subnet_id) {
-                    // Remove from ban queue first
-                    DecryptionNodeBanQueue::<T>::remove(subnet_id, &node_id);
-
-                    // Cancel and then ban
-                    Self::cancel_offchain_worker(subnet_id, &info);
-                    Self::ban_offchain_worker(&node_id);
-
-                    Self::deposit_event(Event::<T>::DecryptionNodeBanned {
-                        subnet_id,
-                        node_id: node_id.clone(),
-                    });
+    /// Bans the queued nodes whose ban block was reached. Returns the consumed weight.
+    pub(crate) fn process_ban_queue(block_number: u64) -> Weight {
+        let db_weight = T::DbWeight::get();
+        let mut weight = Weight::zero();
 
-                    log::info!(
-                        "Offchain worker banned at block {}: subnet {}, node {:?}",
-                        block_number,
-                        subnet_id,
-                        node_id
-                    );
-                }
-            });
+        for (subnet_id, node_id, ban_block) in DecryptionNodeBanQueue::<T>::iter() {
+            weight = weight.saturating_add(db_weight.reads(1));
+            if ban_block > block_number {
+                continue;
+            }
+
+            weight = weight.saturating_add(db_weight.reads(1));
+            // Get the node info before removing from queue
+            if let Some(info) = SubnetDecryptionData::<T>::get(subnet_id) {
+                // Remove from ban queue first
+                DecryptionNodeBanQueue::<T>::remove(subnet_id, &node_id);
+
+                // Cancel and then ban
+                weight = weight
+                    .saturating_add(Self::cancel_offchain_worker(subnet_id, &info))
+                    .saturating_add(db_weight.writes(2));
+                Self::ban_offchain_worker(&node_id);
+
+                Self::deposit_event(Event::<T>::DecryptionNodeBanned {
+                    subnet_id,
+                    node_id: node_id.clone(),
+                });
+
+                log::info!(
+                    "Offchain worker banned at block {}: subnet {}, node {:?}",
+                    block_number,
+                    subnet_id,
+                    node_id
+                );
+            }
+        }
+
+        weight
     }
 
-    pub fn cancel_expired_offchain_workers(block_number: u64) {
+    /// Queues the decryption nodes that stopped pinging, or held encrypted weights for longer
+    /// than the subnet's maximum encryption period, for a ban. Returns the consumed weight.
+    pub fn cancel_expired_offchain_workers(block_number: u64) -> Weight {
+        let db_weight = T::DbWeight::get();
         let max_inactivity_blocks =
             T::PingInterval::get().saturating_mul(T::MissedPingsForInactivity::get() as u64);
 
         // Get only subnets that use encryption and have encrypted weights
-        let (with_encryption, _) = Self::get_valid_subnets(None);
+        let ((with_encryption, _), mut weight) = Self::weigh_valid_subnets(None);
 
         let buffer = T::EncryptionPeriodBuffer::get();
 
-        with_encryption
-            .into_iter()
-            .filter_map(|subnet_id| {
-                SubnetDecryptionData::<T>::get(subnet_id).map(|info| (subnet_id, info))
-            })
-            .filter(|(subnet_id, info)| {
-                block_number.saturating_sub(info.last_keep_alive) > max_inactivity_blocks
-                    || block_number.saturating_sub(info.validity_block.unwrap_or(u64::MAX))
-                        > Self::get_max_encryption_interval(subnet_id)
-            })
-            .for_each(|(subnet_id, info)| {
-                Self::put_offworker_to_ban_queue(subnet_id, &info, buffer)
-            });
+        for subnet_id in with_encryption {
+            // The decryption data and the subnet's maximum encryption period
+            weight = weight.saturating_add(db_weight.reads(2));
+            let Some(info) = SubnetDecryptionData::<T>::get(subnet_id) else {
+                continue;
+            };
+
+            let expired = block_number.saturating_sub(info.last_keep_alive) > max_inactivity_blocks
+                || block_number.saturating_sub(info.validity_block.unwrap_or(u64::MAX))
+                    > Self::get_max_encryption_interval(&subnet_id);
+            if expired {
+                weight = weight.saturating_add(db_weight.reads_writes(1, 1));
+                Self::put_offworker_to_ban_queue(subnet_id, &info, buffer);
+            }
+        }
+
+        weight
     }
 
-    pub fn cleanup_weight_encryption_data(subnet_id: u16) {
-        let _ = WeightEncryptionData::<T>::clear_prefix(subnet_id, u32::MAX, None);
+    /// Drops the encrypted weights of a subnet. Returns the consumed weight.
+    pub fn cleanup_weight_encryption_data(subnet_id: u16) -> Weight {
+        let cleared = WeightEncryptionData::<T>::clear_prefix(subnet_id, u32::MAX, None);
+        T::DbWeight::get().reads_writes(u64::from(cleared.loops), u64::from(cleared.unique))
     }
 
     /// Cleans up weight copying state of a subnet by removing weights and parameters.
-    /// If increase_pending_emission is true, returns tokens to pending emissions.
-    /// Returns the total emission amount that was processed.
-    fn cleanup_subnet_wc_state(subnet_id: u16, clear_node_assing: bool) {
+    /// If clear_node_assing is true, the decryption node assignment is dropped too.
+    /// Returns the consumed weight.
+    pub(crate) fn cleanup_subnet_wc_state(subnet_id: u16, clear_node_assing: bool) -> Weight {
         // Clear ConsensusParameters
-        let _ = ConsensusParameters::<T>::clear_prefix(subnet_id, u32::MAX, None);
+        let cleared = ConsensusParameters::<T>::clear_prefix(subnet_id, u32::MAX, None);
+        let mut weight =
+            T::DbWeight::get().reads_writes(u64::from(cleared.loops), u64::from(cleared.unique));
 
         // --- Cleanup The Conditionals ---
         if clear_node_assing {
             SubnetDecryptionData::<T>::remove(subnet_id);
+            weight = weight.saturating_add(T::DbWeight::get().writes(1));
         }
+
+        weight
     }
 
     /// Cleans up all hanging subnets (subnets that have turned their weight encryption off)
     /// by removing their weight copying state.
     /// Recycles / Burns the pending emission, this aims to disincentivize subnet owners from
-    /// switching the parameter, unless absolutelly neccessary Returns the number of subnets
-    /// that were cleaned up.
-    pub fn clear_hanging_subnet_state() -> usize {
-        let (_, hanging_subnets) = Self::get_valid_subnets(None);
+    /// switching the parameter, unless absolutelly neccessary. Returns the number of subnets
+    /// that were cleaned up, and the consumed weight.
+    pub fn clear_hanging_subnet_state() -> (usize, Weight) {
+        let ((_, hanging_subnets), mut weight) = Self::weigh_valid_subnets(None);
 
         for subnet_id in hanging_subnets.clone() {
-            Self::cleanup_subnet_wc_state(subnet_id, true);
-            Self::cleanup_weight_encryption_data(subnet_id);
+            weight = weight
+                .saturating_add(Self::cleanup_subnet_wc_state(subnet_id, true))
+                .saturating_add(Self::cleanup_weight_encryption_data(subnet_id));
         }
 
-        hanging_subnets.len()
+        (hanging_subnets.len(), weight)
     }
 
     /// Cancels an offchain worker for a subnet by cleaning up its weight copying state,
-    /// banning the worker, and reassigning the subnet to a different worker.
-    fn cancel_offchain_worker(subnet_id: u16, info: &SubnetDecryptionInfo<T>) {
+    /// banning the worker, and reassigning the subnet to a different worker. Returns the
+    /// consumed weight.
+    fn cancel_offchain_worker(subnet_id: u16, info: &SubnetDecryptionInfo<T>) -> Weight {
         // sum up emission of subnet before deleting
-        let total_emission = ConsensusParameters::<T>::iter_prefix(subnet_id)
-            .fold(0u64, |acc, (_, params)| {
-                acc.saturating_add(params.token_emission)
-            });
+        let (epochs, total_emission) = ConsensusParameters::<T>::iter_prefix(subnet_id).fold(
+            (0u64, 0u64),
+            |(epochs, emission), (_, params)| {
+                (
+                    epochs.saturating_add(1),
+                    emission.saturating_add(params.token_emission),
+                )
+            },
+        );
         // Add tokens back to pending emission if requested
         update_pending_emission::<T>(subnet_id, &total_emission);
-        Self::cleanup_subnet_wc_state(subnet_id, true);
-        Self::cleanup_weight_encryption_data(subnet_id);
+        let mut weight = T::DbWeight::get()
+            .reads_writes(epochs.saturating_add(1), 2)
+            .saturating_add(Self::cleanup_subnet_wc_state(subnet_id, true))
+            .saturating_add(Self::cleanup_weight_encryption_data(subnet_id));
 
         // Additional operations specific to canceling offchain worker
         let current_block = pallet_subspace::Pallet::<T>::get_current_block_number();
@@ -584,16 +673,18 @@ impl<T: Config> Pallet<T> {
         Self::ban_offchain_worker(&info.node_id);
 
         // Reassign the subnet to a different offchain worker
-        Self::distribute_subnets_to_nodes(current_block);
+        weight = weight.saturating_add(Self::distribute_subnets_to_nodes(current_block));
 
         // Emit an event
         Self::deposit_event(Event::<T>::DecryptionNodeCanceled {
             subnet_id,
             node_id: info.node_id.clone(),
         });
+
+        weight
     }
 
-    fn ban_offchain_worker(node_id: &T::AccountId) {
+    pub(crate) fn ban_offchain_worker(node_id: &T::AccountId) {
         let ban_duration = T::OffchainWorkerBanDuration::get();
         let current_block = pallet_subspace::Pallet::<T>::get_current_block_number();
         let ban_expiry = current_block.saturating_add(ban_duration);
@@ -608,11 +699,19 @@ impl<T: Config> Pallet<T> {
     ///
     /// # Arguments
     /// * `block_number` - The current block number when this function is called
-    pub(crate) fn assign_activation_blocks(block_number: u64) {
+    ///
+    /// # Returns
+    /// The consumed weight
+    pub(crate) fn assign_activation_blocks(block_number: u64) -> Weight {
+        let db_weight = T::DbWeight::get();
+        let mut weight = Weight::zero();
+
         // Iterate through all subnets in SubnetDecryptionData
         for (subnet_id, mut subnet_info) in SubnetDecryptionData::<T>::iter() {
+            weight = weight.saturating_add(db_weight.reads(1));
             // Check if subnet doesn't already have an activation block
             if subnet_info.validity_block.is_none() {
+                weight = weight.saturating_add(db_weight.reads(1));
                 // Check if there's any weight encryption data for this subnet
                 let has_encrypted_weights =
                     WeightEncryptionData::<T>::iter_prefix(subnet_id).next().is_some();
@@ -622,9 +721,12 @@ impl<T: Config> Pallet<T> {
                 if has_encrypted_weights {
                     subnet_info.validity_block = Some(block_number);
                     SubnetDecryptionData::<T>::insert(subnet_id, subnet_info);
+                    weight = weight.saturating_add(db_weight.writes(1));
                 }
             }
         }
+
+        weight
     }
 
     pub(crate) fn rotate_decryption_node_if_needed(subnet_id: u16, info: SubnetDecryptionInfo<T>) {
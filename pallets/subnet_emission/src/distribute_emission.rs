@@ -1,8 +1,10 @@
 use super::*;
-use crate::subnet_consensus::{linear::LinearEpoch, treasury::TreasuryEpoch};
+use crate::subnet_consensus::{
+    fixed::FixedAllocationEpoch, linear::LinearEpoch, treasury::TreasuryEpoch,
+};
 
 use crate::subnet_consensus::util::params::ConsensusParams;
-use frame_support::storage::with_storage_layer;
+use frame_support::{storage::with_storage_layer, traits::Get, weights::Weight};
 use pallet_subnet_emission_api::SubnetConsensus;
 use pallet_subspace::{Pallet as PalletSubspace, N};
 use subnet_consensus::yuma::YumaEpoch;
@@ -17,18 +19,55 @@ const NO_WEIGHTS: &str = "no weights";
 /// * `subnets_emission_distribution` - A map of subnet IDs to their emission values.
 ///
 /// This function iterates through all subnets, updates their pending emissions,
-/// and runs an epoch if it's time for that subnet.
-fn process_subnets<T: Config>(block_number: u64, subnets_emission_distribution: PricedSubnets) {
+/// and runs the epochs that are due. Each subnet's epoch is offset by its netuid, so subnets
+/// sharing a tempo are spread over the tempo blocks. When more epochs are due than
+/// `MaxEpochsPerBlock`, the remaining ones are deferred to the next blocks, oldest first.
+/// Scheduled consensus changes are applied right after the subnet's epoch.
+///
+/// Returns the consumed weight.
+fn process_subnets<T: Config>(
+    block_number: u64,
+    subnets_emission_distribution: PricedSubnets,
+) -> Weight {
+    let db_weight = T::DbWeight::get();
+    let mut weight = db_weight.reads_writes(1, 1);
+
+    let mut due = DeferredEpochs::<T>::get();
+    // The subnet might have been removed while deferred
+    due.retain(|netuid| N::<T>::contains_key(netuid));
+    let previously_deferred = due.len();
+
     for netuid in N::<T>::iter_keys() {
+        weight = weight.saturating_add(db_weight.reads_writes(3, 1));
+
         update_pending_emission::<T>(
             netuid,
             subnets_emission_distribution.get(&netuid).unwrap_or(&0),
         );
 
-        if pallet_subspace::Pallet::<T>::blocks_until_next_epoch(netuid, block_number) == 0 {
-            run_epoch::<T>(netuid);
+        if pallet_subspace::Pallet::<T>::blocks_until_next_epoch(netuid, block_number) == 0
+            && !due.contains(&netuid)
+        {
+            due.push(netuid);
         }
     }
+
+    let max_epochs = usize::from(T::MaxEpochsPerBlock::get());
+    let deferred = due.split_off(max_epochs.min(due.len()));
+
+    for netuid in due {
+        weight = weight.saturating_add(run_epoch::<T>(netuid));
+        weight = weight.saturating_add(Pallet::<T>::apply_pending_consensus_change(netuid));
+    }
+
+    for netuid in deferred.iter().skip(previously_deferred.saturating_sub(max_epochs)) {
+        log::info!("deferring epoch of subnet {netuid}, too many epochs in this block");
+        Pallet::<T>::deposit_event(Event::<T>::EpochDeferred(*netuid));
+    }
+
+    DeferredEpochs::<T>::set(deferred);
+
+    weight
 }
 /// Updates the pending emission for a given subnet.
 ///
@@ -56,28 +95,67 @@ pub(crate) fn update_pending_emission<T: Config>(netuid: u16, new_queued_emissio
 /// This function clears the set weight rate limiter, retrieves the pending emission,
 /// and if there's emission to distribute, runs the consensus algorithm. If successful,
 /// it finalizes the epoch. If an error occurs during consensus, it logs the error
-fn run_epoch<T: Config>(netuid: u16) {
+///
+/// Returns the consumed weight.
+fn run_epoch<T: Config>(netuid: u16) -> Weight {
     log::trace!("running epoch for subnet {netuid}");
 
     let emission_to_drain = PendingEmission::<T>::get(netuid);
-    if emission_to_drain > 0 {
-        match run_consensus_algorithm::<T>(netuid, emission_to_drain) {
-            Ok(_) => {
-                finalize_epoch::<T>(netuid, true); // clear emission
-            }
-            Err(NO_WEIGHTS) => {
-                log::warn!("no weights set on subnet {netuid}");
-                finalize_epoch::<T>(netuid, false); // do not clear emission, just deposit event
-            }
-            Err(e) => {
-                log::error!(
-                    "Error running consensus algorithm for subnet {}: {:?}",
-                    netuid,
-                    e
-                );
-            }
+    if emission_to_drain == 0 {
+        return T::DbWeight::get().reads(1);
+    }
+
+    let weight = epoch_weight::<T>(netuid);
+
+    match run_consensus_algorithm::<T>(netuid, emission_to_drain) {
+        Ok(_) => {
+            finalize_epoch::<T>(netuid, true); // clear emission
+        }
+        Err(NO_WEIGHTS) => {
+            log::warn!("no weights set on subnet {netuid}");
+            finalize_epoch::<T>(netuid, false); // do not clear emission, just deposit event
+        }
+        Err(e) => {
+            log::error!(
+                "Error running consensus algorithm for subnet {}: {:?}",
+                netuid,
+                e
+            );
         }
     }
+
+    weight
+}
+
+/// Worst-case weight of the subnet's consensus run. Linear and fixed allocation consensus do
+/// strictly less work than Yuma over the same modules, so they're charged as Yuma.
+fn epoch_weight<T: Config>(netuid: u16) -> Weight {
+    let db_weight = T::DbWeight::get();
+    let base = db_weight.reads_writes(2, 1);
+
+    match SubnetConsensusType::<T>::get(netuid) {
+        Some(
+            SubnetConsensus::Yuma | SubnetConsensus::Linear | SubnetConsensus::FixedAllocation,
+        ) => base
+            .saturating_add(db_weight.reads(1))
+            .saturating_add(consensus_weight::<T>(u64::from(N::<T>::get(netuid)))),
+        Some(SubnetConsensus::Treasury) => base.saturating_add(db_weight.reads_writes(2, 2)),
+        Some(SubnetConsensus::Root) | None => base,
+    }
+}
+
+/// Storage accessed by a Yuma run over `modules` modules.
+///
+/// The parameters snapshot reads the key, stake, bonds, encrypted weights and weight delegation
+/// of every module, plus 14 subnet parameters and vectors. The epoch reads every module's
+/// weights, writes back its bonds, and pays its emission out as stake, which checks the
+/// registration and updates the stake maps and total stake. The results are 10 subnet vectors,
+/// and the founder is paid to its balance.
+fn consensus_weight<T: Config>(modules: u64) -> Weight {
+    T::DbWeight::get().reads_writes(
+        modules.saturating_mul(10).saturating_add(15),
+        modules.saturating_mul(4).saturating_add(11),
+    )
 }
 
 // ---------------------------------
@@ -111,6 +189,9 @@ fn run_consensus_algorithm<T: Config>(
             SubnetConsensus::Treasury => run_treasury_consensus::<T>(netuid, emission_to_drain),
             SubnetConsensus::Linear => run_linear_consensus::<T>(netuid, emission_to_drain),
             SubnetConsensus::Yuma => run_yuma_consensus::<T>(netuid, emission_to_drain),
+            SubnetConsensus::FixedAllocation => {
+                run_fixed_allocation_consensus::<T>(netuid, emission_to_drain)
+            }
         }
     })
 }
@@ -160,7 +241,10 @@ pub fn run_linear_consensus<T: Config>(
 /// A Result indicating success or failure of the Yuma consensus algorithm.
 ///
 /// This function creates and runs a new YumaEpoch, logging any errors that occur.
-fn run_yuma_consensus<T: Config>(netuid: u16, emission_to_drain: u64) -> Result<(), &'static str> {
+pub(crate) fn run_yuma_consensus<T: Config>(
+    netuid: u16,
+    emission_to_drain: u64,
+) -> Result<(), &'static str> {
     log::info!("Running Yuma consensus for subnet {netuid}");
 
     let params = ConsensusParams::<T>::new(netuid, emission_to_drain)?;
@@ -195,7 +279,7 @@ fn run_yuma_consensus<T: Config>(netuid: u16, emission_to_drain: u64) -> Result<
     if let Some(info) = SubnetDecryptionData::<T>::get(netuid) {
         log::info!("checking rotation for subnet {netuid}");
         crate::Pallet::<T>::rotate_decryption_node_if_needed(netuid, info);
-    } else {
+    } else if !SubnetDecryptionCommittee::<T>::contains_key(netuid) {
         log::error!(
             "subnet {netuid} received decrypted weights to run but has no decryption data."
         );
@@ -247,6 +331,24 @@ fn run_treasury_consensus<T: Config>(
         })
 }
 
+/// Runs the fixed allocation consensus, distributing the emission by the shares the founder
+/// set in [`FixedAllocations`]. While the subnet has no allocation, for example after every
+/// allocated module deregistered, the emission is kept for the next epoch.
+fn run_fixed_allocation_consensus<T: Config>(
+    netuid: u16,
+    emission_to_drain: u64,
+) -> Result<(), &'static str> {
+    let params = ConsensusParams::<T>::new(netuid, emission_to_drain)?;
+
+    FixedAllocationEpoch::<T>::new(netuid, params)
+        .run(FixedAllocations::<T>::get(netuid))
+        .map_err(|err| {
+            log::error!("could not run fixed allocation consensus for {netuid}: {err:?}");
+            "could not run fixed allocation consensus"
+        })
+        .map(|output| output.apply())
+}
+
 // Runs the treasury consensus algorithm for subnet 1.
 
 // ---------------------------------
@@ -278,12 +380,12 @@ impl<T: Config> Pallet<T> {
     /// * `emission_per_block` - The total emission to be distributed per block.
     ///
     /// This function calculates the emission distribution across subnets and
-    /// processes each subnet accordingly.
-    pub fn process_emission_distribution(block_number: u64, emission_per_block: u64) {
+    /// processes each subnet accordingly, returning the consumed weight.
+    pub fn process_emission_distribution(block_number: u64, emission_per_block: u64) -> Weight {
         log::debug!("stepping block {block_number:?}");
 
         let subnets_emission_distribution = Self::get_subnet_pricing(emission_per_block);
-        process_subnets::<T>(block_number, subnets_emission_distribution);
+        process_subnets::<T>(block_number, subnets_emission_distribution)
     }
 
     // ---------------------------------
@@ -331,7 +433,7 @@ impl<T: Config> Pallet<T> {
     pub fn can_remove_subnet(netuid: u16) -> bool {
         matches!(
             SubnetConsensusType::<T>::get(netuid),
-            Some(SubnetConsensus::Yuma)
+            Some(SubnetConsensus::Yuma | SubnetConsensus::FixedAllocation)
         )
     }
 
@@ -339,7 +441,9 @@ impl<T: Config> Pallet<T> {
     pub fn is_mineable_subnet(netuid: u16) -> bool {
         matches!(
             SubnetConsensusType::<T>::get(netuid),
-            Some(SubnetConsensus::Linear) | Some(SubnetConsensus::Yuma)
+            Some(SubnetConsensus::Linear)
+                | Some(SubnetConsensus::Yuma)
+                | Some(SubnetConsensus::FixedAllocation)
         )
     }
 
@@ -1,11 +1,15 @@
 #![allow(non_snake_case)]
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use crate::types::{BlockWeights, PublicKey, SubnetDecryptionInfo};
+use crate::types::{
+    BlockDecryptionShares, BlockWeights, DecryptionCommittee, PublicKey, SubnetDecryptionInfo,
+    ThresholdConfig,
+};
 use frame_system::pallet_prelude::OriginFor;
 pub use pallet::*;
 use parity_scale_codec::{Decode, Encode};
 use scale_info::TypeInfo;
+use sp_runtime::Percent;
 use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
 
 // ! Pallet that handles the emission distribution amongst subnets
@@ -13,6 +17,7 @@ use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
 // Pallet Imports
 // ==============
 
+pub mod consensus_change;
 pub mod decryption;
 pub mod distribute_emission;
 pub mod migrations;
@@ -23,6 +28,9 @@ pub mod subnet_pricing {
 
 pub mod set_weights;
 pub mod subnet_consensus;
+pub mod threshold;
+#[cfg(feature = "try-runtime")]
+mod try_state;
 pub mod types;
 pub mod weights;
 
@@ -106,6 +114,11 @@ pub mod pallet {
         #[pallet::constant]
         type EncryptionPeriodBuffer: Get<u64>;
 
+        /// The maximum number of subnet epochs run in a single block, due epochs over it are
+        /// deferred to the following blocks
+        #[pallet::constant]
+        type MaxEpochsPerBlock: Get<u16>;
+
         type WeightInfo: WeightInfo;
     }
 
@@ -121,11 +134,17 @@ pub mod pallet {
         double_maps: {
             Weights,
             WeightEncryptionData,
-            ConsensusParameters
+            ConsensusParameters,
+            DecryptionShares
         },
         maps: {
             SubnetDecryptionData,
-            SubnetConsensusType
+            SubnetConsensusType,
+            PendingConsensusChange,
+            FixedAllocations,
+            ThresholdDecryptionConfig,
+            SubnetDecryptionCommittee,
+            PendingThresholdDecryptionReset
         }
     );
 
@@ -161,6 +180,41 @@ pub mod pallet {
     #[pallet::storage]
     pub type SubnetConsensusType<T> = StorageMap<_, Identity, u16, SubnetConsensus>;
 
+    /// Consensus type the subnet moves to once its current epoch finishes
+    #[pallet::storage]
+    pub type PendingConsensusChange<T> = StorageMap<_, Identity, u16, SubnetConsensus>;
+
+    /// Emission shares set by the founder, distributed by the fixed allocation consensus. Kept by
+    /// module key, so shares follow the modules when uids are swapped on deregistration
+    #[pallet::storage]
+    pub type FixedAllocations<T: Config> =
+        StorageMap<_, Identity, u16, Vec<(T::AccountId, Percent)>, ValueQuery>;
+
+    /// Threshold decryption parameters of a subnet. Subnets without it use a single decryption
+    /// node
+    #[pallet::storage]
+    pub type ThresholdDecryptionConfig<T> = StorageMap<_, Identity, u16, ThresholdConfig>;
+
+    /// Decryption committee assigned to a subnet using threshold decryption
+    #[pallet::storage]
+    pub type SubnetDecryptionCommittee<T> = StorageMap<_, Identity, u16, DecryptionCommittee<T>>;
+
+    /// Subnets whose threshold decryption parameters changed, their encrypted state is dropped at
+    /// the start of the next block
+    #[pallet::storage]
+    pub type PendingThresholdDecryptionReset<T> = StorageMap<_, Identity, u16, ()>;
+
+    /// Decryption shares submitted by committee members, kept until the threshold is reached
+    #[pallet::storage]
+    pub type DecryptionShares<T: Config> = StorageDoubleMap<
+        _,
+        Identity,
+        u16,
+        Identity,
+        T::AccountId,
+        Vec<BlockDecryptionShares>,
+    >;
+
     /// Netuid, to block number to consensus parameters
     #[pallet::storage]
     pub type ConsensusParameters<T> =
@@ -184,6 +238,10 @@ pub mod pallet {
     #[pallet::storage]
     pub type PendingEmission<T> = StorageMap<_, Identity, u16, u64, ValueQuery>;
 
+    /// Subnets whose epoch is due but didn't fit in the per block limit, oldest first
+    #[pallet::storage]
+    pub type DeferredEpochs<T> = StorageValue<_, Vec<u16>, ValueQuery>;
+
     #[pallet::storage]
     pub type SubnetEmission<T> = StorageMap<_, Identity, u16, u64, ValueQuery>;
 
@@ -221,37 +279,53 @@ pub mod pallet {
                 block_number
             );
 
-            let cleared = Self::clear_hanging_subnet_state();
+            let (cleared, mut weight) = Self::clear_hanging_subnet_state();
             log::info!("Cleared state of {cleared} subnets");
+            weight = weight.saturating_add(Self::apply_threshold_decryption_resets());
 
-            Self::distribute_subnets_to_nodes(block_number);
+            weight = weight.saturating_add(Self::distribute_subnets_to_nodes(block_number));
             log::info!("Distributed subnets to nodes");
-            Self::assign_activation_blocks(block_number);
-            Self::cancel_expired_offchain_workers(block_number);
-            Self::process_ban_queue(block_number);
+            weight = weight
+                .saturating_add(Self::assign_activation_blocks(block_number))
+                .saturating_add(Self::cancel_expired_offchain_workers(block_number))
+                .saturating_add(Self::cancel_expired_committees(block_number))
+                .saturating_add(Self::process_ban_queue(block_number));
             log::info!("Cancelled expired offchain workers");
             let emission_per_block = Self::get_total_emission_per_block();
             log::info!("Emission per block: {:?}", emission_per_block);
+            let db_weight = T::DbWeight::get();
+            // Total issuance and the unit emission
+            weight = weight.saturating_add(db_weight.reads(2));
+
             // Make sure to use storage layer,
             // so runtime can never panic in initialization hook
-            let res: Result<(), DispatchError> = with_storage_layer(|| {
-                Self::process_emission_distribution(block_number, emission_per_block);
-                Ok(())
+            let res: Result<Weight, DispatchError> = with_storage_layer(|| {
+                Ok(Self::process_emission_distribution(
+                    block_number,
+                    emission_per_block,
+                ))
             });
-            if let Err(err) = res {
-                log::error!("Error in on_initialize emission: {err:?}, skipping...");
+            match res {
+                Ok(emission_weight) => weight = weight.saturating_add(emission_weight),
+                Err(err) => log::error!("Error in on_initialize emission: {err:?}, skipping..."),
             }
 
             for netuid in pallet_subspace::N::<T>::iter_keys() {
+                weight = weight.saturating_add(db_weight.reads(2));
                 if pallet_subspace::Pallet::<T>::blocks_until_next_epoch(netuid, block_number) > 0 {
                     continue;
                 }
 
                 // Clear weights for normal subnets
-                Self::clear_set_weight_rate_limiter(netuid);
+                weight = weight.saturating_add(Self::clear_set_weight_rate_limiter(netuid));
             }
 
-            Weight::zero()
+            weight
+        }
+
+        #[cfg(feature = "try-runtime")]
+        fn try_state(_n: BlockNumberFor<T>) -> Result<(), sp_runtime::TryRuntimeError> {
+            Self::do_try_state()
         }
     }
 
@@ -260,6 +334,8 @@ pub mod pallet {
     pub enum Event<T: Config> {
         /// Subnets tempo has finished or Snapshot has been taken
         EpochFinalized(u16),
+        /// Subnet epoch is due but was moved to a later block, as too many epochs were due
+        EpochDeferred(u16),
         /// Weight copying decryption was canceled
         DecryptionNodeCanceled {
             subnet_id: u16,
@@ -283,6 +359,35 @@ pub mod pallet {
             subnet_id: u16,
             node_id: T::AccountId,
         },
+        /// Subnet founder changed the threshold decryption parameters
+        ThresholdDecryptionConfigured {
+            subnet_id: u16,
+            config: Option<ThresholdConfig>,
+        },
+        /// A decryption committee was assigned to a subnet
+        DecryptionCommitteeAssigned {
+            subnet_id: u16,
+            members: Vec<T::AccountId>,
+        },
+        /// Enough decryption shares were received and the subnet weights were decrypted
+        ThresholdDecryptionCompleted { subnet_id: u16 },
+        /// The decryption committee didn't deliver in time, pending encrypted epochs were dropped
+        DecryptionCommitteeExpired { subnet_id: u16 },
+        /// The decryption committee served for the rotation interval and was dissolved
+        DecryptionCommitteeRotated { subnet_id: u16 },
+        /// The subnet will move to the consensus type at its next epoch boundary
+        ConsensusChangeScheduled {
+            subnet_id: u16,
+            consensus: SubnetConsensus,
+        },
+        /// Subnet founder changed the emission shares of the fixed allocation consensus
+        FixedAllocationSet { subnet_id: u16 },
+        /// The subnet moved to another consensus type, its consensus state was reset
+        ConsensusChanged {
+            subnet_id: u16,
+            previous: SubnetConsensus,
+            consensus: SubnetConsensus,
+        },
     }
 
     #[derive(Debug)]
@@ -438,5 +543,25 @@ pub mod pallet {
         pub fn remove_weight_control(origin: OriginFor<T>, netuid: u16) -> DispatchResult {
             Self::do_remove_weight_control(origin, netuid)
         }
+
+        #[pallet::call_index(4)]
+        #[pallet::weight((T::DbWeight::get().reads_writes(2, 2), DispatchClass::Normal, Pays::Yes))]
+        pub fn set_threshold_decryption(
+            origin: OriginFor<T>,
+            netuid: u16,
+            config: Option<ThresholdConfig>,
+        ) -> DispatchResult {
+            Self::do_set_threshold_decryption(origin, netuid, config)
+        }
+
+        #[pallet::call_index(5)]
+        #[pallet::weight((<T as pallet::Config>::WeightInfo::set_weights(), DispatchClass::Normal, Pays::No))]
+        pub fn set_fixed_allocation(
+            origin: OriginFor<T>,
+            netuid: u16,
+            allocation: Vec<(u16, Percent)>,
+        ) -> DispatchResult {
+            Self::do_set_fixed_allocation(origin, netuid, allocation)
+        }
     }
 }
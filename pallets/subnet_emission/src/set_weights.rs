@@ -1,16 +1,18 @@
 use super::*;
 use crate::subnet_consensus::util::params::ConsensusParams;
 use frame_support::{ensure, pallet_prelude::DispatchResult};
-use frame_system::ensure_signed;
 use pallet_subnet_emission_api::SubnetConsensus;
-use pallet_subspace::{Error, Pallet as PalletSubspace, WeightSettingDelegation};
+use pallet_subspace::{
+    Error, OperatorPermission, Pallet as PalletSubspace, WeightSettingDelegation,
+};
 use sp_core::Get;
 
 impl<T: Config> Pallet<T> {
     /// Sets weights for a node in a specific subnet.
     /// # Arguments
     ///
-    /// * `origin` - The origin of the call, must be a signed account.
+    /// * `origin` - The origin of the call, signed by the module key or by one of its operator keys
+    ///   with the `SetWeights` permission.
     /// * `netuid` - The ID of the subnet.
     /// * `uids` - A vector of UIDs to set weights for.
     /// * `values` - A vector of weight values corresponding to the UIDs.
@@ -54,7 +56,8 @@ impl<T: Config> Pallet<T> {
         uids: Vec<u16>,
         values: Vec<u16>,
     ) -> DispatchResult {
-        let key = ensure_signed(origin)?;
+        let key =
+            PalletSubspace::<T>::ensure_module_operator(origin, OperatorPermission::SetWeights)?;
 
         if pallet_subspace::UseWeightsEncryption::<T>::get(netuid) {
             return Err(Error::<T>::SubnetEncrypted.into());
@@ -299,8 +302,10 @@ impl<T: Config> Pallet<T> {
     ///
     /// This function removes all entries from the SetWeightCallsPerEpoch storage
     /// for the specified subnet.
-    pub fn clear_set_weight_rate_limiter(netuid: u16) {
-        let _ = pallet_subspace::SetWeightCallsPerEpoch::<T>::clear_prefix(netuid, u32::MAX, None);
+    pub fn clear_set_weight_rate_limiter(netuid: u16) -> Weight {
+        let removed =
+            pallet_subspace::SetWeightCallsPerEpoch::<T>::clear_prefix(netuid, u32::MAX, None);
+        T::DbWeight::get().reads_writes(removed.loops.into(), removed.unique.into())
     }
 
     pub fn do_delegate_weight_control(
@@ -308,7 +313,8 @@ impl<T: Config> Pallet<T> {
         netuid: u16,
         target: T::AccountId,
     ) -> DispatchResult {
-        let key = ensure_signed(origin)?;
+        let key =
+            PalletSubspace::<T>::ensure_module_operator(origin, OperatorPermission::SetWeights)?;
 
         // Check if the sender is registered in the network
         let Some(uid) = pallet_subspace::Pallet::<T>::get_uid_for_key(netuid, &key) else {
@@ -349,7 +355,8 @@ impl<T: Config> Pallet<T> {
     }
 
     pub fn do_remove_weight_control(origin: T::RuntimeOrigin, netuid: u16) -> DispatchResult {
-        let key = ensure_signed(origin)?;
+        let key =
+            PalletSubspace::<T>::ensure_module_operator(origin, OperatorPermission::SetWeights)?;
 
         let Some(_) = pallet_subspace::Pallet::<T>::get_uid_for_key(netuid, &key) else {
             return Err(Error::<T>::ModuleDoesNotExist.into());
@@ -388,7 +395,8 @@ impl<T: Config> Pallet<T> {
         decrypted_weights_hash: Vec<u8>,
         set_last_updated: bool,
     ) -> DispatchResult {
-        let key = ensure_signed(origin.clone())?;
+        let key =
+            PalletSubspace::<T>::ensure_module_operator(origin, OperatorPermission::SetWeights)?;
 
         if !pallet_subspace::UseWeightsEncryption::<T>::get(netuid) {
             return Err(Error::<T>::SubnetNotEncrypted.into());
@@ -0,0 +1,170 @@
+use crate::{
+    subnet_consensus::util::{consensus::*, params},
+    Config, EmissionError, Event, FixedAllocations, Pallet,
+};
+use core::marker::PhantomData;
+use frame_support::{ensure, pallet_prelude::DispatchResult, DebugNoBound};
+use frame_system::{ensure_signed, pallet_prelude::OriginFor};
+use pallet_subspace::{vec, Error, Founder, Keys, N};
+use sp_runtime::Percent;
+use sp_std::{collections::btree_set::BTreeSet, vec::Vec};
+use substrate_fixed::types::I32F32;
+
+/// Distributes the emission by the shares the founder allocated to each module, without
+/// validators setting weights. The whole emission goes out as incentive.
+#[derive(DebugNoBound)]
+pub struct FixedAllocationEpoch<T: Config> {
+    subnet_id: u16,
+
+    params: params::ConsensusParams<T>,
+    modules: params::FlattenedModules<T::AccountId>,
+
+    _pd: PhantomData<T>,
+}
+
+impl<T: Config> FixedAllocationEpoch<T> {
+    pub fn new(subnet_id: u16, mut params: params::ConsensusParams<T>) -> Self {
+        let modules = sp_std::mem::take(&mut params.modules).into();
+
+        Self {
+            subnet_id,
+
+            params,
+            modules,
+
+            _pd: Default::default(),
+        }
+    }
+
+    pub fn run(
+        self,
+        allocation: Vec<(T::AccountId, Percent)>,
+    ) -> Result<ConsensusOutput<T>, EmissionError> {
+        log::debug!(
+            "running fixed allocation for subnet_id {}, will emit {:?} modules and {:?} to founder",
+            self.subnet_id,
+            self.params.token_emission,
+            self.params.founder_emission
+        );
+
+        let module_count = self.modules.module_count::<usize>();
+        let zeros = vec![I32F32::from_num(0); module_count];
+
+        let mut shares = zeros.clone();
+        let mut total: u32 = 0;
+        for (key, share) in allocation {
+            let uid = self
+                .modules
+                .keys
+                .iter()
+                .position(|module| module.0 == key)
+                .ok_or(EmissionError::Other("allocated module is not registered"))?;
+            if let Some(entry) = shares.get_mut(uid) {
+                *entry = I32F32::from_num(share.deconstruct())
+                    .checked_div(I32F32::from_num(100))
+                    .unwrap_or_default();
+            }
+            total = total.saturating_add(u32::from(share.deconstruct()));
+        }
+        ensure!(total == 100, "allocation doesn't sum to 100%");
+
+        let stake = StakeVal::unchecked_from_inner(self.modules.stake_normalized.clone());
+        let active_stake = ActiveStake::unchecked_from_inner(self.modules.stake_normalized.clone());
+        let ema_bonds = vec![Vec::new(); module_count];
+
+        process_consensus_output::<T>(
+            &self.params,
+            &self.modules,
+            stake,
+            active_stake,
+            ConsensusVal::unchecked_from_inner(zeros.clone()),
+            IncentivesVal::unchecked_from_inner(shares.clone()),
+            DividendsVal::unchecked_from_inner(zeros.clone()),
+            TrustVal::unchecked_from_inner(zeros.clone()),
+            RanksVal::unchecked_from_inner(shares),
+            vec![true; module_count],
+            ValidatorTrustVal::unchecked_from_inner(zeros),
+            vec![false; module_count],
+            &ema_bonds,
+        )
+    }
+}
+
+impl<T: Config> Pallet<T> {
+    /// Sets the emission share of each uid for the fixed allocation consensus. Only the subnet
+    /// founder can call it, shares must sum to 100%.
+    pub fn do_set_fixed_allocation(
+        origin: OriginFor<T>,
+        netuid: u16,
+        allocation: Vec<(u16, Percent)>,
+    ) -> DispatchResult {
+        let key = ensure_signed(origin)?;
+
+        ensure!(
+            N::<T>::contains_key(netuid),
+            Error::<T>::NetworkDoesNotExist
+        );
+        ensure!(Founder::<T>::get(netuid) == key, Error::<T>::NotFounder);
+
+        let uids: BTreeSet<_> = allocation.iter().map(|(uid, _)| *uid).collect();
+        let total = allocation.iter().fold(0u32, |acc, (_, share)| {
+            acc.saturating_add(u32::from(share.deconstruct()))
+        });
+        ensure!(
+            uids.len() == allocation.len() && total == 100,
+            Error::<T>::InvalidFixedAllocation
+        );
+
+        let allocation = allocation
+            .into_iter()
+            .map(|(uid, share)| {
+                Keys::<T>::get(netuid, uid)
+                    .map(|module_key| (module_key, share))
+                    .ok_or(Error::<T>::InvalidFixedAllocation)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        FixedAllocations::<T>::insert(netuid, allocation);
+        Self::deposit_event(Event::<T>::FixedAllocationSet { subnet_id: netuid });
+
+        Ok(())
+    }
+
+    /// Drops the share of a removed module, scaling the shares of the modules left back up to
+    /// 100%. The allocation is removed once no module with a share is left.
+    pub fn remove_fixed_allocation(netuid: u16, module_key: &T::AccountId) {
+        FixedAllocations::<T>::mutate_exists(netuid, |allocation| {
+            let Some(entries) = allocation else {
+                return;
+            };
+            let Some(position) = entries.iter().position(|(key, _)| key == module_key) else {
+                return;
+            };
+            entries.remove(position);
+
+            let remaining = entries.iter().fold(0u32, |acc, (_, share)| {
+                acc.saturating_add(u32::from(share.deconstruct()))
+            });
+            if remaining == 0 {
+                *allocation = None;
+                return;
+            }
+
+            let mut total: u32 = 0;
+            for (_, share) in entries.iter_mut() {
+                let scaled = u32::from(share.deconstruct())
+                    .saturating_mul(100)
+                    .checked_div(remaining)
+                    .unwrap_or_default();
+                *share = Percent::from_parts(u8::try_from(scaled).unwrap_or(100));
+                total = total.saturating_add(scaled);
+            }
+
+            // Rounding leftovers go to the largest share
+            if let Some((_, share)) = entries.iter_mut().max_by_key(|(_, share)| *share) {
+                let leftover = u8::try_from(100u32.saturating_sub(total)).unwrap_or_default();
+                *share = Percent::from_parts(share.deconstruct().saturating_add(leftover));
+            }
+        });
+    }
+}
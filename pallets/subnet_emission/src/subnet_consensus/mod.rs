@@ -1,5 +1,6 @@
 // use frame_support::{traits::Get, weights::Weight};
 // use frame_system::Config;
+pub mod fixed;
 pub mod linear;
 pub mod treasury;
 pub mod util;
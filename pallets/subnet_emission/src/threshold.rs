@@ -0,0 +1,535 @@
+//! Threshold decryption of encrypted weights.
+//!
+//! Subnets with a [`ThresholdDecryptionConfig`] are assigned a committee of authorities instead of
+//! a single decryption node. Validators encrypt their weights with a random AES-256-GCM key, split
+//! that key into `committee_size` Shamir shares over GF(256) and encrypt share `i` to the RSA key
+//! of committee member `i`:
+//!
+//! `version || member_count || (share_len: u16 || RSA-OAEP(x || y))* || blake2_256(x || y)* ||
+//! nonce || AES-GCM(payload)`
+//!
+//! Each member can only decrypt its own share, which it submits through the offworker's
+//! `send_decrypted_weights`. Submitted shares are checked against the commitments in the envelope,
+//! so a faulty member can't corrupt the recovered key. Once `threshold` members submitted their
+//! shares, the runtime recovers the AES keys and decrypts the weights itself, so no single node
+//! sees them early.
+//!
+//! Committees that don't deliver within the subnet's maximum encryption period are dissolved and
+//! their silent members banned, and committees are rotated after a completed decryption once they
+//! served for `DecryptionNodeRotationInterval` blocks.
+
+use super::*;
+use crate::{
+    distribute_emission::update_pending_emission,
+    types::{BlockDecryptionShares, DecryptionCommittee, ThresholdConfig},
+};
+use aes_gcm::{aead::Aead, Aes256Gcm, KeyInit, Nonce};
+use frame_support::{ensure, pallet_prelude::DispatchResult, weights::Weight};
+use frame_system::ensure_signed;
+use pallet_subspace::Error;
+use sp_core::Get;
+use sp_io::hashing::blake2_256;
+use sp_std::collections::btree_set::BTreeSet;
+
+/// Leading byte of weights encrypted to a decryption committee.
+pub const THRESHOLD_ENVELOPE_VERSION: u8 = 2;
+
+/// Size of the AES-GCM nonce in the envelope.
+pub const AES_NONCE_SIZE: usize = 12;
+
+/// Size of a share commitment in the envelope.
+pub const SHARE_COMMITMENT_SIZE: usize = 32;
+
+/// Borrowed view over a threshold envelope.
+pub struct ThresholdEnvelope<'a> {
+    /// RSA encrypted shares, ordered as the committee members.
+    pub shares: Vec<&'a [u8]>,
+    /// `blake2_256` of every decrypted share, ordered as the committee members.
+    pub commitments: Vec<&'a [u8]>,
+    pub nonce: &'a [u8],
+    pub ciphertext: &'a [u8],
+}
+
+/// Splits a threshold envelope into its encrypted shares, share commitments and the AES-GCM
+/// ciphertext.
+pub fn parse_envelope(encrypted: &[u8]) -> Option<ThresholdEnvelope<'_>> {
+    let (&version, rest) = encrypted.split_first()?;
+    if version != THRESHOLD_ENVELOPE_VERSION {
+        return None;
+    }
+
+    let (&member_count, mut rest) = rest.split_first()?;
+    let mut shares = Vec::with_capacity(member_count as usize);
+    for _ in 0..member_count {
+        let len = rest.get(..2)?;
+        let len = u16::from_be_bytes([*len.first()?, *len.get(1)?]) as usize;
+        let end = len.checked_add(2)?;
+
+        shares.push(rest.get(2..end)?);
+        rest = rest.get(end..)?;
+    }
+
+    let mut commitments = Vec::with_capacity(member_count as usize);
+    for _ in 0..member_count {
+        commitments.push(rest.get(..SHARE_COMMITMENT_SIZE)?);
+        rest = rest.get(SHARE_COMMITMENT_SIZE..)?;
+    }
+
+    Some(ThresholdEnvelope {
+        shares,
+        commitments,
+        nonce: rest.get(..AES_NONCE_SIZE)?,
+        ciphertext: rest.get(AES_NONCE_SIZE..)?,
+    })
+}
+
+/// Multiplication in GF(256) with the AES reduction polynomial.
+pub fn gf256_mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0u8;
+    while b != 0 {
+        if b & 1 == 1 {
+            product ^= a;
+        }
+
+        let carry = a & 0x80 != 0;
+        a = a.wrapping_shl(1);
+        if carry {
+            a ^= 0x1b;
+        }
+        b = b.wrapping_shr(1);
+    }
+
+    product
+}
+
+/// Multiplicative inverse in GF(256), `a^254`. Zero has no inverse and maps to zero.
+pub fn gf256_inv(a: u8) -> u8 {
+    let mut result = 1u8;
+    let mut base = a;
+    let mut exponent = 254u8;
+    while exponent != 0 {
+        if exponent & 1 == 1 {
+            result = gf256_mul(result, base);
+        }
+        base = gf256_mul(base, base);
+        exponent = exponent.wrapping_shr(1);
+    }
+
+    result
+}
+
+/// Recovers a secret from Shamir shares encoded as `x || y`, by Lagrange interpolation at zero.
+/// Shares must have distinct, non-zero `x` coordinates and equally long `y` values.
+pub fn combine_shares(shares: &[Vec<u8>]) -> Option<Vec<u8>> {
+    let points = shares
+        .iter()
+        .map(|share| share.split_first().map(|(x, y)| (*x, y)))
+        .collect::<Option<Vec<_>>>()?;
+
+    let (_, first) = points.first()?;
+    let secret_len = first.len();
+
+    let mut xs = BTreeMap::new();
+    for (x, y) in &points {
+        if *x == 0 || y.len() != secret_len || xs.insert(*x, ()).is_some() {
+            return None;
+        }
+    }
+
+    let mut secret = sp_std::vec![0u8; secret_len];
+    for (i, (x_i, y_i)) in points.iter().enumerate() {
+        // Lagrange basis polynomial of point `i` evaluated at zero
+        let basis = points
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| *j != i)
+            .fold(1u8, |acc, (_, (x_j, _))| {
+                gf256_mul(acc, gf256_mul(*x_j, gf256_inv(x_j ^ x_i)))
+            });
+
+        for (byte, y) in secret.iter_mut().zip(y_i.iter()) {
+            *byte ^= gf256_mul(*y, basis);
+        }
+    }
+
+    Some(secret)
+}
+
+/// Decrypts the AES-GCM part of an envelope with the recovered key and decodes the
+/// `(len, uid, weight)*, key` payload.
+pub fn decrypt_envelope(
+    aes_key: &[u8],
+    envelope: &ThresholdEnvelope,
+) -> Option<(Vec<(u16, u16)>, Vec<u8>)> {
+    let cipher = Aes256Gcm::new_from_slice(aes_key).ok()?;
+    let payload = cipher.decrypt(Nonce::from_slice(envelope.nonce), envelope.ciphertext).ok()?;
+
+    let length = u32::from_be_bytes(payload.get(..4)?.try_into().ok()?) as usize;
+    let weights_end = length.checked_mul(4)?.checked_add(4)?;
+
+    let weights = payload
+        .get(4..weights_end)?
+        .chunks_exact(4)
+        .map(|chunk| {
+            let uid = u16::from_be_bytes([*chunk.first()?, *chunk.get(1)?]);
+            let weight = u16::from_be_bytes([*chunk.get(2)?, *chunk.get(3)?]);
+            Some((uid, weight))
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    Some((weights, payload.get(weights_end..)?.to_vec()))
+}
+
+impl<T: Config> Pallet<T> {
+    /// Sets or clears the threshold decryption parameters of a subnet. Only the subnet founder
+    /// can call it. Weights encrypted under the previous setup can't be decrypted anymore, so
+    /// pending encrypted state is dropped at the start of the next block and its emission returned
+    /// to the subnet.
+    pub fn do_set_threshold_decryption(
+        origin: OriginFor<T>,
+        netuid: u16,
+        config: Option<ThresholdConfig>,
+    ) -> DispatchResult {
+        let key = ensure_signed(origin)?;
+
+        ensure!(
+            pallet_subspace::N::<T>::contains_key(netuid),
+            Error::<T>::NetworkDoesNotExist
+        );
+        ensure!(
+            pallet_subspace::Founder::<T>::get(netuid) == key,
+            Error::<T>::NotFounder
+        );
+
+        if let Some(config) = &config {
+            ensure!(
+                config.threshold > 0
+                    && config.threshold <= config.committee_size
+                    && u32::from(config.committee_size) <= T::MaxAuthorities::get(),
+                Error::<T>::InvalidThresholdDecryptionConfig
+            );
+        }
+
+        ThresholdDecryptionConfig::<T>::set(netuid, config);
+        PendingThresholdDecryptionReset::<T>::insert(netuid, ());
+
+        Self::deposit_event(Event::<T>::ThresholdDecryptionConfigured {
+            subnet_id: netuid,
+            config,
+        });
+
+        Ok(())
+    }
+
+    /// Drops the decryption assignment and every pending encrypted epoch of a subnet, returning
+    /// their emission back to pending emission.
+    pub(crate) fn reset_encrypted_subnet_state(netuid: u16) -> Weight {
+        let (epochs, total_emission) = ConsensusParameters::<T>::iter_prefix_values(netuid).fold(
+            (0u64, 0u64),
+            |(epochs, emission), params| {
+                (
+                    epochs.saturating_add(1),
+                    emission.saturating_add(params.token_emission),
+                )
+            },
+        );
+        update_pending_emission::<T>(netuid, &total_emission);
+
+        let cleared = [
+            ConsensusParameters::<T>::clear_prefix(netuid, u32::MAX, None),
+            WeightEncryptionData::<T>::clear_prefix(netuid, u32::MAX, None),
+            DecryptionShares::<T>::clear_prefix(netuid, u32::MAX, None),
+        ]
+        .iter()
+        .fold((0u64, 0u64), |(reads, writes), result| {
+            (
+                reads.saturating_add(u64::from(result.loops)),
+                writes.saturating_add(u64::from(result.unique)),
+            )
+        });
+        SubnetDecryptionData::<T>::remove(netuid);
+        SubnetDecryptionCommittee::<T>::remove(netuid);
+
+        T::DbWeight::get().reads_writes(
+            epochs.saturating_add(cleared.0).saturating_add(1),
+            cleared.1.saturating_add(3),
+        )
+    }
+
+    /// Drops the encrypted state of subnets whose threshold decryption parameters changed in the
+    /// previous block.
+    pub(crate) fn apply_threshold_decryption_resets() -> Weight {
+        PendingThresholdDecryptionReset::<T>::drain().fold(
+            T::DbWeight::get().reads(1),
+            |weight, (netuid, ())| {
+                weight
+                    .saturating_add(T::DbWeight::get().writes(1))
+                    .saturating_add(Self::reset_encrypted_subnet_state(netuid))
+            },
+        )
+    }
+
+    /// Dissolves committees that didn't deliver the decryption shares of the oldest pending epoch
+    /// within the subnet's maximum encryption period. Members that didn't submit their shares are
+    /// banned, and the pending epochs are dropped so a new committee starts over.
+    pub fn cancel_expired_committees(block: u64) -> Weight {
+        let db_weight = T::DbWeight::get();
+        let buffer = T::EncryptionPeriodBuffer::get();
+
+        let committees = SubnetDecryptionCommittee::<T>::iter().collect::<Vec<_>>();
+        let mut weight = db_weight.reads(u64::try_from(committees.len()).unwrap_or(u64::MAX));
+
+        for (netuid, committee) in committees {
+            let blocks = ConsensusParameters::<T>::iter_key_prefix(netuid).collect::<Vec<_>>();
+            weight = weight.saturating_add(
+                db_weight.reads(u64::try_from(blocks.len()).unwrap_or(u64::MAX).saturating_add(1)),
+            );
+
+            let Some(oldest) = blocks.into_iter().min() else {
+                continue;
+            };
+            let timeout = Self::get_max_encryption_interval(&netuid).saturating_add(buffer);
+            if block.saturating_sub(oldest) <= timeout {
+                continue;
+            }
+
+            for (member, _) in &committee.members {
+                weight = weight.saturating_add(db_weight.reads(1));
+                if DecryptionShares::<T>::contains_key(netuid, member) {
+                    continue;
+                }
+
+                Self::ban_offchain_worker(member);
+                weight = weight.saturating_add(db_weight.writes(1));
+                Self::deposit_event(Event::<T>::DecryptionNodeBanned {
+                    subnet_id: netuid,
+                    node_id: member.clone(),
+                });
+            }
+
+            weight = weight.saturating_add(Self::reset_encrypted_subnet_state(netuid));
+            Self::deposit_event(Event::<T>::DecryptionCommitteeExpired { subnet_id: netuid });
+        }
+
+        weight
+    }
+
+    /// Assigns `committee_size` active nodes to the subnet, continuing from the decryption node
+    /// cursor so committees are spread over all authorities.
+    pub(crate) fn assign_decryption_committee(
+        netuid: u16,
+        config: ThresholdConfig,
+        active_nodes: &[SubnetDecryptionInfo<T>],
+        block: u64,
+    ) {
+        if SubnetDecryptionCommittee::<T>::contains_key(netuid) {
+            return;
+        }
+
+        let committee_size = config.committee_size as usize;
+        if active_nodes.len() < committee_size {
+            log::warn!(
+                "subnet {netuid} needs {committee_size} decryption nodes, only {} are active",
+                active_nodes.len()
+            );
+            return;
+        }
+
+        let cursor = DecryptionNodeCursor::<T>::get() as usize;
+        let members = active_nodes
+            .iter()
+            .cycle()
+            .skip(cursor.checked_rem(active_nodes.len()).unwrap_or(0))
+            .take(committee_size)
+            .map(|node| (node.node_id.clone(), node.node_public_key.clone()))
+            .collect::<Vec<_>>();
+
+        DecryptionNodeCursor::<T>::set(cursor.saturating_add(committee_size) as u16);
+        SubnetDecryptionCommittee::<T>::insert(
+            netuid,
+            DecryptionCommittee {
+                members: members.clone(),
+                threshold: config.threshold,
+                formed_at: block,
+            },
+        );
+
+        Self::deposit_event(Event::<T>::DecryptionCommitteeAssigned {
+            subnet_id: netuid,
+            members: members.into_iter().map(|(id, _)| id).collect(),
+        });
+    }
+
+    /// Position of the account in the decryption committee of the subnet.
+    pub fn get_committee_index(netuid: u16, acc_id: &T::AccountId) -> Option<u8> {
+        SubnetDecryptionCommittee::<T>::get(netuid)?
+            .members
+            .iter()
+            .position(|(member, _)| member == acc_id)
+            .and_then(|index| index.try_into().ok())
+    }
+
+    /// Subnets where the account is a member of the decryption committee, with its position.
+    pub fn get_committee_subnets(acc_id: &T::AccountId) -> Vec<(u16, u8)> {
+        SubnetDecryptionCommittee::<T>::iter_keys()
+            .filter_map(|netuid| Some((netuid, Self::get_committee_index(netuid, acc_id)?)))
+            .collect()
+    }
+
+    /// Stores the decryption shares of a committee member. Epochs whose weights all got enough
+    /// shares are decrypted and run through consensus, oldest first, while the later ones wait
+    /// for more shares. Committees that served for the rotation interval are dissolved once no
+    /// epoch is pending, so the next epochs go to a new committee.
+    pub fn handle_decryption_shares(
+        netuid: u16,
+        acc_id: &T::AccountId,
+        shares: Vec<BlockDecryptionShares>,
+    ) -> DispatchResult {
+        let committee =
+            SubnetDecryptionCommittee::<T>::get(netuid).ok_or(Error::<T>::NetworkDoesNotExist)?;
+        let index = committee
+            .members
+            .iter()
+            .position(|(member, _)| member == acc_id)
+            .ok_or(Error::<T>::NotDecryptionCommitteeMember)?;
+        ensure!(
+            Self::shares_match_commitments(netuid, index, &shares),
+            Error::<T>::InvalidDecryptionShare
+        );
+
+        DecryptionShares::<T>::insert(netuid, acc_id, shares);
+
+        let submitted = DecryptionShares::<T>::iter_prefix(netuid).count();
+        if submitted < committee.threshold as usize {
+            return Ok(());
+        }
+
+        let weights = Self::combine_decryption_shares(netuid, &committee);
+        if weights.is_empty() {
+            return Ok(());
+        }
+
+        let decrypted = weights.iter().map(|(block, _)| *block).collect::<BTreeSet<_>>();
+        Self::remove_decryption_shares(netuid, &decrypted);
+
+        Self::deposit_event(Event::<T>::ThresholdDecryptionCompleted { subnet_id: netuid });
+        Self::handle_decrypted_epochs(netuid, weights);
+
+        let block = pallet_subspace::Pallet::<T>::get_current_block_number();
+        let pending = ConsensusParameters::<T>::iter_key_prefix(netuid).next().is_some();
+        if !pending
+            && block.saturating_sub(committee.formed_at) >= T::DecryptionNodeRotationInterval::get()
+        {
+            // Weights encrypted to the old committee can't be decrypted by the new one
+            SubnetDecryptionCommittee::<T>::remove(netuid);
+            Self::cleanup_weight_encryption_data(netuid);
+            Self::deposit_event(Event::<T>::DecryptionCommitteeRotated { subnet_id: netuid });
+        }
+
+        Ok(())
+    }
+
+    /// Drops the shares of the decrypted epochs, and the submissions left without any.
+    fn remove_decryption_shares(netuid: u16, decrypted: &BTreeSet<u64>) {
+        let submissions = DecryptionShares::<T>::iter_prefix(netuid).collect::<Vec<_>>();
+        for (member, mut shares) in submissions {
+            shares.retain(|(block, _)| !decrypted.contains(block));
+            if shares.is_empty() {
+                DecryptionShares::<T>::remove(netuid, member);
+            } else {
+                DecryptionShares::<T>::insert(netuid, member, shares);
+            }
+        }
+    }
+
+    /// Checks every submitted share against the commitment of the member in the module's
+    /// envelope. Shares of epochs or modules that aren't pending are ignored when combining.
+    fn shares_match_commitments(
+        netuid: u16,
+        index: usize,
+        shares: &[BlockDecryptionShares],
+    ) -> bool {
+        shares.iter().all(|(block, uid_shares)| {
+            let Some(params) = ConsensusParameters::<T>::get(netuid, block) else {
+                return true;
+            };
+            let envelopes = params
+                .modules
+                .values()
+                .map(|module| (module.uid, &module.weight_encrypted))
+                .collect::<BTreeMap<_, _>>();
+
+            uid_shares.iter().all(|(uid, share)| {
+                let Some(encrypted) = envelopes.get(uid) else {
+                    return true;
+                };
+                parse_envelope(encrypted)
+                    .and_then(|envelope| envelope.commitments.get(index).copied())
+                    .is_some_and(|commitment| commitment == blake2_256(share).as_slice())
+            })
+        })
+    }
+
+    /// Decrypts the pending epochs whose encrypted weights are settled, oldest first, stopping
+    /// at the first epoch that still waits for shares. A module's weights are settled once
+    /// `threshold` members sent a share for them, or once every member reported on the epoch.
+    /// Modules whose key can't be recovered, or whose weights fail to decrypt, get empty
+    /// weights, the same way a single decryption node reports them.
+    fn combine_decryption_shares(
+        netuid: u16,
+        committee: &DecryptionCommittee<T>,
+    ) -> Vec<BlockWeights> {
+        let threshold = committee.threshold as usize;
+        let submitted: Vec<BTreeMap<u64, BTreeMap<u16, Vec<u8>>>> =
+            DecryptionShares::<T>::iter_prefix_values(netuid)
+                .map(|blocks| {
+                    blocks
+                        .into_iter()
+                        .map(|(block, shares)| (block, shares.into_iter().collect()))
+                        .collect()
+                })
+                .collect();
+
+        let mut epochs = ConsensusParameters::<T>::iter_prefix(netuid).collect::<Vec<_>>();
+        epochs.sort_by_key(|(block, _)| *block);
+
+        let mut decrypted = Vec::new();
+        for (block, params) in epochs {
+            let reports =
+                submitted.iter().filter_map(|blocks| blocks.get(&block)).collect::<Vec<_>>();
+            let everyone_reported = reports.len() >= committee.members.len();
+
+            let mut uid_weights = Vec::with_capacity(params.modules.len());
+            for module in params.modules.values() {
+                let uid = module.uid;
+                let Some(envelope) = parse_envelope(&module.weight_encrypted) else {
+                    uid_weights.push((uid, Vec::new(), Vec::new()));
+                    continue;
+                };
+
+                let shares = reports
+                    .iter()
+                    .filter_map(|shares| shares.get(&uid).cloned())
+                    .take(threshold)
+                    .collect::<Vec<_>>();
+                if shares.len() < threshold && !everyone_reported {
+                    return decrypted;
+                }
+
+                let weights = (shares.len() == threshold)
+                    .then(|| combine_shares(&shares))
+                    .flatten()
+                    .and_then(|aes_key| decrypt_envelope(&aes_key, &envelope));
+
+                match weights {
+                    Some((weights, key)) => uid_weights.push((uid, weights, key)),
+                    None => uid_weights.push((uid, Vec::new(), Vec::new())),
+                }
+            }
+
+            decrypted.push((block, uid_weights));
+        }
+
+        decrypted
+    }
+}
@@ -0,0 +1,76 @@
+use super::*;
+use frame_support::ensure;
+use pallet_subspace::N;
+use sp_runtime::TryRuntimeError;
+use sp_std::collections::btree_set::BTreeSet;
+
+impl<T: Config> Pallet<T> {
+    /// Checks the storage invariants of the pallet. Ran by `try_state` after every block in
+    /// try-runtime checks and in tests.
+    pub(crate) fn do_try_state() -> Result<(), TryRuntimeError> {
+        Self::check_weights_storage()?;
+        Self::check_epoch_storage()?;
+        Self::check_decryption_storage()?;
+
+        Ok(())
+    }
+
+    /// Weights are only kept for modules of live subnets, `remove_module` moves the weights of
+    /// the replacing module into the removed uid.
+    fn check_weights_storage() -> Result<(), TryRuntimeError> {
+        for (netuid, uid) in Weights::<T>::iter_keys() {
+            ensure!(N::<T>::contains_key(netuid), "Weights of a removed subnet");
+
+            let n = N::<T>::get(netuid);
+            if uid >= n {
+                log::error!("subnet {netuid} has weights for uid {uid}, but only {n} modules");
+                return Err("Weights holds a uid past N".into());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// A deferred epoch is queued once, however many tempos it waits for. Consensus changes
+    /// wait for the epoch of live subnets.
+    fn check_epoch_storage() -> Result<(), TryRuntimeError> {
+        let deferred = DeferredEpochs::<T>::get();
+        let unique = deferred.iter().collect::<BTreeSet<_>>();
+        ensure!(
+            unique.len() == deferred.len(),
+            "DeferredEpochs holds a subnet twice"
+        );
+        ensure!(
+            PendingConsensusChange::<T>::iter_keys().all(N::<T>::contains_key),
+            "PendingConsensusChange of a removed subnet"
+        );
+
+        Ok(())
+    }
+
+    /// Threshold setups must be satisfiable by their committees, and resets only wait for live
+    /// subnets.
+    fn check_decryption_storage() -> Result<(), TryRuntimeError> {
+        for (_, config) in ThresholdDecryptionConfig::<T>::iter() {
+            ensure!(
+                config.threshold > 0 && config.threshold <= config.committee_size,
+                "ThresholdDecryptionConfig threshold out of the committee size"
+            );
+        }
+
+        for (_, committee) in SubnetDecryptionCommittee::<T>::iter() {
+            ensure!(
+                committee.threshold > 0
+                    && usize::from(committee.threshold) <= committee.members.len(),
+                "decryption committee is smaller than its threshold"
+            );
+        }
+
+        ensure!(
+            PendingThresholdDecryptionReset::<T>::iter_keys().all(N::<T>::contains_key),
+            "PendingThresholdDecryptionReset of a removed subnet"
+        );
+
+        Ok(())
+    }
+}
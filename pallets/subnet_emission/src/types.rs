@@ -3,6 +3,8 @@ use super::*;
 pub type PublicKey = (Vec<u8>, Vec<u8>);
 pub type BlockWeights = (u64, Vec<(u16, Vec<(u16, u16)>, Vec<u8>)>);
 pub type KeylessBlockWeights = (u64, Vec<(u16, Vec<(u16, u16)>)>);
+/// (Block, [(Module uid, Decryption share)])
+pub type BlockDecryptionShares = (u64, Vec<(u16, Vec<u8>)>);
 
 /// Information about a subnet decryption node, including its identity, activation status,
 /// and rotation details.
@@ -18,3 +20,21 @@ where
     pub last_keep_alive: u64,
     pub rotating_from: Option<T::AccountId>,
 }
+
+/// Committee size and number of shares required to decrypt the weights of a subnet.
+#[derive(Clone, Copy, Encode, Decode, TypeInfo, Debug, PartialEq, Eq)]
+pub struct ThresholdConfig {
+    pub committee_size: u8,
+    pub threshold: u8,
+}
+
+/// Authorities sharing the decryption of a subnet's weights, in share order.
+#[derive(Clone, Encode, Decode, TypeInfo, Debug)]
+pub struct DecryptionCommittee<T>
+where
+    T: Config + pallet_subspace::Config + TypeInfo,
+{
+    pub members: Vec<(T::AccountId, PublicKey)>,
+    pub threshold: u8,
+    pub formed_at: u64,
+}
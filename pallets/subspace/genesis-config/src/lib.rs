@@ -14,6 +14,8 @@ pub struct ConfigSubnet<Name, AccountId: Ord + PartialOrd + PartialEq + Eq> {
     pub min_allowed_weights: Option<u16>,
     pub max_allowed_weights: Option<u16>,
     pub max_allowed_uids: Option<u16>,
+    #[serde(default)]
+    pub use_weights_encryption: Option<bool>,
     pub modules: Vec<ConfigModule<Name, AccountId>>,
 }
 
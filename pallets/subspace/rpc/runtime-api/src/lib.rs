@@ -41,8 +41,30 @@ pub struct ModuleInfo {
     pub stats: ModuleStats,
 }
 
+#[derive(Decode, Encode, PartialEq, Eq, Clone, Debug, TypeInfo, Serialize, Deserialize)]
+pub struct TreasuryGrantInfo {
+    pub id: u64,
+    pub proposal_id: u64,
+    pub recipient: AccountId,
+    pub amount_per_period: u64,
+    pub period_length: u64,
+    pub periods: u32,
+    pub paid_periods: u32,
+    pub start_block: u64,
+    pub next_payout_block: u64,
+    /// What the treasury still has to pay over the remaining periods.
+    pub remaining_liability: u64,
+}
+
 sp_api::decl_runtime_apis! {
     pub trait SubspaceRuntimeApi {
         fn get_module_info(key: AccountId, netuid: u16) -> ModuleInfo;
     }
+
+    pub trait GovernanceRuntimeApi {
+        /// The treasury grants that still have periods to pay.
+        fn get_active_treasury_grants() -> Vec<TreasuryGrantInfo>;
+        /// How much the treasury owes over all active grants.
+        fn get_treasury_grant_liabilities() -> u64;
+    }
 }
@@ -224,7 +224,9 @@ benchmarks! {
         params.max_allowed_validators,
         params.use_weights_encryption,
         params.copier_margin,
-        params.max_encryption_period
+        params.max_encryption_period,
+        params.pruning_policy,
+        params.registration_mode
     )
     // 11
 
@@ -33,6 +33,8 @@ pub mod macros;
 pub mod math;
 pub mod network {
     pub mod module;
+    pub mod operator;
+    pub mod pool;
     pub mod registration;
     pub mod staking;
     pub mod subnet;
@@ -47,13 +49,15 @@ pub mod params {
 pub mod migrations;
 pub mod rpc;
 pub mod selections;
+#[cfg(feature = "try-runtime")]
+mod try_state;
 pub mod weights;
 
 pub use crate::params::{
-    burn::{BurnType, GeneralBurnConfiguration},
+    burn::{BurnType, GeneralBurnConfiguration, RegistrationCost},
     global::GlobalParams,
     module::{ModuleChangeset, ModuleParams},
-    subnet::{DefaultSubnetParams, SubnetChangeset, SubnetParams},
+    subnet::{DefaultSubnetParams, PruningPolicy, RegistrationMode, SubnetChangeset, SubnetParams},
 };
 use selections::{config, dispatches, errors, events, genesis, hooks};
 
@@ -71,7 +75,7 @@ pub mod pallet {
     use frame_support::{
         dispatch::DispatchResult,
         pallet_prelude::{ValueQuery, *},
-        traits::Currency,
+        traits::{Currency, ReservableCurrency},
         Identity,
     };
     use frame_system::pallet_prelude::*;
@@ -87,10 +91,10 @@ pub mod pallet {
     pub type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
 
     #[cfg(feature = "testnet")]
-    const STORAGE_VERSION: StorageVersion = StorageVersion::new(34);
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(35);
 
     #[cfg(not(feature = "testnet"))]
-    const STORAGE_VERSION: StorageVersion = StorageVersion::new(15);
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(16);
 
     #[pallet::pallet]
     #[pallet::storage_version(STORAGE_VERSION)]
@@ -108,6 +112,9 @@ pub mod pallet {
             Address,
             Metadata,
             RegistrationBlock,
+            RegistrationAllowlist,
+            PendingRegistrations,
+            RegistrationDeposits,
         },
         maps: {
             BondsMovingAverage: u64 = 900_000,
@@ -130,6 +137,8 @@ pub mod pallet {
             MaxEncryptionPeriod: Option<u64> = Some(10_800),
             CopierMargin: I64F64 = I64F64::from_num(0),
             UseWeightsEncryption,
+            SubnetPruningPolicy,
+            SubnetRegistrationMode,
             AlphaValues: (u16, u16) = (45875, 58982),
             MinValidatorStake,
             MaxAllowedUids: u16 = 420,
@@ -273,6 +282,76 @@ pub mod pallet {
     #[pallet::storage]
     pub type UseWeightsEncryption<T: Config> = StorageMap<_, Identity, u16, bool, ValueQuery>;
 
+    /// How modules are picked for deregistration when the subnet is full
+    #[pallet::storage]
+    pub type SubnetPruningPolicy<T: Config> =
+        StorageMap<_, Identity, u16, PruningPolicy, ValueQuery>;
+
+    /// Who may register modules on the subnet
+    #[pallet::storage]
+    pub type SubnetRegistrationMode<T: Config> =
+        StorageMap<_, Identity, u16, RegistrationMode, ValueQuery>;
+
+    /// Module keys the founder allows to register, used by the non-open registration modes
+    #[pallet::storage]
+    pub type RegistrationAllowlist<T: Config> =
+        StorageDoubleMap<_, Identity, u16, Blake2_128Concat, T::AccountId, ()>;
+
+    /// A registration cost held in reserve, see [`RegistrationCost::Deposit`]
+    #[derive(Encode, Decode, CloneNoBound, PartialEqNoBound, EqNoBound, TypeInfo, DebugNoBound)]
+    #[scale_info(skip_type_params(T))]
+    pub struct RegistrationDeposit<T: Config> {
+        /// Account the deposit is reserved from
+        pub payer: T::AccountId,
+        pub amount: u64,
+        /// Terms at registration time, later config changes don't apply
+        pub min_tenure: u64,
+        pub refund: Percent,
+    }
+
+    /// How the registration cost of a module was paid
+    #[derive(Encode, Decode, CloneNoBound, PartialEqNoBound, EqNoBound, TypeInfo, DebugNoBound)]
+    #[scale_info(skip_type_params(T))]
+    pub enum RegistrationCharge<T: Config> {
+        Burned(u64),
+        Reserved(RegistrationDeposit<T>),
+    }
+
+    /// A registration queued until the subnet founder approves or rejects it
+    #[derive(Encode, Decode, CloneNoBound, PartialEqNoBound, EqNoBound, TypeInfo, DebugNoBound)]
+    #[scale_info(skip_type_params(T))]
+    pub struct PendingRegistration<T: Config> {
+        /// Account that paid the burn, refunded on rejection
+        pub payer: T::AccountId,
+        pub name: Vec<u8>,
+        pub address: Vec<u8>,
+        pub metadata: Option<Vec<u8>>,
+        /// Registration cost paid when the request was made
+        pub charge: RegistrationCharge<T>,
+        pub requested_at: u64,
+        /// Block the request is dropped and refunded at if the founder hasn't answered it
+        pub expires_at: u64,
+    }
+
+    /// Registration requests of subnets in `FounderApproval` mode, by module key
+    #[pallet::storage]
+    pub type PendingRegistrations<T: Config> =
+        StorageDoubleMap<_, Identity, u16, Blake2_128Concat, T::AccountId, PendingRegistration<T>>;
+
+    /// Number of blocks a queued registration waits for the founder before being refunded
+    #[pallet::storage]
+    pub type PendingRegistrationTimeout<T> = StorageValue<_, u64, ValueQuery, ConstU64<10_800>>;
+
+    /// Queued registrations by the block they expire at
+    #[pallet::storage]
+    pub type PendingRegistrationExpiries<T: Config> =
+        StorageDoubleMap<_, Identity, u64, Blake2_128Concat, (u16, T::AccountId), ()>;
+
+    /// Registration deposits of the modules, by module key
+    #[pallet::storage]
+    pub type RegistrationDeposits<T: Config> =
+        StorageDoubleMap<_, Identity, u16, Blake2_128Concat, T::AccountId, RegistrationDeposit<T>>;
+
     #[pallet::storage]
     pub type AlphaValues<T: Config> =
         StorageMap<_, Identity, u16, (u16, u16), ValueQuery, AlphaValuesDefaultValue>;
@@ -469,6 +548,86 @@ pub mod pallet {
     pub type WeightSettingDelegation<T: Config> =
         StorageDoubleMap<_, Identity, u16, Identity, T::AccountId, T::AccountId>;
 
+    /// Operational calls a module key can let its operator keys sign
+    #[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, TypeInfo, Debug)]
+    pub enum OperatorPermission {
+        /// Setting, encrypting and delegating the module weights
+        SetWeights,
+        /// Updating the module name, address, fees and metadata
+        UpdateModule,
+    }
+
+    /// Hot keys allowed to sign operational calls for a module key, with the permissions granted
+    /// to each. Unlike `WeightSettingDelegation`, which hands weight control to another module,
+    /// operators aren't modules themselves and can't touch funds or deregister the module.
+    #[pallet::storage]
+    pub type OperatorKeys<T: Config> = StorageDoubleMap<
+        _,
+        Identity,
+        T::AccountId,
+        Identity,
+        T::AccountId,
+        BTreeSet<OperatorPermission>,
+        ValueQuery,
+    >;
+
+    /// The module key each operator key acts for
+    #[pallet::storage]
+    pub type OperatedModule<T: Config> = StorageMap<_, Identity, T::AccountId, T::AccountId>;
+
+    /// Operator keys offered by a module key, with the permissions they get once the operator
+    /// key accepts them. Nothing is granted until then.
+    #[pallet::storage]
+    pub type PendingOperatorKeys<T: Config> = StorageDoubleMap<
+        _,
+        Identity,
+        T::AccountId,
+        Identity,
+        T::AccountId,
+        BTreeSet<OperatorPermission>,
+    >;
+
+    // --- Nomination Pools ---
+
+    /// A pool staking its members' balances across the modules chosen by its manager
+    #[derive(Encode, Decode, CloneNoBound, PartialEqNoBound, EqNoBound, TypeInfo, DebugNoBound)]
+    #[scale_info(skip_type_params(T))]
+    pub struct NominationPool<T: Config> {
+        /// Account that picks the modules the pool stakes to
+        pub manager: T::AccountId,
+        /// Account holding the pool stake, derived from the pool id
+        pub account: T::AccountId,
+        pub modules: Vec<T::AccountId>,
+        /// Sum of the shares of every member
+        pub total_shares: u64,
+        /// Stake returned to the pool account by deregistered modules, not yet restaked or paid
+        /// out. Tracked apart from the account balance, so donations don't move the share price
+        pub idle: u64,
+    }
+
+    /// A member's part of a pool
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, TypeInfo, Debug, Default)]
+    pub struct PoolMember {
+        pub shares: u64,
+        /// Amount joined with and not yet withdrawn, anything above it is claimable
+        pub deposited: u64,
+    }
+
+    #[pallet::storage]
+    pub type NominationPools<T: Config> = StorageMap<_, Identity, u32, NominationPool<T>>;
+
+    #[pallet::storage]
+    pub type NextPoolId<T> = StorageValue<_, u32, ValueQuery>;
+
+    /// Pool ids by their stake holding account
+    #[pallet::storage]
+    pub type PoolAccounts<T: Config> = StorageMap<_, Identity, T::AccountId, u32>;
+
+    /// Pool memberships by member account and pool id
+    #[pallet::storage]
+    pub type PoolMembers<T: Config> =
+        StorageDoubleMap<_, Identity, T::AccountId, Identity, u32, PoolMember>;
+
     #[pallet::storage]
     pub type Bridged<T: Config> = StorageMap<_, Identity, T::AccountId, u64, ValueQuery>;
     // --- Module Fees ---
@@ -501,6 +660,31 @@ pub mod pallet {
     #[pallet::storage]
     pub type MinFees<T> = StorageValue<_, MinimumFees, ValueQuery, DefaultMinimumFees<T>>;
 
+    /// Contains the maximum allowed values for delegation fees
+    #[derive(Encode, Decode, Clone, PartialEq, TypeInfo, Debug, Eq)]
+    pub struct MaximumFees {
+        /// Maximum fee for stake delegation
+        pub stake_delegation_fee: Percent,
+        /// Maximum fee for validator weight delegation
+        pub validator_weight_fee: Percent,
+    }
+
+    #[pallet::type_value]
+    pub fn DefaultMaximumFees<T: Config>() -> MaximumFees {
+        MaximumFees {
+            stake_delegation_fee: Percent::from_percent(100),
+            validator_weight_fee: Percent::from_percent(100),
+        }
+    }
+
+    /// Storage for maximum fees that can be updated via runtime
+    #[pallet::storage]
+    pub type MaxFees<T> = StorageValue<_, MaximumFees, ValueQuery, DefaultMaximumFees<T>>;
+
+    /// Number of blocks a validator fee increase is announced for before taking effect
+    #[pallet::storage]
+    pub type FeeAnnouncementPeriod<T> = StorageValue<_, u64, ValueQuery, ConstU64<10_800>>;
+
     /// A fee structure containing delegation fees for both stake and validator weight
     #[derive(Encode, Decode, Clone, PartialEq, TypeInfo, Debug, Eq)]
     pub struct ValidatorFees {
@@ -529,6 +713,25 @@ pub mod pallet {
     pub type ValidatorFeeConfig<T: Config> =
         StorageMap<_, Identity, T::AccountId, ValidatorFees, ValueQuery, DefaultValidatorFees<T>>;
 
+    /// A fee increase waiting for its announcement period to end
+    #[derive(Encode, Decode, Clone, PartialEq, TypeInfo, Debug, Eq)]
+    pub struct PendingFeeChange {
+        /// The fees the validator will charge once the change is active
+        pub fees: ValidatorFees,
+        /// Block at which the fees replace the validator's current ones
+        pub activation_block: u64,
+    }
+
+    /// Maps validator accounts to their announced fee increase
+    #[pallet::storage]
+    pub type PendingValidatorFees<T: Config> =
+        StorageMap<_, Identity, T::AccountId, PendingFeeChange>;
+
+    /// Validators with an announced fee increase, by the block it activates at
+    #[pallet::storage]
+    pub type PendingFeeActivations<T: Config> =
+        StorageDoubleMap<_, Identity, u64, Identity, T::AccountId, ()>;
+
     impl ValidatorFees {
         /// Creates a new ValidatorFees instance with validation against minimum fees
         pub fn new<T: Config>(
@@ -67,3 +67,101 @@ pub mod v15 {
         }
     }
 }
+
+pub mod v16 {
+    use frame_support::{traits::OnRuntimeUpgrade, weights::Weight};
+    use sp_std::marker::PhantomData;
+
+    use super::*;
+
+    pub mod old_storage {
+        use super::*;
+        use frame_support::pallet_prelude::{Decode, Encode};
+
+        #[derive(Decode, Encode)]
+        pub struct GeneralBurnConfiguration {
+            pub min_burn: u64,
+            pub max_burn: u64,
+            pub adjustment_alpha: u64,
+            pub target_registrations_interval: u16,
+            pub target_registrations_per_interval: u16,
+            pub max_registrations_per_interval: u16,
+        }
+
+        impl GeneralBurnConfiguration {
+            pub fn migrate<T: Config>(self) -> crate::GeneralBurnConfiguration<T> {
+                crate::GeneralBurnConfiguration {
+                    min_burn: self.min_burn,
+                    max_burn: self.max_burn,
+                    adjustment_alpha: self.adjustment_alpha,
+                    target_registrations_interval: self.target_registrations_interval,
+                    target_registrations_per_interval: self.target_registrations_per_interval,
+                    max_registrations_per_interval: self.max_registrations_per_interval,
+                    registration_cost: RegistrationCost::Burn,
+                    _pd: PhantomData,
+                }
+            }
+        }
+    }
+
+    /// Adds the registration cost to the burn configurations, keeping every subnet on burns.
+    pub struct MigrateToV16<T>(sp_std::marker::PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for MigrateToV16<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let on_chain_version = StorageVersion::get::<Pallet<T>>();
+            if on_chain_version != 15 {
+                log::info!("Storage v16 already updated");
+                return Weight::zero();
+            }
+
+            let weight = translate_burn_configs::<T>();
+
+            log::info!("Migrating storage to v16");
+            StorageVersion::new(16).put::<Pallet<T>>();
+            weight.saturating_add(T::DbWeight::get().reads_writes(1, 1))
+        }
+    }
+
+    /// Re-encodes the module and subnet burn configurations with the registration cost.
+    pub fn translate_burn_configs<T: Config>() -> Weight {
+        let mut translated = 1u64;
+        ModuleBurnConfig::<T>::translate(|_, config: old_storage::GeneralBurnConfiguration| {
+            translated = translated.saturating_add(1);
+            Some(config.migrate())
+        });
+        let _ = SubnetBurnConfig::<T>::translate(
+            |config: Option<old_storage::GeneralBurnConfiguration>| {
+                config.map(|config| config.migrate())
+            },
+        );
+
+        T::DbWeight::get().reads_writes(translated, translated)
+    }
+}
+
+/// Testnet versioned its storage apart from mainnet, and sits at version 34 with the data mainnet
+/// had at version 15. This runs the mainnet steps since then.
+pub mod testnet {
+    use frame_support::{traits::OnRuntimeUpgrade, weights::Weight};
+
+    use super::*;
+
+    pub struct MigrateToV35<T>(sp_std::marker::PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for MigrateToV35<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let on_chain_version = StorageVersion::get::<Pallet<T>>();
+            if on_chain_version != 34 {
+                log::info!("Testnet storage v35 already updated");
+                return T::DbWeight::get().reads(1);
+            }
+
+            let weight = v16::translate_burn_configs::<T>();
+
+            log::info!("Migrating testnet storage to v35");
+            StorageVersion::new(35).put::<Pallet<T>>();
+            weight.saturating_add(T::DbWeight::get().reads_writes(1, 1))
+        }
+    }
+}
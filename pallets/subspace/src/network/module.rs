@@ -6,11 +6,10 @@ pub struct SubnetDistributionParameters;
 
 impl<T: Config> Pallet<T> {
     pub fn do_update_module(
-        origin: T::RuntimeOrigin,
+        key: T::AccountId,
         netuid: u16,
         changeset: ModuleChangeset<T>,
     ) -> DispatchResult {
-        let key = ensure_signed(origin)?;
         let uid: u16 = Self::get_uid_for_key(netuid, &key).ok_or(Error::<T>::ModuleDoesNotExist)?;
         changeset.apply(netuid, key, uid)?;
         Ok(())
@@ -65,6 +64,11 @@ impl<T: Config> Pallet<T> {
             module_key
         );
 
+        // --- Refund The Registration Deposit ---
+        // Voluntary deregistrations, pruning and subnet deregistrations settle it before
+        // getting here.
+        Self::refund_registration_deposit(netuid, &module_key);
+
         // --- Remove All Module Related Storage ---
         StorageHandler::remove_all::<T>(netuid, uid, replace_uid, &module_key, &replace_key)?;
         <T as SubnetEmissionApi<T::AccountId>>::clear_module_includes(
@@ -84,6 +88,8 @@ impl<T: Config> Pallet<T> {
         // registered on any subnet.
         if Uids::<T>::iter().all(|(_, key, _)| key != module_key) {
             ValidatorFeeConfig::<T>::remove(&module_key);
+            Self::remove_pending_fees(&module_key);
+            Self::remove_operator_keys(&module_key);
             Self::remove_stake_from_storage(&module_key);
         }
 
@@ -100,4 +106,89 @@ impl<T: Config> Pallet<T> {
 
         Ok(())
     }
+
+    /// Updates the fees of a validator. Lowered fees apply right away, while raised ones are
+    /// announced and only take effect after `FeeAnnouncementPeriod` blocks, and at least on the
+    /// next block. A new update replaces any announcement still pending.
+    pub fn update_validator_fees(key: &T::AccountId, fees: ValidatorFees) {
+        let current = ValidatorFeeConfig::<T>::get(key);
+        let instant = ValidatorFees {
+            stake_delegation_fee: fees.stake_delegation_fee.min(current.stake_delegation_fee),
+            validator_weight_fee: fees.validator_weight_fee.min(current.validator_weight_fee),
+        };
+
+        ValidatorFeeConfig::<T>::insert(key, instant.clone());
+        if instant != current {
+            Self::deposit_event(Event::ValidatorFeesUpdated(key.clone(), instant.clone()));
+        }
+
+        if fees == instant {
+            Self::remove_pending_fees(key);
+            return;
+        }
+
+        // Repeating the announcement doesn't push back its activation
+        if PendingValidatorFees::<T>::get(key).is_some_and(|pending| pending.fees == fees) {
+            return;
+        }
+
+        Self::remove_pending_fees(key);
+        let activation_block = Self::get_current_block_number()
+            .saturating_add(FeeAnnouncementPeriod::<T>::get().max(1));
+        PendingFeeActivations::<T>::insert(activation_block, key, ());
+        PendingValidatorFees::<T>::insert(
+            key,
+            PendingFeeChange {
+                fees: fees.clone(),
+                activation_block,
+            },
+        );
+        Self::deposit_event(Event::ValidatorFeesAnnounced(
+            key.clone(),
+            fees,
+            activation_block,
+        ));
+    }
+
+    /// Activates the announced fee increases whose period ends at this block. Fees are capped to
+    /// the current `MaxFees`, in case it was lowered during the announcement.
+    pub fn apply_pending_fee_changes(block: u64) -> Weight {
+        let mut reads: u64 = 2;
+        let mut writes: u64 = 0;
+
+        let max_fees = MaxFees::<T>::get();
+        for (key, ()) in PendingFeeActivations::<T>::drain_prefix(block) {
+            reads = reads.saturating_add(1);
+            writes = writes.saturating_add(1);
+
+            let Some(change) = PendingValidatorFees::<T>::take(&key) else {
+                continue;
+            };
+
+            let fees = ValidatorFees {
+                stake_delegation_fee: change
+                    .fees
+                    .stake_delegation_fee
+                    .min(max_fees.stake_delegation_fee),
+                validator_weight_fee: change
+                    .fees
+                    .validator_weight_fee
+                    .min(max_fees.validator_weight_fee),
+            };
+
+            ValidatorFeeConfig::<T>::insert(&key, fees.clone());
+            writes = writes.saturating_add(2);
+
+            Self::deposit_event(Event::ValidatorFeesUpdated(key, fees));
+        }
+
+        T::DbWeight::get().reads_writes(reads, writes)
+    }
+
+    /// Drops the fee increase announced by the validator, if any.
+    pub(crate) fn remove_pending_fees(key: &T::AccountId) {
+        if let Some(pending) = PendingValidatorFees::<T>::take(key) {
+            PendingFeeActivations::<T>::remove(pending.activation_block, key);
+        }
+    }
 }
@@ -0,0 +1,146 @@
+use crate::*;
+use frame_support::pallet_prelude::DispatchResult;
+use sp_runtime::DispatchError;
+
+impl<T: Config> Pallet<T> {
+    /// Maximum number of operator keys a single module key can have.
+    pub const MAX_OPERATOR_KEYS: usize = 16;
+
+    /// Resolves the module key an operational call acts for. Module keys act for themselves,
+    /// operator keys act for the module that granted them `permission`.
+    pub fn ensure_module_operator(
+        origin: T::RuntimeOrigin,
+        permission: OperatorPermission,
+    ) -> Result<T::AccountId, DispatchError> {
+        let key = ensure_signed(origin)?;
+
+        let Some(module_key) = OperatedModule::<T>::get(&key) else {
+            return Ok(key);
+        };
+
+        ensure!(
+            OperatorKeys::<T>::get(&module_key, &key).contains(&permission),
+            Error::<T>::NotModuleOperator
+        );
+
+        Ok(module_key)
+    }
+
+    /// Offers the operator key the given permissions over the calling module key. They only
+    /// apply once the operator key accepts them, unless it already operates the module, in which
+    /// case its permissions are replaced right away.
+    pub fn do_set_operator_key(
+        origin: T::RuntimeOrigin,
+        operator: T::AccountId,
+        permissions: Vec<OperatorPermission>,
+    ) -> DispatchResult {
+        let key = ensure_signed(origin)?;
+
+        ensure!(
+            Self::is_registered(None, &key),
+            Error::<T>::ModuleDoesNotExist
+        );
+        ensure!(
+            !permissions.is_empty(),
+            Error::<T>::InvalidOperatorPermissions
+        );
+        ensure!(
+            operator != key && !Self::is_registered(None, &operator),
+            Error::<T>::OperatorKeyInUse
+        );
+
+        let permissions: BTreeSet<_> = permissions.into_iter().collect();
+
+        match OperatedModule::<T>::get(&operator) {
+            Some(module_key) => {
+                ensure!(module_key == key, Error::<T>::OperatorKeyInUse);
+
+                OperatorKeys::<T>::insert(&key, &operator, &permissions);
+                Self::deposit_event(Event::OperatorKeySet(
+                    key,
+                    operator,
+                    permissions.into_iter().collect(),
+                ));
+            }
+            None => {
+                ensure!(
+                    PendingOperatorKeys::<T>::contains_key(&key, &operator)
+                        || Self::operator_key_count(&key) < Self::MAX_OPERATOR_KEYS,
+                    Error::<T>::TooManyOperatorKeys
+                );
+
+                PendingOperatorKeys::<T>::insert(&key, &operator, &permissions);
+                Self::deposit_event(Event::OperatorKeyOffered(
+                    key,
+                    operator,
+                    permissions.into_iter().collect(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Makes the calling key an operator of the module key, with the permissions it offered.
+    pub fn do_accept_operator_key(
+        origin: T::RuntimeOrigin,
+        module_key: T::AccountId,
+    ) -> DispatchResult {
+        let operator = ensure_signed(origin)?;
+
+        let permissions = PendingOperatorKeys::<T>::get(&module_key, &operator)
+            .ok_or(Error::<T>::OperatorKeyNotFound)?;
+        ensure!(
+            !Self::is_registered(None, &operator) && !OperatedModule::<T>::contains_key(&operator),
+            Error::<T>::OperatorKeyInUse
+        );
+
+        PendingOperatorKeys::<T>::remove(&module_key, &operator);
+        OperatorKeys::<T>::insert(&module_key, &operator, &permissions);
+        OperatedModule::<T>::insert(&operator, &module_key);
+
+        Self::deposit_event(Event::OperatorKeySet(
+            module_key,
+            operator,
+            permissions.into_iter().collect(),
+        ));
+        Ok(())
+    }
+
+    /// Revokes every permission of the operator key over the calling module key, or withdraws
+    /// the offer if it wasn't accepted yet.
+    pub fn do_remove_operator_key(
+        origin: T::RuntimeOrigin,
+        operator: T::AccountId,
+    ) -> DispatchResult {
+        let key = ensure_signed(origin)?;
+
+        let offered = PendingOperatorKeys::<T>::take(&key, &operator).is_some();
+        let granted = OperatorKeys::<T>::contains_key(&key, &operator);
+        ensure!(offered || granted, Error::<T>::OperatorKeyNotFound);
+
+        if granted {
+            OperatorKeys::<T>::remove(&key, &operator);
+            OperatedModule::<T>::remove(&operator);
+        }
+
+        Self::deposit_event(Event::OperatorKeyRemoved(key, operator));
+        Ok(())
+    }
+
+    /// Operator keys of the module key, accepted or not.
+    fn operator_key_count(module_key: &T::AccountId) -> usize {
+        OperatorKeys::<T>::iter_key_prefix(module_key)
+            .count()
+            .saturating_add(PendingOperatorKeys::<T>::iter_key_prefix(module_key).count())
+    }
+
+    /// Drops every operator key of a module key that is no longer registered anywhere.
+    pub fn remove_operator_keys(module_key: &T::AccountId) {
+        for operator in OperatorKeys::<T>::iter_key_prefix(module_key).collect::<Vec<_>>() {
+            OperatedModule::<T>::remove(&operator);
+        }
+        let _ = OperatorKeys::<T>::clear_prefix(module_key, u32::MAX, None);
+        let _ = PendingOperatorKeys::<T>::clear_prefix(module_key, u32::MAX, None);
+    }
+}
@@ -0,0 +1,282 @@
+use crate::*;
+use frame_support::pallet_prelude::DispatchResult;
+use sp_runtime::{traits::TrailingZeroInput, ArithmeticError, DispatchError};
+use sp_std::collections::btree_set::BTreeSet;
+
+impl<T: Config> Pallet<T> {
+    /// Maximum number of modules a nomination pool can stake to.
+    pub const MAX_POOL_MODULES: usize = 16;
+
+    pub fn do_create_pool(origin: T::RuntimeOrigin, modules: Vec<T::AccountId>) -> DispatchResult {
+        let manager = ensure_signed(origin)?;
+        Self::validate_pool_modules(&modules)?;
+
+        let pool_id = NextPoolId::<T>::get();
+        NextPoolId::<T>::put(pool_id.checked_add(1).ok_or(ArithmeticError::Overflow)?);
+
+        let account = Self::pool_account(pool_id);
+        NominationPools::<T>::insert(
+            pool_id,
+            NominationPool {
+                manager: manager.clone(),
+                account: account.clone(),
+                modules,
+                total_shares: 0,
+                idle: 0,
+            },
+        );
+        PoolAccounts::<T>::insert(account, pool_id);
+
+        Self::deposit_event(Event::PoolCreated(pool_id, manager));
+        Ok(())
+    }
+
+    /// Replaces the modules of the pool, moving all of its stake over to them.
+    pub fn do_set_pool_modules(
+        origin: T::RuntimeOrigin,
+        pool_id: u32,
+        modules: Vec<T::AccountId>,
+    ) -> DispatchResult {
+        let key = ensure_signed(origin)?;
+        let mut pool = NominationPools::<T>::get(pool_id).ok_or(Error::<T>::PoolDoesNotExist)?;
+        ensure!(pool.manager == key, Error::<T>::NotPoolManager);
+        Self::validate_pool_modules(&modules)?;
+
+        let mut amount: u64 = 0;
+        for (module_key, stake) in Self::get_stake_to_vector(&pool.account) {
+            Self::decrease_stake(&pool.account, &module_key, stake);
+            amount = amount.saturating_add(stake);
+        }
+        amount = amount.saturating_add(Self::withdraw_idle_pool_balance(&mut pool, u64::MAX)?);
+
+        pool.modules = modules;
+        Self::stake_across_pool_modules(&pool, amount)?;
+        NominationPools::<T>::insert(pool_id, pool);
+
+        Self::deposit_event(Event::PoolModulesSet(pool_id));
+        Ok(())
+    }
+
+    /// Stakes the amount through the pool, minting shares at the current share value.
+    pub fn do_join_pool(origin: T::RuntimeOrigin, pool_id: u32, amount: u64) -> DispatchResult {
+        let member = ensure_signed(origin)?;
+        let mut pool = NominationPools::<T>::get(pool_id).ok_or(Error::<T>::PoolDoesNotExist)?;
+
+        ensure!(
+            Self::has_enough_balance(&member, amount),
+            Error::<T>::NotEnoughBalanceToStake
+        );
+
+        let value = Self::get_pool_value(&pool);
+        let shares = if pool.total_shares == 0 || value == 0 {
+            amount
+        } else {
+            mul_div(amount, pool.total_shares, value)
+        };
+        ensure!(shares > 0, Error::<T>::StakeTooSmall);
+
+        Self::remove_balance_from_account(&member, amount)?;
+        Self::stake_across_pool_modules(&pool, amount)?;
+
+        pool.total_shares =
+            pool.total_shares.checked_add(shares).ok_or(ArithmeticError::Overflow)?;
+        NominationPools::<T>::insert(pool_id, pool);
+        PoolMembers::<T>::mutate(&member, pool_id, |membership| {
+            let membership = membership.get_or_insert_with(PoolMember::default);
+            membership.shares = membership.shares.saturating_add(shares);
+            membership.deposited = membership.deposited.saturating_add(amount);
+        });
+
+        Self::deposit_event(Event::PoolJoined(pool_id, member, amount));
+        Ok(())
+    }
+
+    /// Burns the member's shares, paying out their part of the pool.
+    pub fn do_leave_pool(origin: T::RuntimeOrigin, pool_id: u32, shares: u64) -> DispatchResult {
+        let member = ensure_signed(origin)?;
+        let mut pool = NominationPools::<T>::get(pool_id).ok_or(Error::<T>::PoolDoesNotExist)?;
+        let mut membership =
+            PoolMembers::<T>::get(&member, pool_id).ok_or(Error::<T>::NotPoolMember)?;
+
+        ensure!(
+            shares > 0 && shares <= membership.shares,
+            Error::<T>::NotEnoughPoolShares
+        );
+
+        let amount = Self::withdraw_from_pool(&mut pool, shares)?;
+        Self::add_balance_to_account(&member, amount);
+
+        let withdrawn_deposit = mul_div(membership.deposited, shares, membership.shares);
+        membership.deposited = membership.deposited.saturating_sub(withdrawn_deposit);
+        membership.shares = membership.shares.saturating_sub(shares);
+
+        if membership.shares == 0 {
+            PoolMembers::<T>::remove(&member, pool_id);
+        } else {
+            PoolMembers::<T>::insert(&member, pool_id, membership);
+        }
+        NominationPools::<T>::insert(pool_id, pool);
+
+        Self::deposit_event(Event::PoolLeft(pool_id, member, amount));
+        Ok(())
+    }
+
+    /// Pays out the part of the member's pool value that exceeds what they joined with.
+    pub fn do_claim_pool_rewards(origin: T::RuntimeOrigin, pool_id: u32) -> DispatchResult {
+        let member = ensure_signed(origin)?;
+        let mut pool = NominationPools::<T>::get(pool_id).ok_or(Error::<T>::PoolDoesNotExist)?;
+        let mut membership =
+            PoolMembers::<T>::get(&member, pool_id).ok_or(Error::<T>::NotPoolMember)?;
+
+        let value = Self::get_pool_value(&pool);
+        let member_value = mul_div(membership.shares, value, pool.total_shares);
+        let rewards = member_value.saturating_sub(membership.deposited);
+
+        let shares = mul_div(rewards, pool.total_shares, value).min(membership.shares);
+        ensure!(shares > 0, Error::<T>::NothingToClaim);
+
+        let amount = Self::withdraw_from_pool(&mut pool, shares)?;
+        Self::add_balance_to_account(&member, amount);
+
+        membership.shares = membership.shares.saturating_sub(shares);
+        if membership.shares == 0 {
+            PoolMembers::<T>::remove(&member, pool_id);
+        } else {
+            PoolMembers::<T>::insert(&member, pool_id, membership);
+        }
+        NominationPools::<T>::insert(pool_id, pool);
+
+        Self::deposit_event(Event::PoolRewardsClaimed(pool_id, member, amount));
+        Ok(())
+    }
+
+    /// Keyless account holding the stake of the pool.
+    pub fn pool_account(pool_id: u32) -> T::AccountId {
+        let entropy = (b"subspace/pool", pool_id).using_encoded(sp_core::blake2_256);
+        Decode::decode(&mut TrailingZeroInput::new(entropy.as_ref()))
+            .expect("infinite length input; no invalid inputs for type; qed")
+    }
+
+    /// Returns the pool id owning the account, if it is a pool account.
+    pub fn get_pool_of_account(account: &T::AccountId) -> Option<u32> {
+        PoolAccounts::<T>::get(account)
+    }
+
+    /// Returns the stake the member holds through the pools it joined.
+    pub fn get_pooled_stake(member: &T::AccountId) -> u64 {
+        PoolMembers::<T>::iter_prefix(member)
+            .filter_map(|(pool_id, membership)| {
+                let pool = NominationPools::<T>::get(pool_id)?;
+                let staked = Self::get_owned_stake(&pool.account);
+                Some(mul_div(membership.shares, staked, pool.total_shares))
+            })
+            .sum()
+    }
+
+    /// Stake and idle stake held by the pool. Balance sent to the pool account is not counted.
+    pub fn get_pool_value(pool: &NominationPool<T>) -> u64 {
+        Self::get_owned_stake(&pool.account).saturating_add(pool.idle)
+    }
+
+    /// Records stake returned to a pool account by a deregistered module as idle pool stake.
+    pub(crate) fn return_stake_to_pool(account: &T::AccountId, amount: u64) {
+        let Some(pool_id) = Self::get_pool_of_account(account) else {
+            return;
+        };
+
+        NominationPools::<T>::mutate(pool_id, |pool| {
+            if let Some(pool) = pool {
+                pool.idle = pool.idle.saturating_add(amount);
+            }
+        });
+    }
+
+    fn validate_pool_modules(modules: &[T::AccountId]) -> DispatchResult {
+        let unique: BTreeSet<_> = modules.iter().collect();
+        ensure!(
+            !modules.is_empty()
+                && modules.len() <= Self::MAX_POOL_MODULES
+                && unique.len() == modules.len(),
+            Error::<T>::InvalidPoolModules
+        );
+        ensure!(
+            modules.iter().all(|module_key| Self::is_registered(None, module_key)),
+            Error::<T>::ModuleDoesNotExist
+        );
+
+        Ok(())
+    }
+
+    /// Splits the amount evenly over the pool modules that are still registered.
+    fn stake_across_pool_modules(pool: &NominationPool<T>, amount: u64) -> DispatchResult {
+        let modules: Vec<_> = pool
+            .modules
+            .iter()
+            .filter(|module_key| Self::is_registered(None, module_key))
+            .collect();
+        let count = u64::try_from(modules.len()).unwrap_or(u64::MAX);
+        ensure!(count > 0, Error::<T>::InvalidPoolModules);
+
+        let part = amount.checked_div(count).unwrap_or_default();
+        let remainder = amount.checked_rem(count).unwrap_or_default();
+        for (index, module_key) in modules.into_iter().enumerate() {
+            let stake = if index == 0 {
+                part.saturating_add(remainder)
+            } else {
+                part
+            };
+            if stake > 0 {
+                Self::increase_stake(&pool.account, module_key, stake);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Unstakes the value of the shares from every module of the pool, along with their part
+    /// of the idle balance left by deregistered modules, and burns them.
+    fn withdraw_from_pool(pool: &mut NominationPool<T>, shares: u64) -> Result<u64, DispatchError> {
+        let mut amount: u64 = 0;
+        for (module_key, stake) in Self::get_stake_to_vector(&pool.account) {
+            let part = mul_div(stake, shares, pool.total_shares);
+            Self::decrease_stake(&pool.account, &module_key, part);
+            amount = amount.saturating_add(part);
+        }
+
+        let idle = mul_div(pool.idle, shares, pool.total_shares);
+        amount = amount.saturating_add(Self::withdraw_idle_pool_balance(pool, idle)?);
+
+        pool.total_shares = pool.total_shares.saturating_sub(shares);
+        Ok(amount)
+    }
+
+    fn withdraw_idle_pool_balance(
+        pool: &mut NominationPool<T>,
+        amount: u64,
+    ) -> Result<u64, DispatchError> {
+        let amount = amount.min(pool.idle).min(Self::get_balance_u64(&pool.account));
+        if amount == 0 {
+            return Ok(0);
+        }
+
+        let _ = T::Currency::withdraw(
+            &pool.account,
+            amount,
+            WithdrawReasons::except(WithdrawReasons::TIP),
+            ExistenceRequirement::AllowDeath,
+        )
+        .map_err(|_| Error::<T>::BalanceCouldNotBeRemoved)?;
+
+        pool.idle = pool.idle.saturating_sub(amount);
+        Ok(amount)
+    }
+}
+
+/// Computes `value * numerator / denominator` without overflowing, zero if the denominator is.
+fn mul_div(value: u64, numerator: u64, denominator: u64) -> u64 {
+    u128::from(value)
+        .checked_mul(u128::from(numerator))
+        .and_then(|product| product.checked_div(u128::from(denominator)))
+        .and_then(|result| u64::try_from(result).ok())
+        .unwrap_or_default()
+}
@@ -1,11 +1,16 @@
 use crate::*;
 // use frame_support::storage::with_storage_layer;
 
-use frame_support::{pallet_prelude::DispatchResult, sp_runtime::DispatchError};
+use frame_support::{
+    pallet_prelude::DispatchResult,
+    sp_runtime::DispatchError,
+    traits::{tokens::BalanceStatus, ReservableCurrency},
+    weights::Weight,
+};
 use frame_system::ensure_signed;
 use pallet_subnet_emission_api::SubnetConsensus;
 use sp_core::Get;
-use sp_runtime::BoundedVec;
+use sp_runtime::{BoundedVec, Percent};
 use substrate_fixed::types::I110F18;
 
 impl<T: Config> Pallet<T> {
@@ -36,6 +41,8 @@ impl<T: Config> Pallet<T> {
     /// * The module key is already registered.
     /// * The maximum number of modules per network has been reached.
     /// * The root network registration requirements are not met.
+    /// * The subnet only accepts allowlisted module keys and this one is not.
+    /// * The module key already has a registration request waiting for approval.
     ///
     /// # Effects
     ///
@@ -46,6 +53,10 @@ impl<T: Config> Pallet<T> {
     /// 3. Add the stake to the module.
     /// 4. Update registration counters.
     /// 5. Emit a `ModuleRegistered` event.
+    ///
+    /// On subnets in `FounderApproval` mode, modules missing from the allowlist are not
+    /// registered. The burn is paid and the request is queued until the founder approves or
+    /// rejects it, emitting a `RegistrationRequested` event.
     pub fn do_register(
         origin: T::RuntimeOrigin,
         network_name: Vec<u8>,
@@ -64,12 +75,24 @@ impl<T: Config> Pallet<T> {
         let netuid =
             Self::get_netuid_for_name(&network_name).ok_or(Error::<T>::NetworkDoesNotExist)?;
 
-        Self::validate_registration_request(netuid, &key, &module_key)?;
+        let allowlisted = RegistrationAllowlist::<T>::contains_key(netuid, &module_key);
+        match SubnetRegistrationMode::<T>::get(netuid) {
+            RegistrationMode::Allowlist if !allowlisted => {
+                return Err(Error::<T>::NotInRegistrationAllowlist.into());
+            }
+            RegistrationMode::FounderApproval if !allowlisted => {
+                return Self::queue_registration(netuid, key, module_key, name, address, metadata);
+            }
+            _ => {}
+        }
+
+        let charge = Self::validate_registration_request(netuid, &key, &module_key)?;
 
         Self::reserve_module_slot(netuid, &module_key)?;
 
         let uid = Self::register_module(netuid, &module_key, name, address, metadata)?;
         Self::finalize_registration(netuid, uid, &module_key)?;
+        Self::hold_registration_deposit(netuid, &module_key, charge);
 
         Ok(())
     }
@@ -135,6 +158,11 @@ impl<T: Config> Pallet<T> {
     /// # Events
     ///
     /// Emits a `ModuleDeregistered` event when successful.
+    ///
+    /// # Deposits
+    ///
+    /// If the module registered with a deposit, the refundable part is given back once the
+    /// module held its slot for the minimum tenure. Everything else goes to the treasury.
     pub fn do_deregister(origin: T::RuntimeOrigin, netuid: u16) -> DispatchResult {
         // --- 1. Check that the caller has signed the transaction.
         let key = ensure_signed(origin)?;
@@ -142,7 +170,8 @@ impl<T: Config> Pallet<T> {
         let Some(uid) = Self::get_uid_for_key(netuid, &key) else {
             return Err(Error::<T>::ModuleDoesNotExist.into());
         };
-        // --- 3. Remove the module from the subnet.
+        // --- 3. Settle the registration deposit and remove the module from the subnet.
+        Self::release_registration_deposit(netuid, uid, &key)?;
         Self::remove_module(netuid, uid, true)?;
         ensure!(
             !Self::key_registered(netuid, &key),
@@ -155,15 +184,219 @@ impl<T: Config> Pallet<T> {
         Ok(())
     }
 
+    /// Allows the module key to register on the subnet, skipping the approval queue on
+    /// `FounderApproval` subnets. Only the subnet founder can manage the allowlist.
+    pub fn do_add_to_registration_allowlist(
+        origin: T::RuntimeOrigin,
+        netuid: u16,
+        module_key: T::AccountId,
+    ) -> DispatchResult {
+        Self::ensure_subnet_founder(origin, netuid)?;
+
+        RegistrationAllowlist::<T>::insert(netuid, &module_key, ());
+        Self::deposit_event(Event::AddedToRegistrationAllowlist(netuid, module_key));
+
+        Ok(())
+    }
+
+    /// Removes the module key from the subnet allowlist. Modules that are already registered
+    /// are kept.
+    pub fn do_remove_from_registration_allowlist(
+        origin: T::RuntimeOrigin,
+        netuid: u16,
+        module_key: T::AccountId,
+    ) -> DispatchResult {
+        Self::ensure_subnet_founder(origin, netuid)?;
+
+        ensure!(
+            RegistrationAllowlist::<T>::contains_key(netuid, &module_key),
+            Error::<T>::NotInRegistrationAllowlist
+        );
+
+        RegistrationAllowlist::<T>::remove(netuid, &module_key);
+        Self::deposit_event(Event::RemovedFromRegistrationAllowlist(netuid, module_key));
+
+        Ok(())
+    }
+
+    /// Registers a module queued by a `FounderApproval` subnet. The burn was already paid when
+    /// the request was made.
+    pub fn do_approve_registration(
+        origin: T::RuntimeOrigin,
+        netuid: u16,
+        module_key: T::AccountId,
+    ) -> DispatchResult {
+        Self::ensure_subnet_founder(origin, netuid)?;
+
+        let request = Self::take_pending_registration(netuid, &module_key)
+            .ok_or(Error::<T>::RegistrationNotPending)?;
+
+        ensure!(
+            !Self::key_registered(netuid, &module_key),
+            Error::<T>::KeyAlreadyRegistered
+        );
+
+        Self::reserve_module_slot(netuid, &module_key)?;
+
+        let uid = Self::register_module(
+            netuid,
+            &module_key,
+            request.name,
+            request.address,
+            request.metadata,
+        )?;
+        Self::finalize_registration(netuid, uid, &module_key)?;
+        Self::hold_registration_deposit(netuid, &module_key, request.charge);
+
+        Ok(())
+    }
+
+    /// Drops a module queued by a `FounderApproval` subnet and refunds the registration cost to
+    /// whoever paid for the request.
+    pub fn do_reject_registration(
+        origin: T::RuntimeOrigin,
+        netuid: u16,
+        module_key: T::AccountId,
+    ) -> DispatchResult {
+        Self::ensure_subnet_founder(origin, netuid)?;
+
+        let request = Self::take_pending_registration(netuid, &module_key)
+            .ok_or(Error::<T>::RegistrationNotPending)?;
+        Self::refund_registration_request(&request)?;
+
+        Self::deposit_event(Event::RegistrationRejected(netuid, module_key));
+
+        Ok(())
+    }
+
+    /// Refunds every queued registration of the subnet. Called before the subnet is removed.
+    pub(crate) fn refund_pending_registrations(netuid: u16) {
+        for (module_key, request) in PendingRegistrations::<T>::drain_prefix(netuid) {
+            PendingRegistrationExpiries::<T>::remove(request.expires_at, (netuid, &module_key));
+            if Self::refund_registration_request(&request).is_err() {
+                log::error!(
+                    "could not refund the registration request of {module_key:?} on subnet {netuid}"
+                );
+            }
+        }
+    }
+
+    // This code is running under the `on_initialize` hook, returns the consumed weight
+    pub fn prune_expired_registrations(block_number: u64) -> Weight {
+        let db_weight = T::DbWeight::get();
+        let mut weight = db_weight.reads(1);
+
+        for ((netuid, module_key), ()) in
+            PendingRegistrationExpiries::<T>::drain_prefix(block_number)
+        {
+            weight = weight.saturating_add(db_weight.reads_writes(3, 3));
+
+            let Some(request) = PendingRegistrations::<T>::take(netuid, &module_key) else {
+                continue;
+            };
+            if Self::refund_registration_request(&request).is_err() {
+                log::error!(
+                    "could not refund the expired registration request of {module_key:?} on subnet {netuid}"
+                );
+            }
+
+            Self::deposit_event(Event::RegistrationExpired(netuid, module_key));
+        }
+
+        weight
+    }
+
     // --------------------------
     // Registration Utils
     // --------------------------
 
+    fn take_pending_registration(
+        netuid: u16,
+        module_key: &T::AccountId,
+    ) -> Option<PendingRegistration<T>> {
+        let request = PendingRegistrations::<T>::take(netuid, module_key)?;
+        PendingRegistrationExpiries::<T>::remove(request.expires_at, (netuid, module_key));
+        Some(request)
+    }
+
+    fn ensure_subnet_founder(origin: T::RuntimeOrigin, netuid: u16) -> DispatchResult {
+        let key = ensure_signed(origin)?;
+
+        ensure!(
+            SubnetNames::<T>::contains_key(netuid),
+            Error::<T>::NetuidDoesNotExist
+        );
+        ensure!(Founder::<T>::get(netuid) == key, Error::<T>::NotFounder);
+
+        Ok(())
+    }
+
+    fn queue_registration(
+        netuid: u16,
+        payer: T::AccountId,
+        module_key: T::AccountId,
+        name: Vec<u8>,
+        address: Vec<u8>,
+        metadata: Option<Vec<u8>>,
+    ) -> DispatchResult {
+        ensure!(
+            !PendingRegistrations::<T>::contains_key(netuid, &module_key),
+            Error::<T>::RegistrationAlreadyPending
+        );
+
+        // Rejects invalid names and addresses before the founder has to look at them.
+        ModuleChangeset::<T>::new(
+            name.clone(),
+            address.clone(),
+            DefaultValidatorFees::<T>::get(),
+            metadata.clone(),
+        )
+        .validate(netuid)?;
+
+        let charge = Self::validate_registration_request(netuid, &payer, &module_key)?;
+
+        let requested_at = Self::get_current_block_number();
+        let expires_at = requested_at.saturating_add(PendingRegistrationTimeout::<T>::get());
+        PendingRegistrationExpiries::<T>::insert(expires_at, (netuid, &module_key), ());
+        PendingRegistrations::<T>::insert(
+            netuid,
+            &module_key,
+            PendingRegistration {
+                payer,
+                name,
+                address,
+                metadata,
+                charge,
+                requested_at,
+                expires_at,
+            },
+        );
+
+        Self::deposit_event(Event::RegistrationRequested(netuid, module_key));
+
+        Ok(())
+    }
+
+    fn refund_registration_request(request: &PendingRegistration<T>) -> DispatchResult {
+        match &request.charge {
+            RegistrationCharge::Burned(burn) => {
+                let burn = Self::u64_to_balance(*burn).ok_or(Error::<T>::ArithmeticError)?;
+                Self::add_balance_to_account(&request.payer, burn);
+            }
+            RegistrationCharge::Reserved(deposit) => {
+                T::Currency::unreserve(&deposit.payer, deposit.amount);
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the registration limits and charges the subnet burn, either burning it or reserving
+    /// it as a deposit depending on the subnet's [`RegistrationCost`].
     fn validate_registration_request(
         netuid: u16,
         key: &T::AccountId,
         module_key: &T::AccountId,
-    ) -> DispatchResult {
+    ) -> Result<RegistrationCharge<T>, DispatchError> {
         let burn_config = ModuleBurnConfig::<T>::get(netuid);
         ensure!(
             RegistrationsThisInterval::<T>::get(netuid)
@@ -179,15 +412,144 @@ impl<T: Config> Pallet<T> {
             !Self::key_registered(netuid, module_key),
             Error::<T>::KeyAlreadyRegistered
         );
+        ensure!(
+            !OperatedModule::<T>::contains_key(module_key),
+            Error::<T>::OperatorKeyInUse
+        );
 
         let rootnet_id = T::get_consensus_netuid(SubnetConsensus::Root).unwrap_or(Self::ROOTNET_ID);
-        if netuid != rootnet_id {
-            let burn =
-                Self::u64_to_balance(Burn::<T>::get(netuid)).ok_or(Error::<T>::ArithmeticError)?;
-            Self::remove_balance_from_account(key, burn)
+        if netuid == rootnet_id {
+            return Ok(RegistrationCharge::Burned(0));
+        }
+
+        let burn = Burn::<T>::get(netuid);
+        match burn_config.registration_cost {
+            RegistrationCost::Burn => {
+                Self::remove_balance_from_account(
+                    key,
+                    Self::u64_to_balance(burn).ok_or(Error::<T>::ArithmeticError)?,
+                )
                 .map_err(|_| Error::<T>::NotEnoughBalanceToRegister)?;
+
+                Ok(RegistrationCharge::Burned(burn))
+            }
+            RegistrationCost::Deposit { min_tenure, refund } => {
+                T::Currency::reserve(key, burn)
+                    .map_err(|_| Error::<T>::NotEnoughBalanceToRegister)?;
+
+                Ok(RegistrationCharge::Reserved(RegistrationDeposit {
+                    payer: key.clone(),
+                    amount: burn,
+                    min_tenure,
+                    refund,
+                }))
+            }
+        }
+    }
+
+    // --------------------------
+    // Registration Deposits
+    // --------------------------
+
+    fn hold_registration_deposit(
+        netuid: u16,
+        module_key: &T::AccountId,
+        charge: RegistrationCharge<T>,
+    ) {
+        if let RegistrationCharge::Reserved(deposit) = charge {
+            RegistrationDeposits::<T>::insert(netuid, module_key, deposit);
+        }
+    }
+
+    /// Settles the deposit of a module deregistering itself. The refundable part goes back to
+    /// the payer if the module stayed for the minimum tenure, the rest is forfeited.
+    fn release_registration_deposit(
+        netuid: u16,
+        uid: u16,
+        module_key: &T::AccountId,
+    ) -> DispatchResult {
+        let Some(deposit) = RegistrationDeposits::<T>::take(netuid, module_key) else {
+            return Ok(());
+        };
+
+        let tenure = Self::get_current_block_number()
+            .saturating_sub(RegistrationBlock::<T>::get(netuid, uid));
+        let refund = if tenure >= deposit.min_tenure {
+            deposit.refund.mul_floor(deposit.amount)
+        } else {
+            0
+        };
+
+        if refund > 0 {
+            T::Currency::unreserve(&deposit.payer, refund);
+            Self::deposit_event(Event::RegistrationDepositRefunded(
+                netuid,
+                module_key.clone(),
+                refund,
+            ));
         }
 
+        Self::forfeit_deposit(
+            netuid,
+            module_key,
+            &deposit.payer,
+            deposit.amount.saturating_sub(refund),
+        )
+    }
+
+    /// Sends the whole deposit of a module to the treasury. Called when the module is pruned for
+    /// its low score, or deregistered by its subnet for misbehaving.
+    pub fn forfeit_registration_deposit(netuid: u16, module_key: &T::AccountId) -> DispatchResult {
+        let Some(deposit) = RegistrationDeposits::<T>::take(netuid, module_key) else {
+            return Ok(());
+        };
+
+        Self::forfeit_deposit(netuid, module_key, &deposit.payer, deposit.amount)
+    }
+
+    /// Gives back the whole deposit of a module removed by something else than itself, pruning
+    /// or its subnet, e.g. a rootnet validator replaced by a higher stake.
+    pub(crate) fn refund_registration_deposit(netuid: u16, module_key: &T::AccountId) {
+        if let Some(deposit) = RegistrationDeposits::<T>::take(netuid, module_key) {
+            Self::refund_deposit(netuid, module_key.clone(), deposit);
+        }
+    }
+
+    /// Gives back every deposit of the subnet. Called before the subnet is removed, as its
+    /// modules didn't leave on their own.
+    pub(crate) fn refund_subnet_deposits(netuid: u16) {
+        for (module_key, deposit) in RegistrationDeposits::<T>::drain_prefix(netuid) {
+            Self::refund_deposit(netuid, module_key, deposit);
+        }
+    }
+
+    fn refund_deposit(netuid: u16, module_key: T::AccountId, deposit: RegistrationDeposit<T>) {
+        T::Currency::unreserve(&deposit.payer, deposit.amount);
+        Self::deposit_event(Event::RegistrationDepositRefunded(
+            netuid,
+            module_key,
+            deposit.amount,
+        ));
+    }
+
+    fn forfeit_deposit(
+        netuid: u16,
+        module_key: &T::AccountId,
+        payer: &T::AccountId,
+        amount: u64,
+    ) -> DispatchResult {
+        if amount == 0 {
+            return Ok(());
+        }
+
+        let treasury = T::get_dao_treasury_address();
+        T::Currency::repatriate_reserved(payer, &treasury, amount, BalanceStatus::Free)?;
+        Self::deposit_event(Event::RegistrationDepositForfeited(
+            netuid,
+            module_key.clone(),
+            amount,
+        ));
+
         Ok(())
     }
 
@@ -219,7 +581,7 @@ impl<T: Config> Pallet<T> {
         Ok(())
     }
 
-    /// Determines which peer to prune from the network based on the lowest pruning score.
+    /// Determines which peer to prune from the network based on the subnet's [`PruningPolicy`].
     ///
     /// # Arguments
     ///
@@ -232,11 +594,9 @@ impl<T: Config> Pallet<T> {
     ///
     /// # Behavior
     ///
-    /// 1. Filters peers based on immunity period and minimum stake requirements.
-    /// 2. Calculates pruning scores for eligible peers.
-    /// 3. Selects the peer with the lowest pruning score, prioritizing: a. Peers with zero
-    ///    emission, choosing the oldest. b. If no zero emission peers, selects based on lowest
-    ///    score and oldest registration.
+    /// 1. Filters out peers in immunity period and validators holding a permit.
+    /// 2. Calculates pruning scores for eligible peers according to the pruning policy.
+    /// 3. Selects the peer with the lowest pruning score, the oldest one on ties.
     ///
     /// # Note
     ///
@@ -245,59 +605,96 @@ impl<T: Config> Pallet<T> {
     pub fn get_lowest_uid(netuid: u16, ignore_immunity: bool) -> Option<u16> {
         let current_block = Self::get_current_block_number();
         let immunity_period = ImmunityPeriod::<T>::get(netuid) as u64;
-        let emission_vec = Emission::<T>::get(netuid);
-        let dividend_vec = Dividends::<T>::get(netuid);
-        let incentive_vec = Incentive::<T>::get(netuid);
+        let validator_permits = ValidatorPermits::<T>::get(netuid);
 
-        let uids: Vec<_> = RegistrationBlock::<T>::iter_prefix(netuid)
-            .filter(move |&(uid, block_at_registration)| {
+        let candidates =
+            RegistrationBlock::<T>::iter_prefix(netuid).filter(|&(uid, block_at_registration)| {
                 if ignore_immunity
                     || current_block.saturating_sub(block_at_registration) >= immunity_period
                 {
-                    !*ValidatorPermits::<T>::get(netuid).get(uid as usize).unwrap_or(&false)
+                    !*validator_permits.get(uid as usize).unwrap_or(&false)
                 } else {
                     false
                 }
-            })
-            .map(|(uid, block_at_registration)| {
-                let emission =
-                    I110F18::from_num(emission_vec.get(uid as usize).copied().unwrap_or_default());
-
-                let dividend_perc =
-                    I110F18::from_num(dividend_vec.get(uid as usize).copied().unwrap_or_default());
-                let incentive_perc =
-                    I110F18::from_num(incentive_vec.get(uid as usize).copied().unwrap_or_default());
-
-                if dividend_perc == 0 && incentive_perc == 0 {
-                    return (uid, I110F18::from_num(0), block_at_registration);
-                }
-
-                let dividend = dividend_perc
-                    .saturating_div(dividend_perc.saturating_add(incentive_perc))
-                    .saturating_mul(emission);
-                let incentive = incentive_perc
-                    .saturating_div(dividend_perc.saturating_add(incentive_perc))
-                    .saturating_mul(emission);
-
-                let pruning_score =
-                    (I110F18::from_num(0.3).saturating_mul(dividend)).saturating_add(incentive);
-                (uid, pruning_score, block_at_registration)
-            })
-            .collect();
+            });
+
+        let uids: Vec<_> = match SubnetPruningPolicy::<T>::get(netuid) {
+            PruningPolicy::EmissionScore {
+                dividend_weight,
+                incentive_weight,
+            } => {
+                let emission_vec = Emission::<T>::get(netuid);
+                let dividend_vec = Dividends::<T>::get(netuid);
+                let incentive_vec = Incentive::<T>::get(netuid);
+                let dividend_weight = Self::percent_to_fixed(dividend_weight);
+                let incentive_weight = Self::percent_to_fixed(incentive_weight);
+
+                candidates
+                    .map(|(uid, block_at_registration)| {
+                        let emission = I110F18::from_num(
+                            emission_vec.get(uid as usize).copied().unwrap_or_default(),
+                        );
+
+                        let dividend_perc = I110F18::from_num(
+                            dividend_vec.get(uid as usize).copied().unwrap_or_default(),
+                        );
+                        let incentive_perc = I110F18::from_num(
+                            incentive_vec.get(uid as usize).copied().unwrap_or_default(),
+                        );
+
+                        if dividend_perc == 0 && incentive_perc == 0 {
+                            return (uid, I110F18::from_num(0), block_at_registration);
+                        }
+
+                        let dividend = dividend_perc
+                            .saturating_div(dividend_perc.saturating_add(incentive_perc))
+                            .saturating_mul(emission);
+                        let incentive = incentive_perc
+                            .saturating_div(dividend_perc.saturating_add(incentive_perc))
+                            .saturating_mul(emission);
+
+                        let pruning_score = (dividend_weight.saturating_mul(dividend))
+                            .saturating_add(incentive_weight.saturating_mul(incentive));
+                        (uid, pruning_score, block_at_registration)
+                    })
+                    .collect()
+            }
+            PruningPolicy::LowestStake => candidates
+                .map(|(uid, block_at_registration)| {
+                    let stake = Keys::<T>::get(netuid, uid)
+                        .map(|key| Self::get_delegated_stake(&key))
+                        .unwrap_or_default();
+                    (uid, I110F18::from_num(stake), block_at_registration)
+                })
+                .collect(),
+            PruningPolicy::LowestTrust => {
+                let trust_vec = Trust::<T>::get(netuid);
+                candidates
+                    .map(|(uid, block_at_registration)| {
+                        let trust = trust_vec.get(uid as usize).copied().unwrap_or_default();
+                        (uid, I110F18::from_num(trust), block_at_registration)
+                    })
+                    .collect()
+            }
+            PruningPolicy::OldestFirst => candidates
+                .map(|(uid, block_at_registration)| {
+                    (uid, I110F18::from_num(0), block_at_registration)
+                })
+                .collect(),
+        };
 
-        // Age is secondary to the emission.
+        // Age is secondary to the pruning score.
         uids.iter()
-            // This is usual scenario, that is why we check for oldest 0 emission to return early
-            .filter(|&(_, pruning_score, _)| *pruning_score == 0)
-            .min_by_key(|&(_, _, block_at_registration)| block_at_registration)
-            .or_else(|| {
-                uids.iter().min_by(|&(_, score_a, block_a), &(_, score_b, block_b)| {
-                    score_a.cmp(score_b).then_with(|| block_a.cmp(block_b))
-                })
+            .min_by(|&(_, score_a, block_a), &(_, score_b, block_b)| {
+                score_a.cmp(score_b).then_with(|| block_a.cmp(block_b))
             })
             .map(|(uid, _, _)| *uid)
     }
 
+    fn percent_to_fixed(percent: Percent) -> I110F18 {
+        I110F18::from_num(percent.deconstruct()).saturating_div(I110F18::from_num(100))
+    }
+
     pub fn add_subnet_from_registration(changeset: SubnetChangeset<T>) -> DispatchResult {
         let num_subnets: u16 = Self::get_total_subnets();
         let max_subnets: u16 = MaxAllowedSubnets::<T>::get();
@@ -363,16 +760,23 @@ impl<T: Config> Pallet<T> {
 
     fn replace_lowest_priority_node(netuid: u16, ignore_immunity: bool) -> DispatchResult {
         if let Some(uid) = Self::get_lowest_uid(netuid, ignore_immunity) {
-            Self::remove_module(netuid, uid, false)
+            Self::prune_module(netuid, uid, false)
         } else {
             Err(Error::<T>::NetworkIsImmuned.into())
         }
     }
 
+    /// Removes a module for its low score, forfeiting its registration deposit.
+    fn prune_module(netuid: u16, uid: u16, deregister_subnet_if_empty: bool) -> DispatchResult {
+        let module_key = Keys::<T>::get(netuid, uid).ok_or(Error::<T>::ModuleDoesNotExist)?;
+        Self::forfeit_registration_deposit(netuid, &module_key)?;
+        Self::remove_module(netuid, uid, deregister_subnet_if_empty)
+    }
+
     fn remove_from_lowest_emission_subnet() -> DispatchResult {
         if let Some(subnet_id) = T::get_lowest_emission_netuid(true) {
             if let Some(module_uid) = Self::get_lowest_uid(subnet_id, true) {
-                Self::remove_module(subnet_id, module_uid, true)
+                Self::prune_module(subnet_id, module_uid, true)
             } else {
                 Err(Error::<T>::NetworkIsImmuned.into())
             }
@@ -408,8 +812,11 @@ impl<T: Config> Pallet<T> {
 
     // --- Registration Burn ---
 
-    // This code is running under the `on_initialize` hook
-    pub fn adjust_registration_parameters(block_number: u64) {
+    // This code is running under the `on_initialize` hook, returns the consumed weight
+    pub fn adjust_registration_parameters(block_number: u64) -> Weight {
+        let db_weight = T::DbWeight::get();
+        let mut weight = db_weight.reads_writes(3, 3);
+
         // For subnet prices
         let subnet_config = SubnetBurnConfig::<T>::get();
         let subnet_burn = SubnetBurn::<T>::get();
@@ -432,6 +839,8 @@ impl<T: Config> Pallet<T> {
         RegistrationsPerBlock::<T>::mutate(|val| *val = 0);
 
         for (netuid, _) in Tempo::<T>::iter() {
+            weight = weight.saturating_add(db_weight.reads_writes(4, 2));
+
             let module_config = ModuleBurnConfig::<T>::get(netuid);
             let module_burn = Burn::<T>::get(netuid);
             Self::adjust_burn_parameters(
@@ -449,6 +858,8 @@ impl<T: Config> Pallet<T> {
                 },
             );
         }
+
+        weight
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -544,10 +955,13 @@ impl<T: Config> Pallet<T> {
         N::<T>::iter().map(|(_, value)| value).sum()
     }
 
-    pub fn clear_rootnet_daily_weight_calls(block: u64) {
+    pub fn clear_rootnet_daily_weight_calls(block: u64) -> Weight {
         // 10_800 == blocks in a day
         if block.checked_rem(10_800).is_some_and(|r| r == 0) {
-            let _ = RootNetWeightCalls::<T>::clear(u32::MAX, None);
+            let removed = RootNetWeightCalls::<T>::clear(u32::MAX, None);
+            return T::DbWeight::get().reads_writes(removed.loops.into(), removed.unique.into());
         }
+
+        Weight::zero()
     }
 }
@@ -395,6 +395,7 @@ impl<T: Config> Pallet<T> {
                 &staker,
                 Self::u64_to_balance(delegate_stake_amount).unwrap(),
             );
+            Self::return_stake_to_pool(&staker, delegate_stake_amount);
         }
     }
 
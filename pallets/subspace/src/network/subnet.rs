@@ -61,6 +61,8 @@ impl<T: Config> Pallet<T> {
                 Self::remove_stake_from_storage(subnet_only_account);
                 // Clear validator fees
                 ValidatorFeeConfig::<T>::remove(subnet_only_account);
+                Self::remove_pending_fees(subnet_only_account);
+                Self::remove_operator_keys(subnet_only_account);
             });
     }
 
@@ -81,6 +83,10 @@ impl<T: Config> Pallet<T> {
         // unregistered with "active" stake storage or "active" delegation fee storage.
         Self::clear_subnet_only_accounts_data(netuid);
 
+        // Queued registrations never got a module, so their burn is given back.
+        Self::refund_pending_registrations(netuid);
+        Self::refund_subnet_deposits(netuid);
+
         // --- Delete Subnet Includes Storage For All Pallets ---
 
         Self::clear_subnet_includes(netuid);
@@ -251,7 +257,8 @@ impl<T: Config> Pallet<T> {
     ///
     /// # Returns
     ///
-    /// The number of blocks until the next epoch
+    /// The number of blocks until the next epoch. The netuid acts as the epoch phase, so subnets
+    /// sharing a tempo run their epochs on different blocks.
     pub fn blocks_until_next_epoch(netuid: u16, block_number: u64) -> u64 {
         let tempo = Tempo::<T>::get(netuid);
 
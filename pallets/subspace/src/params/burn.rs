@@ -2,7 +2,20 @@ use crate::*;
 use frame_support::pallet_prelude::MaxEncodedLen;
 use scale_info::TypeInfo;
 use sp_core::Get;
-use sp_runtime::DispatchError;
+use sp_runtime::{DispatchError, Percent};
+
+/// What happens to the registration cost paid by a module
+#[derive(Clone, Copy, TypeInfo, Decode, Encode, PartialEq, Eq, Debug, Default, MaxEncodedLen)]
+pub enum RegistrationCost {
+    /// The cost is burned
+    #[default]
+    Burn,
+    /// The cost is reserved from the payer as a deposit. Modules deregistering themselves after
+    /// `min_tenure` blocks get `refund` of it back, the rest goes to the treasury. Modules pruned
+    /// for their low score forfeit all of it to the treasury, while modules removed otherwise,
+    /// e.g. by governance, get all of it back.
+    Deposit { min_tenure: u64, refund: Percent },
+}
 
 /// This struct is used for both global (Subnet Burn) and MAP parameters (Module Burn)
 #[derive(
@@ -24,6 +37,9 @@ pub struct GeneralBurnConfiguration<T> {
     pub target_registrations_per_interval: u16,
     /// the maximum number of registrations accepted per interval
     pub max_registrations_per_interval: u16,
+    /// whether the cost is burned or held as a deposit, only used by
+    /// module registrations
+    pub registration_cost: RegistrationCost,
     pub _pd: PhantomData<T>,
 }
 
@@ -47,6 +63,7 @@ impl<T: Config> GeneralBurnConfiguration<T> {
             target_registrations_interval: 5_400,
             target_registrations_per_interval: 1,
             max_registrations_per_interval: T::DefaultMaxSubnetRegistrationsPerInterval::get(),
+            registration_cost: RegistrationCost::Burn,
             _pd: PhantomData,
         }
     }
@@ -59,6 +76,7 @@ impl<T: Config> GeneralBurnConfiguration<T> {
             target_registrations_interval: 142,
             target_registrations_per_interval: 3,
             max_registrations_per_interval: T::DefaultMaxRegistrationsPerInterval::get(),
+            registration_cost: RegistrationCost::Burn,
             _pd: PhantomData,
         }
     }
@@ -79,6 +97,7 @@ impl<T: Config> GeneralBurnConfiguration<T> {
             target_registrations_interval,
             target_registrations_per_interval,
             max_registrations_per_interval,
+            registration_cost: _, // complete freedom
             _pd: _,
         } = self;
 
@@ -16,6 +16,8 @@ pub struct GlobalParams<T: Config> {
     pub max_allowed_modules: u16,         // max number of modules allowed per subnet
     pub max_registrations_per_block: u16, // max number of registrations per block
     pub max_allowed_weights: u16,         // max number of weights per module
+    pub ceiling_stake_delegation_fee: Percent, // max delegation fee
+    pub ceiling_validator_weight_fee: Percent, // max weight-setting delegation fee
 
     // mins
     pub floor_stake_delegation_fee: Percent, // min delegation fee
@@ -29,6 +31,7 @@ pub struct GlobalParams<T: Config> {
 
     // Other
     pub subnet_immunity_period: u64,
+    pub fee_announcement_period: u64, // blocks before a fee increase takes effect
     pub governance_config: GovernanceConfiguration,
 
     pub kappa: u16,
@@ -47,6 +50,9 @@ impl<T: Config> Pallet<T> {
             floor_founder_share: FloorFounderShare::<T>::get(),
             floor_stake_delegation_fee: MinFees::<T>::get().stake_delegation_fee,
             floor_validator_weight_fee: MinFees::<T>::get().validator_weight_fee,
+            ceiling_stake_delegation_fee: MaxFees::<T>::get().stake_delegation_fee,
+            ceiling_validator_weight_fee: MaxFees::<T>::get().validator_weight_fee,
+            fee_announcement_period: FeeAnnouncementPeriod::<T>::get(),
             // registrations
             max_registrations_per_block: MaxRegistrationsPerBlock::<T>::get(),
             // weights
@@ -76,11 +82,14 @@ impl<T: Config> Pallet<T> {
             max_allowed_weights,
             floor_stake_delegation_fee,
             floor_validator_weight_fee,
+            ceiling_stake_delegation_fee,
+            ceiling_validator_weight_fee,
             floor_founder_share,
             min_weight_stake,
-            curator,
+            curator: _,
             general_subnet_application_cost,
             subnet_immunity_period,
+            fee_announcement_period,
             governance_config,
             kappa,
             rho,
@@ -97,6 +106,11 @@ impl<T: Config> Pallet<T> {
             stake_delegation_fee: floor_stake_delegation_fee,
             validator_weight_fee: floor_validator_weight_fee,
         });
+        MaxFees::<T>::put(MaximumFees {
+            stake_delegation_fee: ceiling_stake_delegation_fee,
+            validator_weight_fee: ceiling_validator_weight_fee,
+        });
+        FeeAnnouncementPeriod::<T>::put(fee_announcement_period);
 
         // Registration and weight parameters
         MaxRegistrationsPerBlock::<T>::set(max_registrations_per_block);
@@ -104,7 +118,6 @@ impl<T: Config> Pallet<T> {
         MinWeightStake::<T>::put(min_weight_stake);
 
         // Governance and administrative parameters
-        T::set_curator(&curator);
         FloorFounderShare::<T>::put(floor_founder_share);
         SubnetImmunityPeriod::<T>::put(subnet_immunity_period);
         T::update_global_governance_configuration(governance_config)
@@ -130,11 +143,14 @@ impl<T: Config> Pallet<T> {
             max_allowed_weights,
             floor_stake_delegation_fee,
             floor_validator_weight_fee,
+            ceiling_stake_delegation_fee,
+            ceiling_validator_weight_fee,
             floor_founder_share,
             min_weight_stake: _,
-            curator: _,
+            curator,
             general_subnet_application_cost,
             subnet_immunity_period,
+            fee_announcement_period: _,
             governance_config,
             kappa,
             rho,
@@ -142,6 +158,9 @@ impl<T: Config> Pallet<T> {
 
         let old_params = Self::global_params();
 
+        // The curator council is replaced through its own proposals
+        ensure!(*curator == old_params.curator, Error::<T>::InvalidCurator);
+
         // Name length validations
         ensure!(*max_name_length > 0, Error::<T>::InvalidMaxNameLength);
         ensure!(
@@ -162,6 +181,11 @@ impl<T: Config> Pallet<T> {
             floor_validator_weight_fee >= &old_params.floor_validator_weight_fee,
             Error::<T>::CannotDecreaseFee
         );
+        ensure!(
+            ceiling_stake_delegation_fee >= floor_stake_delegation_fee
+                && ceiling_validator_weight_fee >= floor_validator_weight_fee,
+            Error::<T>::InvalidMaxFees
+        );
         // Subnet and module validations
         ensure!(
             *max_allowed_subnets > 0,
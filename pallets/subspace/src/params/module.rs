@@ -121,7 +121,7 @@ impl<T: Config> ModuleChangeset<T> {
         }
 
         if let Some(new_fees) = fees {
-            ValidatorFeeConfig::<T>::insert(&key, new_fees);
+            Pallet::<T>::update_validator_fees(&key, new_fees);
         }
 
         if let Some(new_metadata) = metadata {
@@ -175,6 +175,13 @@ impl ModuleValidator {
 
     pub fn validate_fees<T: Config>(fees: &ValidatorFees) -> Result<(), sp_runtime::DispatchError> {
         fees.validate::<T>().map_err(|_| Error::<T>::InvalidMinDelegationFee)?;
+
+        let max_fees = MaxFees::<T>::get();
+        ensure!(
+            fees.stake_delegation_fee <= max_fees.stake_delegation_fee
+                && fees.validator_weight_fee <= max_fees.validator_weight_fee,
+            Error::<T>::InvalidMaxDelegationFee
+        );
         Ok(())
     }
 }
@@ -4,12 +4,57 @@ use frame_support::pallet_prelude::DispatchResult;
 use pallet_governance_api::VoteMode;
 use sp_core::Get;
 use sp_runtime::{BoundedVec, DispatchError};
-use sp_std::ops::{Deref, DerefMut};
+use sp_std::{
+    ops::{Deref, DerefMut},
+    vec::Vec,
+};
 
 use frame_support::pallet_prelude::*;
 use pallet_governance_api::GovernanceConfiguration;
+use sp_runtime::Percent;
 use substrate_fixed::types::I64F64;
 
+/// How the module to deregister is picked when a full subnet receives a new registration.
+/// Modules in immunity period and validators with permits are never pruned, ties are broken by
+/// registration age, oldest first.
+#[derive(Decode, Encode, PartialEq, Eq, Clone, Copy, Debug, TypeInfo, MaxEncodedLen)]
+pub enum PruningPolicy {
+    /// Lowest `dividend_weight * dividends + incentive_weight * incentive`, where dividends and
+    /// incentive are the module's emission split by its dividends/incentive ratio.
+    EmissionScore {
+        dividend_weight: Percent,
+        incentive_weight: Percent,
+    },
+    /// Lowest total stake delegated to the module.
+    LowestStake,
+    /// Lowest trust from the last consensus run.
+    LowestTrust,
+    /// Oldest registration.
+    OldestFirst,
+}
+
+impl Default for PruningPolicy {
+    fn default() -> Self {
+        Self::EmissionScore {
+            dividend_weight: Percent::from_percent(30),
+            incentive_weight: Percent::from_percent(100),
+        }
+    }
+}
+
+/// Who may register modules on a subnet.
+#[derive(Decode, Encode, PartialEq, Eq, Clone, Copy, Debug, Default, TypeInfo, MaxEncodedLen)]
+pub enum RegistrationMode {
+    /// Anyone paying the burn can register.
+    #[default]
+    Open,
+    /// Only module keys in the founder's allowlist can register.
+    Allowlist,
+    /// Registrations are queued, with their burn, until the founder approves or rejects them.
+    /// Allowlisted module keys skip the queue.
+    FounderApproval,
+}
+
 #[derive(
     Decode, Encode, PartialEq, Eq, Clone, frame_support::DebugNoBound, TypeInfo, MaxEncodedLen,
 )]
@@ -37,6 +82,10 @@ pub struct SubnetParams<T: Config> {
     pub use_weights_encryption: bool,
     pub copier_margin: I64F64,
     pub max_encryption_period: Option<u64>,
+    // --- Pruning ---
+    pub pruning_policy: PruningPolicy,
+    // --- Registrations ---
+    pub registration_mode: RegistrationMode,
 }
 
 pub struct DefaultSubnetParams<T: Config>(sp_std::marker::PhantomData<((), T)>);
@@ -73,10 +122,38 @@ impl<T: Config> DefaultSubnetParams<T> {
             use_weights_encryption: T::DefaultUseWeightsEncryption::get(),
             copier_margin: CopierMarginDefaultValue::get(),
             max_encryption_period: MaxEncryptionPeriodDefaultValue::get(),
+
+            // --- Pruning ---
+            pruning_policy: PruningPolicy::default(),
+            registration_mode: RegistrationMode::default(),
         }
     }
 }
 
+impl<T: Config> SubnetParams<T> {
+    /// The params of a genesis subnet, with the defaults for everything it leaves unset. The
+    /// result still has to be validated.
+    pub fn from_genesis(
+        subnet: &ConfigSubnet<Vec<u8>, T::AccountId>,
+    ) -> Result<Self, DispatchError> {
+        let def = DefaultSubnetParams::<T>::get();
+
+        Ok(Self {
+            name: subnet.name.clone().try_into().map_err(|_| Error::<T>::SubnetNameTooLong)?,
+            founder: subnet.founder.clone(),
+            tempo: subnet.tempo.unwrap_or(def.tempo),
+            immunity_period: subnet.immunity_period.unwrap_or(def.immunity_period),
+            min_allowed_weights: subnet.min_allowed_weights.unwrap_or(def.min_allowed_weights),
+            max_allowed_weights: subnet.max_allowed_weights.unwrap_or(def.max_allowed_weights),
+            max_allowed_uids: subnet.max_allowed_uids.unwrap_or(def.max_allowed_uids),
+            use_weights_encryption: subnet
+                .use_weights_encryption
+                .unwrap_or(def.use_weights_encryption),
+            ..def
+        })
+    }
+}
+
 /// Wrapper for enhanced type safety.
 /// This exists to make sure we always validate the parameters before using them.
 #[derive(Clone, Debug)]
@@ -105,7 +182,7 @@ impl<T: Config> DerefMut for ValidatedSubnetParams<T> {
     }
 }
 
-const MIN_TEMPO: u16 = 25;
+pub const MIN_TEMPO: u16 = 25;
 const MAX_FOUNDER_SHARE: u16 = 100;
 const MAX_INCENTIVE_RATIO: u16 = 100;
 const MIN_ALLOWED_WEIGHTS: u16 = 1;
@@ -154,6 +231,8 @@ impl<T: Config> ValidatedSubnetParams<T> {
             use_weights_encryption: _, // complete freedom
             copier_margin,
             max_encryption_period,
+            pruning_policy,
+            registration_mode: _, // complete freedom
         } = params;
 
         // Validate min/max weights relationship
@@ -239,6 +318,17 @@ impl<T: Config> ValidatedSubnetParams<T> {
             );
         }
 
+        if let PruningPolicy::EmissionScore {
+            dividend_weight,
+            incentive_weight,
+        } = pruning_policy
+        {
+            ensure!(
+                !dividend_weight.is_zero() || !incentive_weight.is_zero(),
+                Error::<T>::InvalidPruningPolicy
+            );
+        }
+
         if let Some(max_calls) = maximum_set_weight_calls_per_epoch {
             ensure!(
                 *max_calls >= MIN_SET_WEIGHT_CALLS,
@@ -306,6 +396,8 @@ impl<T: Config> SubnetChangeset<T> {
             use_weights_encryption,
             copier_margin,
             max_encryption_period,
+            pruning_policy,
+            registration_mode,
         } = self.params.into_inner();
 
         Pallet::<T>::set_max_allowed_uids(netuid, max_allowed_uids)?;
@@ -336,6 +428,8 @@ impl<T: Config> SubnetChangeset<T> {
         MaxEncryptionPeriod::<T>::insert(netuid, max_encryption_period);
         UseWeightsEncryption::<T>::insert(netuid, use_weights_encryption);
         CopierMargin::<T>::insert(netuid, copier_margin);
+        SubnetPruningPolicy::<T>::insert(netuid, pruning_policy);
+        SubnetRegistrationMode::<T>::insert(netuid, registration_mode);
 
         Pallet::<T>::deposit_event(Event::SubnetParamsUpdated(netuid));
 
@@ -370,6 +464,10 @@ impl<T: Config> Pallet<T> {
             use_weights_encryption: UseWeightsEncryption::<T>::get(netuid),
             copier_margin: CopierMargin::<T>::get(netuid),
             max_encryption_period: MaxEncryptionPeriod::<T>::get(netuid),
+
+            // --- Pruning ---
+            pruning_policy: SubnetPruningPolicy::<T>::get(netuid),
+            registration_mode: SubnetRegistrationMode::<T>::get(netuid),
         }
     }
 }
@@ -17,7 +17,10 @@ pub mod config {
         type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
 
         /// Currency type that will be used to place deposits on modules.
-        type Currency: Currency<Self::AccountId, Balance = u64> + Send + Sync;
+        type Currency: Currency<Self::AccountId, Balance = u64>
+            + ReservableCurrency<Self::AccountId>
+            + Send
+            + Sync;
 
         /// The default number of modules that can be registered per interval.
         type DefaultMaxRegistrationsPerInterval: Get<u16>;
@@ -95,7 +95,7 @@ pub mod dispatches {
             validator_weight_fee: Option<Percent>,
             metadata: Option<Vec<u8>>,
         ) -> DispatchResult {
-            let key = ensure_signed(origin.clone())?;
+            let key = Self::ensure_module_operator(origin, OperatorPermission::UpdateModule)?;
             ensure!(
                 Self::is_registered(Some(netuid), &key),
                 Error::<T>::ModuleDoesNotExist
@@ -118,7 +118,7 @@ pub mod dispatches {
             };
 
             let changeset = ModuleChangeset::update(&params, name, address, fees, metadata);
-            Self::do_update_module(origin, netuid, changeset)
+            Self::do_update_module(key, netuid, changeset)
         }
 
         #[pallet::call_index(10)]
@@ -146,6 +146,8 @@ pub mod dispatches {
             use_weights_encryption: bool,
             copier_margin: I64F64,
             max_encryption_period: Option<u64>,
+            pruning_policy: PruningPolicy,
+            registration_mode: RegistrationMode,
         ) -> DispatchResult {
             let params = SubnetParams {
                 founder,
@@ -171,6 +173,8 @@ pub mod dispatches {
                 use_weights_encryption,
                 copier_margin,
                 max_encryption_period,
+                pruning_policy,
+                registration_mode,
             };
 
             let changeset = SubnetChangeset::update(netuid, params)?;
@@ -186,5 +190,104 @@ pub mod dispatches {
         ) -> DispatchResult {
             Self::do_register_subnet(origin, name, metadata)
         }
+
+        #[pallet::call_index(13)]
+        #[pallet::weight((T::WeightInfo::update_subnet(), DispatchClass::Normal, Pays::No))]
+        pub fn add_to_registration_allowlist(
+            origin: OriginFor<T>,
+            netuid: u16,
+            module_key: T::AccountId,
+        ) -> DispatchResult {
+            Self::do_add_to_registration_allowlist(origin, netuid, module_key)
+        }
+
+        #[pallet::call_index(14)]
+        #[pallet::weight((T::WeightInfo::update_subnet(), DispatchClass::Normal, Pays::No))]
+        pub fn remove_from_registration_allowlist(
+            origin: OriginFor<T>,
+            netuid: u16,
+            module_key: T::AccountId,
+        ) -> DispatchResult {
+            Self::do_remove_from_registration_allowlist(origin, netuid, module_key)
+        }
+
+        #[pallet::call_index(15)]
+        #[pallet::weight((T::WeightInfo::register(), DispatchClass::Normal, Pays::No))]
+        pub fn approve_registration(
+            origin: OriginFor<T>,
+            netuid: u16,
+            module_key: T::AccountId,
+        ) -> DispatchResult {
+            Self::do_approve_registration(origin, netuid, module_key)
+        }
+
+        #[pallet::call_index(16)]
+        #[pallet::weight((T::WeightInfo::update_subnet(), DispatchClass::Normal, Pays::No))]
+        pub fn reject_registration(
+            origin: OriginFor<T>,
+            netuid: u16,
+            module_key: T::AccountId,
+        ) -> DispatchResult {
+            Self::do_reject_registration(origin, netuid, module_key)
+        }
+
+        #[pallet::call_index(17)]
+        #[pallet::weight((T::WeightInfo::update_module(), DispatchClass::Normal, Pays::Yes))]
+        pub fn set_operator_key(
+            origin: OriginFor<T>,
+            operator: T::AccountId,
+            permissions: Vec<OperatorPermission>,
+        ) -> DispatchResult {
+            Self::do_set_operator_key(origin, operator, permissions)
+        }
+
+        #[pallet::call_index(18)]
+        #[pallet::weight((T::WeightInfo::update_module(), DispatchClass::Normal, Pays::No))]
+        pub fn remove_operator_key(origin: OriginFor<T>, operator: T::AccountId) -> DispatchResult {
+            Self::do_remove_operator_key(origin, operator)
+        }
+
+        #[pallet::call_index(19)]
+        #[pallet::weight((T::WeightInfo::add_stake_multiple(), DispatchClass::Normal, Pays::No))]
+        pub fn create_pool(origin: OriginFor<T>, modules: Vec<T::AccountId>) -> DispatchResult {
+            Self::do_create_pool(origin, modules)
+        }
+
+        #[pallet::call_index(20)]
+        #[pallet::weight((T::WeightInfo::add_stake_multiple(), DispatchClass::Normal, Pays::No))]
+        pub fn set_pool_modules(
+            origin: OriginFor<T>,
+            pool_id: u32,
+            modules: Vec<T::AccountId>,
+        ) -> DispatchResult {
+            Self::do_set_pool_modules(origin, pool_id, modules)
+        }
+
+        #[pallet::call_index(21)]
+        #[pallet::weight((T::WeightInfo::add_stake_multiple(), DispatchClass::Normal, Pays::No))]
+        pub fn join_pool(origin: OriginFor<T>, pool_id: u32, amount: u64) -> DispatchResult {
+            Self::do_join_pool(origin, pool_id, amount)
+        }
+
+        #[pallet::call_index(22)]
+        #[pallet::weight((T::WeightInfo::remove_stake_multiple(), DispatchClass::Normal, Pays::No))]
+        pub fn leave_pool(origin: OriginFor<T>, pool_id: u32, shares: u64) -> DispatchResult {
+            Self::do_leave_pool(origin, pool_id, shares)
+        }
+
+        #[pallet::call_index(23)]
+        #[pallet::weight((T::WeightInfo::remove_stake_multiple(), DispatchClass::Normal, Pays::No))]
+        pub fn claim_pool_rewards(origin: OriginFor<T>, pool_id: u32) -> DispatchResult {
+            Self::do_claim_pool_rewards(origin, pool_id)
+        }
+
+        #[pallet::call_index(24)]
+        #[pallet::weight((T::WeightInfo::update_module(), DispatchClass::Normal, Pays::Yes))]
+        pub fn accept_operator_key(
+            origin: OriginFor<T>,
+            module_key: T::AccountId,
+        ) -> DispatchResult {
+            Self::do_accept_operator_key(origin, module_key)
+        }
     }
 }
@@ -105,6 +105,8 @@ pub mod errors {
         InvalidMaxAllowedWeights,
         /// The minimum delegation fee is invalid.
         InvalidMinDelegationFee,
+        /// The delegation fee is above the maximum allowed.
+        InvalidMaxDelegationFee,
         /// The module metadata is invalid.
         InvalidModuleMetadata,
         /// The module metadata is too long.
@@ -209,11 +211,58 @@ pub mod errors {
         /// Cannot decrease fees below their current values.
         /// Fees can only be increased to prevent economic attacks.
         CannotDecreaseFee,
+        /// The maximum fees can't be lower than the minimum fees.
+        InvalidMaxFees,
         /// General error for not having enough balance
         NotEnoughBalance,
         /// Not having enough tokens to bridge back
         NotEnoughBridgedTokens,
         /// User is trying to bridge tokens in closed period
         OutsideValidBlockRange,
+        /// Threshold decryption needs a non-zero threshold no larger than the committee, which
+        /// can't exceed the maximum number of authorities
+        InvalidThresholdDecryptionConfig,
+        /// The account is not part of the subnet's decryption committee
+        NotDecryptionCommitteeMember,
+        /// The decryption share doesn't match the commitment in the encrypted weights
+        InvalidDecryptionShare,
+        /// The emission score pruning policy needs at least one non-zero coefficient
+        InvalidPruningPolicy,
+        /// The subnet only accepts registrations of module keys in its allowlist
+        NotInRegistrationAllowlist,
+        /// The module key already has a registration request waiting for the founder
+        RegistrationAlreadyPending,
+        /// There is no registration request for the module key on this subnet
+        RegistrationNotPending,
+        /// The operator key wasn't granted the permission for this call
+        NotModuleOperator,
+        /// The key is a module key or already operates another module
+        OperatorKeyInUse,
+        /// The module key already has the maximum number of operator keys
+        TooManyOperatorKeys,
+        /// The key isn't an operator key of the module
+        OperatorKeyNotFound,
+        /// Operator keys need at least one permission
+        InvalidOperatorPermissions,
+        /// The nomination pool does not exist
+        PoolDoesNotExist,
+        /// Only the pool manager can change the pool
+        NotPoolManager,
+        /// Pools need between one and `MAX_POOL_MODULES` distinct registered modules
+        InvalidPoolModules,
+        /// The account is not a member of the pool
+        NotPoolMember,
+        /// The member doesn't hold that many shares of the pool
+        NotEnoughPoolShares,
+        /// The member's pool value doesn't exceed what they joined with
+        NothingToClaim,
+        /// Subnets can't move from or to Root consensus, and Linear and Treasury consensus are
+        /// held by a single subnet
+        InvalidConsensusChange,
+        /// Fixed allocations need distinct registered uids whose shares sum to 100%
+        InvalidFixedAllocation,
+        /// The curator can't be changed through the global params, the curator council is
+        /// replaced through curator council proposals
+        InvalidCurator,
     }
 }
@@ -23,10 +23,44 @@ pub mod events {
         ModuleDeregistered(u16, u16, T::AccountId),
         /// Event created when the module's updated information is added to the network
         ModuleUpdated(u16, T::AccountId),
+        /// Event created when a validator announces a fee increase, active from the given block
+        ValidatorFeesAnnounced(T::AccountId, ValidatorFees, u64),
+        /// Event created when a validator's fees change, instantly or after an announcement
+        ValidatorFeesUpdated(T::AccountId, ValidatorFees),
         // Parameter Updates
         /// Event created when global parameters are updated
         GlobalParamsUpdated(GlobalParams<T>),
         /// Event created when subnet parameters are updated
         SubnetParamsUpdated(u16),
+        /// Event created when the founder allows a module key to register on the subnet
+        AddedToRegistrationAllowlist(u16, T::AccountId),
+        /// Event created when the founder removes a module key from the subnet allowlist
+        RemovedFromRegistrationAllowlist(u16, T::AccountId),
+        /// Event created when a registration is queued for the founder's approval
+        RegistrationRequested(u16, T::AccountId),
+        /// Event created when the founder rejects a queued registration, refunding its burn
+        RegistrationRejected(u16, T::AccountId),
+        /// Event created when a queued registration expires unanswered, refunding its burn
+        RegistrationExpired(u16, T::AccountId),
+        /// Event created when part of a registration deposit is given back to its payer
+        RegistrationDepositRefunded(u16, T::AccountId, u64),
+        /// Event created when part of a registration deposit is sent to the treasury
+        RegistrationDepositForfeited(u16, T::AccountId, u64),
+        /// Event created when a module key offers permissions to an operator key
+        OperatorKeyOffered(T::AccountId, T::AccountId, Vec<OperatorPermission>),
+        /// Event created when an operator key gets permissions over a module key
+        OperatorKeySet(T::AccountId, T::AccountId, Vec<OperatorPermission>),
+        /// Event created when a module key revokes an operator key
+        OperatorKeyRemoved(T::AccountId, T::AccountId),
+        /// Event created when a nomination pool is created by its manager
+        PoolCreated(u32, T::AccountId),
+        /// Event created when the manager moves the pool stake to a new set of modules
+        PoolModulesSet(u32),
+        /// Event created when an account stakes the amount through the pool
+        PoolJoined(u32, T::AccountId, u64),
+        /// Event created when a member withdraws the amount from the pool
+        PoolLeft(u32, T::AccountId, u64),
+        /// Event created when a member claims the amount their pool stake earned
+        PoolRewardsClaimed(u32, T::AccountId, u64),
     }
 }
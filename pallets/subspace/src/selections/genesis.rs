@@ -24,25 +24,11 @@ pub mod genesis {
     #[pallet::genesis_build]
     impl<T: Config> BuildGenesisConfig for GenesisConfig<T> {
         fn build(&self) {
-            let def = DefaultSubnetParams::<T>::get();
-
             for (netuid, subnet) in self.subnets.iter().enumerate() {
                 let netuid = netuid as u16;
 
-                let params: SubnetParams<T> = SubnetParams {
-                    name: subnet.name.clone().try_into().expect("subnet name is too long"),
-                    founder: subnet.founder.clone(),
-                    tempo: subnet.tempo.unwrap_or(def.tempo),
-                    immunity_period: subnet.immunity_period.unwrap_or(def.immunity_period),
-                    min_allowed_weights: subnet
-                        .min_allowed_weights
-                        .unwrap_or(def.min_allowed_weights),
-                    max_allowed_weights: subnet
-                        .max_allowed_weights
-                        .unwrap_or(def.max_allowed_weights),
-                    max_allowed_uids: subnet.max_allowed_uids.unwrap_or(def.max_allowed_uids),
-                    ..def.clone()
-                };
+                let params =
+                    SubnetParams::<T>::from_genesis(subnet).expect("subnet name is too long");
 
                 log::info!("registering subnet {netuid} with params: {params:?}");
 
@@ -60,14 +46,19 @@ pub mod genesis {
                         validator_weight_fee: DefaultValidatorFees::<T>::get().validator_weight_fee,
                     };
 
-                    let changeset = ModuleChangeset::new(
-                        module.name.clone(),
-                        module.address.clone(),
-                        fees,
-                        None,
-                    );
+                    // Genesis fees apply right away instead of being announced.
+                    let changeset = ModuleChangeset {
+                        fees: None,
+                        ..ModuleChangeset::new(
+                            module.name.clone(),
+                            module.address.clone(),
+                            fees.clone(),
+                            None,
+                        )
+                    };
                     self::Pallet::<T>::append_module(netuid, &module.key, changeset)
                         .expect("genesis modules are valid");
+                    ValidatorFeeConfig::<T>::insert(&module.key, fees);
                     T::set_weights(netuid, module_uid, module.weights.clone());
 
                     for (staker, stake) in module.stake_from.iter().flatten() {
@@ -9,18 +9,27 @@ pub mod hooks {
                 block_number.try_into().ok().expect("blockchain won't pass 2 ^ 64 blocks");
 
             // Adjust costs to reflect the demand
-            Self::adjust_registration_parameters(block_number);
+            let weight = Self::adjust_registration_parameters(block_number);
 
             // Clears the root net weights daily quota
-            Self::clear_rootnet_daily_weight_calls(block_number);
+            let weight =
+                weight.saturating_add(Self::clear_rootnet_daily_weight_calls(block_number));
 
-            // TODO: fix later
-            Weight::default()
+            // Refunds the queued registrations the founders left unanswered
+            let weight = weight.saturating_add(Self::prune_expired_registrations(block_number));
+
+            // Activates the validator fee increases that were announced long enough ago
+            weight.saturating_add(Self::apply_pending_fee_changes(block_number))
         }
 
         fn on_idle(_n: BlockNumberFor<T>, _remaining: Weight) -> Weight {
             log::info!("running on_idle");
             Weight::zero()
         }
+
+        #[cfg(feature = "try-runtime")]
+        fn try_state(_n: BlockNumberFor<T>) -> Result<(), sp_runtime::TryRuntimeError> {
+            Self::do_try_state()
+        }
     }
 }
@@ -0,0 +1,231 @@
+use crate::*;
+use frame_support::ensure;
+use sp_runtime::TryRuntimeError;
+use sp_std::collections::btree_map::BTreeMap;
+
+impl<T: Config> Pallet<T> {
+    /// Checks the storage invariants of the pallet. Ran by `try_state` after every block in
+    /// try-runtime checks and in tests.
+    pub(crate) fn do_try_state() -> Result<(), TryRuntimeError> {
+        Self::check_stake_storage()?;
+        Self::check_module_storage()?;
+        Self::check_registration_storage()?;
+        Self::check_operator_storage()?;
+        Self::check_pool_storage()?;
+
+        Ok(())
+    }
+
+    /// `StakeFrom` and `StakeTo` mirror each other, and `TotalStake` holds their sum.
+    fn check_stake_storage() -> Result<(), TryRuntimeError> {
+        let mut total_stake: u128 = 0;
+        let mut entries: usize = 0;
+
+        for (staked, staker, amount) in StakeFrom::<T>::iter() {
+            ensure!(
+                StakeTo::<T>::get(&staker, &staked) == amount,
+                "StakeTo disagrees with StakeFrom"
+            );
+
+            total_stake = total_stake.saturating_add(u128::from(amount));
+            entries = entries.saturating_add(1);
+        }
+
+        ensure!(
+            StakeTo::<T>::iter_keys().count() == entries,
+            "StakeTo has entries missing from StakeFrom"
+        );
+
+        if total_stake != u128::from(TotalStake::<T>::get()) {
+            log::error!(
+                "TotalStake is {}, but the stakes sum up to {total_stake}",
+                TotalStake::<T>::get()
+            );
+            return Err("TotalStake differs from the sum of stakes".into());
+        }
+
+        Ok(())
+    }
+
+    /// Every subnet holds exactly `N` modules: `Keys` and `Uids` are a bijection over `0..N`,
+    /// the per-uid vectors have `N` entries and no uid keyed storage goes past `N`. Removed
+    /// subnets leave nothing behind.
+    fn check_module_storage() -> Result<(), TryRuntimeError> {
+        let gaps = SubnetGaps::<T>::get();
+
+        for (netuid, n) in N::<T>::iter() {
+            ensure!(!gaps.contains(&netuid), "live subnet listed in SubnetGaps");
+
+            let modules = usize::from(n);
+            ensure!(
+                Keys::<T>::iter_key_prefix(netuid).count() == modules,
+                "N disagrees with the Keys count"
+            );
+            ensure!(
+                Uids::<T>::iter_key_prefix(netuid).count() == modules,
+                "N disagrees with the Uids count"
+            );
+
+            for (uid, key) in Keys::<T>::iter_prefix(netuid) {
+                ensure!(uid < n, "Keys holds a uid past N");
+                ensure!(
+                    Uids::<T>::get(netuid, &key) == Some(uid),
+                    "Uids disagrees with Keys"
+                );
+            }
+
+            for (storage, len) in [
+                ("Active", Active::<T>::decode_len(netuid)),
+                ("Consensus", Consensus::<T>::decode_len(netuid)),
+                ("Emission", Emission::<T>::decode_len(netuid)),
+                ("Incentive", Incentive::<T>::decode_len(netuid)),
+                ("Dividends", Dividends::<T>::decode_len(netuid)),
+                ("LastUpdate", LastUpdate::<T>::decode_len(netuid)),
+                ("Rank", Rank::<T>::decode_len(netuid)),
+                ("Trust", Trust::<T>::decode_len(netuid)),
+                (
+                    "ValidatorPermits",
+                    ValidatorPermits::<T>::decode_len(netuid),
+                ),
+                ("ValidatorTrust", ValidatorTrust::<T>::decode_len(netuid)),
+                ("PruningScores", PruningScores::<T>::decode_len(netuid)),
+            ] {
+                let len = len.unwrap_or_default();
+                if len != modules {
+                    log::error!("{storage} of subnet {netuid} has {len} entries, expected {n}");
+                    return Err("per-uid vector size differs from N".into());
+                }
+            }
+
+            ensure!(
+                Name::<T>::iter_key_prefix(netuid).all(|uid| uid < n),
+                "Name holds a uid past N"
+            );
+            ensure!(
+                Address::<T>::iter_key_prefix(netuid).all(|uid| uid < n),
+                "Address holds a uid past N"
+            );
+            ensure!(
+                RegistrationBlock::<T>::iter_key_prefix(netuid).all(|uid| uid < n),
+                "RegistrationBlock holds a uid past N"
+            );
+            ensure!(
+                Bonds::<T>::iter_key_prefix(netuid).all(|uid| uid < n),
+                "Bonds holds a uid past N"
+            );
+        }
+
+        ensure!(
+            Keys::<T>::iter_keys().all(|(netuid, _)| N::<T>::contains_key(netuid)),
+            "Keys of a removed subnet"
+        );
+        ensure!(
+            Uids::<T>::iter_keys().all(|(netuid, _)| N::<T>::contains_key(netuid)),
+            "Uids of a removed subnet"
+        );
+        ensure!(
+            Bonds::<T>::iter_keys().all(|(netuid, _)| N::<T>::contains_key(netuid)),
+            "Bonds of a removed subnet"
+        );
+        ensure!(
+            PendingValidatorFees::<T>::iter().all(|(key, pending)| {
+                PendingFeeActivations::<T>::contains_key(pending.activation_block, key)
+            }),
+            "PendingValidatorFees without an activation"
+        );
+        ensure!(
+            PendingFeeActivations::<T>::iter_keys().all(|(block, key)| {
+                PendingValidatorFees::<T>::get(key)
+                    .is_some_and(|pending| pending.activation_block == block)
+            }),
+            "PendingFeeActivations disagrees with PendingValidatorFees"
+        );
+
+        Ok(())
+    }
+
+    /// Deposits are only held for registered modules, and registration requests only exist for
+    /// live subnets.
+    fn check_registration_storage() -> Result<(), TryRuntimeError> {
+        ensure!(
+            RegistrationDeposits::<T>::iter_keys()
+                .all(|(netuid, key)| Uids::<T>::contains_key(netuid, key)),
+            "RegistrationDeposits of an unregistered module"
+        );
+        ensure!(
+            PendingRegistrations::<T>::iter_keys().all(|(netuid, _)| N::<T>::contains_key(netuid)),
+            "PendingRegistrations of a removed subnet"
+        );
+        ensure!(
+            PendingRegistrations::<T>::iter().all(|(netuid, key, request)| {
+                PendingRegistrationExpiries::<T>::contains_key(request.expires_at, (netuid, key))
+            }),
+            "PendingRegistrations without an expiry"
+        );
+        ensure!(
+            RegistrationAllowlist::<T>::iter_keys().all(|(netuid, _)| N::<T>::contains_key(netuid)),
+            "RegistrationAllowlist of a removed subnet"
+        );
+
+        Ok(())
+    }
+
+    /// `OperatedModule` mirrors `OperatorKeys`, so each operator acts for a single module, and
+    /// pending offers are for keys that don't operate the module yet.
+    fn check_operator_storage() -> Result<(), TryRuntimeError> {
+        let mut entries: usize = 0;
+
+        for (module_key, operator, permissions) in OperatorKeys::<T>::iter() {
+            ensure!(
+                OperatedModule::<T>::get(&operator) == Some(module_key),
+                "OperatedModule disagrees with OperatorKeys"
+            );
+            ensure!(!permissions.is_empty(), "operator key without permissions");
+
+            entries = entries.saturating_add(1);
+        }
+
+        ensure!(
+            OperatedModule::<T>::iter_keys().count() == entries,
+            "OperatedModule has entries missing from OperatorKeys"
+        );
+        ensure!(
+            PendingOperatorKeys::<T>::iter().all(|(module_key, operator, permissions)| {
+                !permissions.is_empty() && !OperatorKeys::<T>::contains_key(module_key, operator)
+            }),
+            "invalid pending operator key"
+        );
+
+        Ok(())
+    }
+
+    /// Pool shares add up to the members' shares, pool accounts map back to their pool, and the
+    /// idle pool stake is backed by the pool account balance.
+    fn check_pool_storage() -> Result<(), TryRuntimeError> {
+        let mut shares: BTreeMap<u32, u64> = BTreeMap::new();
+        for (_, pool_id, membership) in PoolMembers::<T>::iter() {
+            ensure!(membership.shares > 0, "pool member without shares");
+            let pool_shares = shares.entry(pool_id).or_default();
+            *pool_shares = pool_shares.saturating_add(membership.shares);
+        }
+
+        for (pool_id, pool) in NominationPools::<T>::iter() {
+            ensure!(
+                PoolAccounts::<T>::get(&pool.account) == Some(pool_id),
+                "PoolAccounts disagrees with NominationPools"
+            );
+            ensure!(
+                shares.remove(&pool_id).unwrap_or_default() == pool.total_shares,
+                "pool total shares differ from the members' shares"
+            );
+            ensure!(
+                pool.idle <= Self::get_balance_u64(&pool.account),
+                "pool idle stake exceeds the pool account balance"
+            );
+        }
+
+        ensure!(shares.is_empty(), "PoolMembers of a missing pool");
+
+        Ok(())
+    }
+}
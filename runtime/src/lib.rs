@@ -24,7 +24,7 @@ use pallet_grandpa::{
 };
 
 // Governance pallets
-use pallet_governance::{Curator, GeneralSubnetApplicationCost, LegitWhitelist};
+use pallet_governance::{GeneralSubnetApplicationCost, LegitWhitelist};
 use pallet_governance_api::GovernanceConfiguration;
 
 // EVM pallets
@@ -84,7 +84,7 @@ use sp_version::NativeVersion;
 use sp_version::RuntimeVersion;
 
 // Subspace runtime API
-use subspace_runtime_api::{ModuleInfo, ModuleParams, ModuleStats};
+use subspace_runtime_api::{ModuleInfo, ModuleParams, ModuleStats, TreasuryGrantInfo};
 
 // Frontier EVM imports
 #[cfg(feature = "testnet")]
@@ -190,12 +190,21 @@ pub mod opaque {
 }
 
 #[cfg(feature = "testnet")]
-pub type Migrations = ();
+pub type Migrations = (
+    pallet_subspace::migrations::testnet::MigrateToV35<Runtime>,
+    pallet_governance::migrations::testnet::MigrateToV5<Runtime>,
+    pallet_governance::migrations::v6::MigrateToV6<Runtime>,
+);
 
 #[cfg(not(feature = "testnet"))]
 pub type Migrations = (
     pallet_offworker::migrations::v1::MigrateToV1<Runtime>,
     pallet_subnet_emission::migrations::v2::MigrateToV2<Runtime>, // set lower block emission
+    pallet_subspace::migrations::v16::MigrateToV16<Runtime>,
+    pallet_governance::migrations::v3::MigrateToV3<Runtime>,
+    pallet_governance::migrations::v4::MigrateToV4<Runtime>,
+    pallet_governance::migrations::v5::MigrateToV5<Runtime>,
+    pallet_governance::migrations::v6::MigrateToV6<Runtime>,
 );
 
 #[sp_version::runtime_version]
@@ -501,6 +510,8 @@ impl pallet_subnet_emission::Config for Runtime {
     // Represented in number of blocks, defines how often node sends keep-alive ping
     type PingInterval = ConstU64<50>;
     type EncryptionPeriodBuffer = ConstU64<100>;
+    // Due epochs over this limit are run in the following blocks
+    type MaxEpochsPerBlock = ConstU16<4>;
     type WeightInfo = pallet_subnet_emission::weights::SubstrateWeight<Runtime>;
 }
 
@@ -728,7 +739,7 @@ impl EnsureAddressOrigin<RuntimeOrigin> for EnsureCuratorAddressTruncated {
                     }
 
                     if pallet_governance::RestrictContractDeploy::<Runtime>::get()
-                        && Some(who.clone()) != Some(pallet_governance::Curator::<Runtime>::get())
+                        && !pallet_governance::Pallet::<Runtime>::is_curator(&who)
                     {
                         return Err(RuntimeOrigin::from(RawOrigin::Signed(who)));
                     }
@@ -1069,6 +1080,30 @@ impl_runtime_apis! {
         }
     }
 
+    impl subspace_runtime_api::GovernanceRuntimeApi<Block> for Runtime {
+        fn get_active_treasury_grants() -> Vec<TreasuryGrantInfo> {
+            GovernanceModule::active_treasury_grants()
+                .into_iter()
+                .map(|grant| TreasuryGrantInfo {
+                    next_payout_block: grant.next_payout_block(),
+                    remaining_liability: grant.remaining_liability(),
+                    id: grant.id,
+                    proposal_id: grant.proposal_id,
+                    recipient: grant.recipient,
+                    amount_per_period: grant.amount_per_period,
+                    period_length: grant.period_length,
+                    periods: grant.periods,
+                    paid_periods: grant.paid_periods,
+                    start_block: grant.start_block,
+                })
+                .collect()
+        }
+
+        fn get_treasury_grant_liabilities() -> u64 {
+            GovernanceModule::treasury_grant_liabilities()
+        }
+    }
+
 
     #[cfg(feature = "testnet")]
     impl fp_rpc::EthereumRuntimeRPCApi<Block> for Runtime {
@@ -1392,6 +1427,20 @@ impl pallet_subnet_emission_api::SubnetEmissionApi<<Runtime as frame_system::Con
         pallet_subnet_emission::SubnetConsensusType::<Runtime>::set(netuid, subnet_consensus)
     }
 
+    fn validate_consensus_change(
+        netuid: u16,
+        subnet_consensus: pallet_subnet_emission_api::SubnetConsensus,
+    ) -> DispatchResult {
+        SubnetEmissionModule::validate_consensus_change(netuid, subnet_consensus)
+    }
+
+    fn schedule_consensus_change(
+        netuid: u16,
+        subnet_consensus: pallet_subnet_emission_api::SubnetConsensus,
+    ) -> DispatchResult {
+        SubnetEmissionModule::schedule_consensus_change(netuid, subnet_consensus)
+    }
+
     fn get_weights(netuid: u16, uid: u16) -> Option<Vec<(u16, u16)>> {
         pallet_subnet_emission::Weights::<Runtime>::get(netuid, uid)
     }
@@ -1416,6 +1465,7 @@ impl pallet_subnet_emission_api::SubnetEmissionApi<<Runtime as frame_system::Con
             module_key,
             replace_key,
         )?;
+        SubnetEmissionModule::remove_fixed_allocation(netuid, module_key);
         Ok(())
     }
 
@@ -1483,11 +1533,7 @@ impl pallet_governance_api::GovernanceApi<<Runtime as frame_system::Config>::Acc
     }
 
     fn get_curator() -> AccountId {
-        Curator::<Runtime>::get()
-    }
-
-    fn set_curator(curator: &AccountId) {
-        Curator::<Runtime>::put(curator)
+        GovernanceModule::curator_council_account()
     }
 
     fn set_general_subnet_application_cost(amount: u64) {
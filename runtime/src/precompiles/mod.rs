@@ -12,10 +12,14 @@ use pallet_evm_precompile_simple::{ECRecover, ECRecoverPublicKey, Identity, Ripe
 
 mod balance_transfer;
 mod ed25519;
+mod multi_signature;
+mod sr25519;
 mod staking;
 
 use balance_transfer::{BalanceTransferPrecompile, BALANCE_TRANSFER_INDEX};
 use ed25519::{Ed25519VerifyPrecompile as Ed25519Verify, ED25519_PRECOMPILE_INDEX};
+use multi_signature::{MultiSignatureVerifyPrecompile, MULTI_SIGNATURE_PRECOMPILE_INDEX};
+use sr25519::{Sr25519VerifyPrecompile as Sr25519Verify, SR25519_PRECOMPILE_INDEX};
 use staking::{StakingPrecompile, STAKING_PRECOMPILE_INDEX};
 
 type PrecompileAddress = H160;
@@ -49,7 +53,7 @@ where
         Self(Default::default())
     }
 
-    pub fn used_addresses() -> [PrecompileAddress; 10] {
+    pub fn used_addresses() -> [PrecompileAddress; 12] {
         [
             hash(ECRECOVER_ADDRESS),
             hash(SHA256_ADDRESS),
@@ -61,6 +65,8 @@ where
             hash(ED25519_PRECOMPILE_INDEX),
             hash(BALANCE_TRANSFER_INDEX),
             hash(STAKING_PRECOMPILE_INDEX),
+            hash(SR25519_PRECOMPILE_INDEX),
+            hash(MULTI_SIGNATURE_PRECOMPILE_INDEX),
         ]
     }
 
@@ -79,6 +85,10 @@ where
             a if a == hash(SHA3FIPS256_ADDRESS) => Some(Sha3FIPS256::execute(handle)),
             a if a == hash(ECRECOVER_PUBKEY_ADDRESS) => Some(ECRecoverPublicKey::execute(handle)),
             a if a == hash(ED25519_PRECOMPILE_INDEX) => Some(Ed25519Verify::execute(handle)),
+            a if a == hash(SR25519_PRECOMPILE_INDEX) => Some(Sr25519Verify::execute(handle)),
+            a if a == hash(MULTI_SIGNATURE_PRECOMPILE_INDEX) => {
+                Some(MultiSignatureVerifyPrecompile::execute(handle))
+            }
             // Custom precompiles
             a if a == hash(BALANCE_TRANSFER_INDEX) => {
                 Some(BalanceTransferPrecompile::execute(handle))
@@ -0,0 +1,186 @@
+extern crate alloc;
+
+use alloc::vec::Vec;
+use fp_evm::{ExitError, ExitSucceed, LinearCostPrecompile, PrecompileFailure};
+use parity_scale_codec::DecodeAll;
+use sp_core::U256;
+use sp_runtime::{traits::Verify, AccountId32, MultiSignature};
+
+use crate::precompiles::get_slice;
+
+pub const MULTI_SIGNATURE_PRECOMPILE_INDEX: u64 = 3004;
+
+type PrecompileResult<T> = Result<T, PrecompileFailure>;
+type VerificationResult = Result<bool, &'static str>;
+
+/// Verifies SCALE encoded `MultiSignature`s against the subspace account that signed them, so
+/// contracts don't need to know which scheme a wallet uses.
+pub struct MultiSignatureVerifyPrecompile;
+
+// Priced like ECRecover, the most expensive scheme a `MultiSignature` can hold.
+impl LinearCostPrecompile for MultiSignatureVerifyPrecompile {
+    const BASE: u64 = 3000;
+    const WORD: u64 = 3;
+
+    fn execute(input: &[u8], _: u64) -> PrecompileResult<(ExitSucceed, Vec<u8>)> {
+        // The selector, message, account, signature offset and signature length.
+        let required_len = 132;
+        if input.len() < required_len {
+            return Err(PrecompileFailure::Error {
+                exit_status: ExitError::Other("input must contain at least 132 bytes".into()),
+            });
+        }
+
+        let verification_result =
+            verify_signature(input).map_err(|e| PrecompileFailure::Error {
+                exit_status: ExitError::Other(e.into()),
+            })?;
+
+        let mut result = [0u8; 32];
+        result[31] = u8::from(verification_result);
+
+        Ok((ExitSucceed::Returned, result.to_vec()))
+    }
+}
+
+fn verify_signature(input: &[u8]) -> VerificationResult {
+    let msg = get_slice(input, 4, 36).map_err(|_| "Failed to get message slice")?;
+
+    let account = get_slice(input, 36, 68)
+        .map_err(|_| "Failed to get account slice")
+        .and_then(|account| AccountId32::try_from(account).map_err(|_| "Account recover failed"))?;
+
+    let signature = get_bytes_argument(input, 64)
+        .map_err(|_| "Failed to get signature slice")
+        .and_then(|sig| {
+            MultiSignature::decode_all(&mut &sig[..]).map_err(|_| "Signature recover failed")
+        })?;
+
+    Ok(signature.verify(msg, &account))
+}
+
+/// The ABI encoded `bytes` argument whose offset is stored at `offset_at`, counting from the
+/// start of the arguments.
+fn get_bytes_argument(input: &[u8], offset_at: usize) -> PrecompileResult<&[u8]> {
+    let arguments = get_slice(input, 4, input.len())?;
+
+    let offset = get_word(arguments, offset_at)?;
+    let len = get_word(arguments, offset)?;
+    let start = offset.checked_add(32).ok_or(PrecompileFailure::Error {
+        exit_status: ExitError::InvalidRange,
+    })?;
+    let end = start.checked_add(len).ok_or(PrecompileFailure::Error {
+        exit_status: ExitError::InvalidRange,
+    })?;
+
+    get_slice(arguments, start, end)
+}
+
+/// Reads the 32 bytes word at `at` as an offset or length.
+fn get_word(data: &[u8], at: usize) -> PrecompileResult<usize> {
+    let end = at.checked_add(32).ok_or(PrecompileFailure::Error {
+        exit_status: ExitError::InvalidRange,
+    })?;
+    let word = U256::from_big_endian(get_slice(data, at, end)?);
+
+    usize::try_from(word).map_err(|_| PrecompileFailure::Error {
+        exit_status: ExitError::InvalidRange,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::precompiles::get_method_id;
+    use hex_literal::hex;
+    use parity_scale_codec::Encode;
+    use sp_core::{ecdsa, ed25519, sr25519};
+
+    const MESSAGE: [u8; 32] = [0x42; 32];
+
+    const SR25519_ACCOUNT: [u8; 32] =
+        hex!("7c0f469d3bd340bae718203fa30ca071a5e37c751e891dbded837b213d45d91d");
+    const SR25519_SIGNATURE: [u8; 64] = hex!(
+        "58a57fff92f372c6f08afbe84fdfddb9f86e2574b8d74931dca0f159863ee82c"
+        "662f526de1f1b5bbbb3d485b79251ea8ace4be6998363c196eedcfb475a7318a"
+    );
+
+    const ED25519_ACCOUNT: [u8; 32] =
+        hex!("6e7a1cdd29b0b78fd13af4c5598feff4ef2a97166e3ca6f2e4fbfccd80505bf1");
+    const ED25519_SIGNATURE: [u8; 64] = hex!(
+        "48a8df6327400cff6ff02704a1d8aa971a3807b34e5807e14e4ca398d5d1e7af"
+        "5e258b8f894467bb82b8f461e1f0283906cf73950ba2e77ac5647487de93c10c"
+    );
+
+    /// ECDSA accounts are the blake2 hash of the compressed public key.
+    const ECDSA_ACCOUNT: [u8; 32] =
+        hex!("f579ea6bc1613ae8777439aa108fbc88f4dce9385b21efc7e44898d2d88acea3");
+    const ECDSA_SIGNATURE: [u8; 65] = hex!(
+        "95935548c091fafdf2040715e59377aa95183e7b086abc2c0edae42f6252e1e9"
+        "0e7877a6108711c05a9eb724cbf7c1f1f42e79eff8af3bd9b17a11e7cbcfd72400"
+    );
+
+    fn input(message: &[u8], account: &[u8], signature: &[u8]) -> Vec<u8> {
+        let mut input = get_method_id("verify(bytes32,bytes32,bytes)").to_vec();
+        input.extend_from_slice(message);
+        input.extend_from_slice(account);
+        input.extend_from_slice(&word(96));
+        input.extend_from_slice(&word(signature.len()));
+        input.extend_from_slice(signature);
+        input.resize(input.len().next_multiple_of(32).saturating_add(4), 0);
+        input
+    }
+
+    fn word(value: usize) -> Vec<u8> {
+        [[0u8; 24].as_slice(), &(value as u64).to_be_bytes()].concat()
+    }
+
+    fn verify(input: &[u8]) -> PrecompileResult<bool> {
+        let (_, output) = MultiSignatureVerifyPrecompile::execute(input, 0)?;
+        Ok(output.last() == Some(&1))
+    }
+
+    fn sr25519() -> Vec<u8> {
+        MultiSignature::Sr25519(sr25519::Signature::from_raw(SR25519_SIGNATURE)).encode()
+    }
+
+    #[test]
+    fn accepts_valid_signatures_of_every_scheme() {
+        let ed25519 = MultiSignature::Ed25519(ed25519::Signature::from_raw(ED25519_SIGNATURE));
+        let ecdsa = MultiSignature::Ecdsa(ecdsa::Signature::from_raw(ECDSA_SIGNATURE));
+
+        for (account, signature) in [
+            (SR25519_ACCOUNT, sr25519()),
+            (ED25519_ACCOUNT, ed25519.encode()),
+            (ECDSA_ACCOUNT, ecdsa.encode()),
+        ] {
+            assert_eq!(
+                verify(&input(&MESSAGE, &account, &signature)).ok(),
+                Some(true)
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_signatures_of_other_messages_or_accounts() {
+        assert_eq!(
+            verify(&input(&[0x43; 32], &SR25519_ACCOUNT, &sr25519())).ok(),
+            Some(false)
+        );
+        assert_eq!(
+            verify(&input(&MESSAGE, &ED25519_ACCOUNT, &sr25519())).ok(),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn fails_on_malformed_signatures() {
+        let mut truncated = sr25519();
+        truncated.pop();
+        assert!(verify(&input(&MESSAGE, &SR25519_ACCOUNT, &truncated)).is_err());
+
+        let mut too_short = input(&MESSAGE, &SR25519_ACCOUNT, &sr25519());
+        too_short.truncate(100);
+        assert!(verify(&too_short).is_err());
+    }
+}
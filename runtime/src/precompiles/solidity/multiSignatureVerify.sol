@@ -0,0 +1,20 @@
+// SPDX-License-Identifier: GPL-3.0
+pragma solidity ^0.8.0;
+
+address constant MULTI_SIGNATURE_VERIFY_PRECOMPILE = 0x0000000000000000000000000000000000000bbc;
+
+interface IMultiSignatureVerify {
+    /**
+     * @dev Verifies a SCALE encoded MultiSignature (Ed25519, Sr25519 or Ecdsa) using provided message and account.
+     *
+     * @param message The 32-byte signature payload message.
+     * @param account The 32-byte account id of the signer.
+     * @param signature The SCALE encoded MultiSignature, prefixed by its scheme index.
+     * @return bool Returns true if the signature is valid for the given message and account, false otherwise.
+     */
+    function verify(
+        bytes32 message,
+        bytes32 account,
+        bytes calldata signature
+    ) external pure returns (bool);
+}
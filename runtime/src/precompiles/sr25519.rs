@@ -0,0 +1,110 @@
+extern crate alloc;
+
+use alloc::vec::Vec;
+use fp_evm::{ExitError, ExitSucceed, LinearCostPrecompile, PrecompileFailure};
+use sp_core::sr25519::{Public, Signature};
+use sp_runtime::traits::Verify;
+
+use crate::precompiles::get_slice;
+
+pub const SR25519_PRECOMPILE_INDEX: u64 = 3003;
+
+type PrecompileResult<T> = Result<T, PrecompileFailure>;
+type VerificationResult = Result<bool, &'static str>;
+
+pub struct Sr25519VerifyPrecompile;
+
+// Priced like ECRecover, sr25519 verification costs about as much as secp256k1 recovery.
+impl LinearCostPrecompile for Sr25519VerifyPrecompile {
+    const BASE: u64 = 3000;
+    const WORD: u64 = 0;
+
+    fn execute(input: &[u8], _: u64) -> PrecompileResult<(ExitSucceed, Vec<u8>)> {
+        let required_len = 132;
+        if input.len() < required_len {
+            return Err(PrecompileFailure::Error {
+                exit_status: ExitError::Other("input must contain 132 bytes".into()),
+            });
+        }
+
+        let verification_result =
+            verify_signature(input).map_err(|e| PrecompileFailure::Error {
+                exit_status: ExitError::Other(e.into()),
+            })?;
+
+        let mut result = [0u8; 32];
+        result[31] = u8::from(verification_result);
+
+        Ok((ExitSucceed::Returned, result.to_vec()))
+    }
+}
+
+/// Signatures are checked under the `substrate` signing context, like the ones made by wallets.
+fn verify_signature(input: &[u8]) -> VerificationResult {
+    let msg = get_slice(input, 4, 36).map_err(|_| "Failed to get message slice")?;
+
+    let public_key = get_slice(input, 36, 68)
+        .map_err(|_| "Failed to get public key slice")
+        .and_then(|pk| Public::try_from(pk).map_err(|_| "Public key recover failed"))?;
+
+    let signature = get_slice(input, 68, 132)
+        .map_err(|_| "Failed to get signature slice")
+        .and_then(|sig| Signature::try_from(sig).map_err(|_| "Signature recover failed"))?;
+
+    Ok(signature.verify(msg, &public_key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::precompiles::get_method_id;
+    use hex_literal::hex;
+
+    const MESSAGE: [u8; 32] = [0x42; 32];
+    const PUBLIC_KEY: [u8; 32] =
+        hex!("7c0f469d3bd340bae718203fa30ca071a5e37c751e891dbded837b213d45d91d");
+    const SIGNATURE: [u8; 64] = hex!(
+        "58a57fff92f372c6f08afbe84fdfddb9f86e2574b8d74931dca0f159863ee82c"
+        "662f526de1f1b5bbbb3d485b79251ea8ace4be6998363c196eedcfb475a7318a"
+    );
+
+    fn input(message: &[u8], public_key: &[u8], signature: &[u8]) -> Vec<u8> {
+        let mut input = get_method_id("verify(bytes32,bytes32,bytes32,bytes32)").to_vec();
+        input.extend_from_slice(message);
+        input.extend_from_slice(public_key);
+        input.extend_from_slice(signature);
+        input
+    }
+
+    fn verify(input: &[u8]) -> PrecompileResult<bool> {
+        let (_, output) = Sr25519VerifyPrecompile::execute(input, 0)?;
+        Ok(output.last() == Some(&1))
+    }
+
+    #[test]
+    fn accepts_valid_signatures() {
+        assert_eq!(
+            verify(&input(&MESSAGE, &PUBLIC_KEY, &SIGNATURE)).ok(),
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn rejects_signatures_of_other_messages_or_keys() {
+        assert_eq!(
+            verify(&input(&[0x43; 32], &PUBLIC_KEY, &SIGNATURE)).ok(),
+            Some(false)
+        );
+        assert_eq!(
+            verify(&input(&MESSAGE, &[0x01; 32], &SIGNATURE)).ok(),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn fails_on_short_input() {
+        let mut input = input(&MESSAGE, &PUBLIC_KEY, &SIGNATURE);
+        input.pop();
+        assert!(verify(&input).is_err());
+    }
+}
@@ -0,0 +1,110 @@
+use crate::mock::*;
+use frame_support::{
+    assert_err, assert_ok,
+    pallet_prelude::{InvalidTransaction, TransactionSource, ValidateUnsigned},
+};
+use pallet_faucet::{Error, Event, FaucetConfig, FaucetConfiguration, LastFaucetClaim};
+use sp_runtime::{transaction_validity::TransactionValidity, DispatchError};
+
+const KEY: AccountId = 0;
+const BLOCK: u64 = 20_000;
+
+fn faucet_call(block_number: u64) -> pallet_faucet::Call<Test> {
+    pallet_faucet::Call::faucet {
+        block_number,
+        nonce: 0,
+        work: vec![0; 32],
+        key: KEY,
+    }
+}
+
+fn validate_claim() -> TransactionValidity {
+    FaucetMod::validate_unsigned(TransactionSource::External, &faucet_call(BLOCK))
+}
+
+fn claim() -> frame_support::dispatch::DispatchResult {
+    FaucetMod::faucet(RuntimeOrigin::none(), BLOCK, 0, vec![0; 32], KEY)
+}
+
+#[test]
+fn claims_on_cooldown_are_rejected() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(BLOCK);
+        let cooldown = FaucetConfig::<Test>::get().cooldown;
+        LastFaucetClaim::<Test>::insert(KEY, BLOCK + 1 - cooldown);
+
+        assert_eq!(validate_claim(), InvalidTransaction::Custom(1).into());
+        assert_err!(claim(), Error::<Test>::ClaimOnCooldown);
+    });
+}
+
+#[test]
+fn claims_are_accepted_once_the_cooldown_passed() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(BLOCK);
+        assert_ok!(validate_claim());
+
+        let cooldown = FaucetConfig::<Test>::get().cooldown;
+        LastFaucetClaim::<Test>::insert(KEY, BLOCK - cooldown);
+        assert_ok!(validate_claim());
+    });
+}
+
+#[test]
+fn accounts_over_the_wealth_cap_are_rejected() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(BLOCK);
+        let wealth_cap = FaucetConfig::<Test>::get().wealth_cap;
+
+        add_balance(KEY, wealth_cap / 2);
+        assert_ok!(validate_claim());
+
+        // Stake counts towards the cap along with the balance
+        increase_stake(KEY, wealth_cap / 2);
+        assert_eq!(validate_claim(), InvalidTransaction::Custom(0).into());
+        assert_err!(claim(), Error::<Test>::TooWealthy);
+    });
+}
+
+#[test]
+fn config_updates_take_effect() {
+    new_test_ext().execute_with(|| {
+        System::set_block_number(BLOCK);
+        LastFaucetClaim::<Test>::insert(KEY, BLOCK - 10);
+        assert_eq!(validate_claim(), InvalidTransaction::Custom(1).into());
+
+        let config = FaucetConfiguration {
+            cooldown: 10,
+            wealth_cap: to_nano(100),
+            ..Default::default()
+        };
+
+        assert_err!(
+            FaucetMod::set_faucet_config(get_origin(KEY), config.clone()),
+            DispatchError::BadOrigin
+        );
+        assert_err!(
+            FaucetMod::set_faucet_config(
+                RuntimeOrigin::root(),
+                FaucetConfiguration {
+                    difficulty: 0,
+                    ..config.clone()
+                }
+            ),
+            Error::<Test>::InvalidFaucetConfig
+        );
+
+        assert_ok!(FaucetMod::set_faucet_config(
+            RuntimeOrigin::root(),
+            config.clone()
+        ));
+        System::assert_last_event(RuntimeEvent::FaucetMod(Event::FaucetConfigUpdated(
+            config.clone(),
+        )));
+        assert_eq!(FaucetConfig::<Test>::get(), config);
+        assert_ok!(validate_claim());
+
+        add_balance(KEY, to_nano(100));
+        assert_eq!(validate_claim(), InvalidTransaction::Custom(0).into());
+    });
+}
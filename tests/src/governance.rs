@@ -2,14 +2,34 @@
 // Proposal
 // ---------
 use crate::mock::*;
+use frame_support::traits::{OnRuntimeUpgrade, StorageVersion};
 pub use frame_support::{assert_err, assert_noop, assert_ok};
 use pallet_governance::{
-    dao::ApplicationStatus, proposal::get_reward_allocation, Curator, CuratorApplications,
-    DaoTreasuryAddress, Error, GeneralSubnetApplicationCost, GlobalGovernanceConfig, GovernanceApi,
-    ProposalStatus, Proposals, SubnetGovernanceConfig, VoteMode,
+    dao::ApplicationStatus,
+    migrations::{
+        testnet,
+        v3::MigrateToV3,
+        v4, v5,
+        v6::{self, MigrateToV6},
+    },
+    proposal::get_reward_allocation,
+    ActiveProposals, CuratorApplications, CuratorApplicationsByUser, CuratorApprovalExpiration,
+    CuratorApprovalThreshold, CuratorApprovals, DaoTreasuryAddress, Error,
+    GeneralSubnetApplicationCost, GlobalGovernanceConfig, GovernanceApi, NextApplicationId,
+    NextProposalId, OpenProposalCount, ProposalData, ProposalPruneCursor, ProposalRetentionPeriod,
+    ProposalStatus, Proposals, SubnetAction, SubnetGovernanceConfig, TreasuryGrantPayouts,
+    TreasuryGrants, UnrewardedProposals, VoteMode,
 };
-use pallet_governance_api::GovernanceConfiguration;
-use pallet_subspace::{params::subnet::SubnetChangeset, GlobalParams, SubnetParams};
+use pallet_governance_api::{
+    DepositPolicy, ForfeitDestination, GovernanceConfiguration, ProposalThresholds,
+};
+use pallet_subnet_emission::PendingConsensusChange;
+use pallet_subnet_emission_api::SubnetConsensus;
+use pallet_subspace::{
+    migrations::v16, params::subnet::SubnetChangeset, Burn, GlobalParams, ModuleBurnConfig,
+    RegistrationCost, RegistrationDeposits, SubnetParams,
+};
+use sp_runtime::Percent;
 use substrate_fixed::{types::extra::U32, FixedI128};
 
 fn register(account: AccountId, subnet_id: u16, module: AccountId, stake: u64) {
@@ -74,6 +94,8 @@ fn global_proposal_validates_parameters() {
                 max_allowed_weights,
                 floor_stake_delegation_fee,
                 floor_validator_weight_fee,
+                ceiling_stake_delegation_fee,
+                ceiling_validator_weight_fee,
                 floor_founder_share,
                 min_weight_stake,
                 curator,
@@ -82,6 +104,7 @@ fn global_proposal_validates_parameters() {
                 kappa,
                 rho,
                 subnet_immunity_period,
+                fee_announcement_period,
             } = global_params;
 
             GovernanceMod::add_global_params_proposal(
@@ -104,6 +127,9 @@ fn global_proposal_validates_parameters() {
                 kappa,
                 rho,
                 subnet_immunity_period,
+                ceiling_stake_delegation_fee,
+                ceiling_validator_weight_fee,
+                fee_announcement_period,
             )
         };
 
@@ -116,6 +142,20 @@ fn global_proposal_validates_parameters() {
         })
         .expect_err("created proposal with invalid max name length");
 
+        test(GlobalParams {
+            ceiling_validator_weight_fee: Percent::from_percent(1),
+            ..SubspaceMod::global_params()
+        })
+        .expect_err("created proposal with a maximum fee below the minimum");
+
+        assert_err!(
+            test(GlobalParams {
+                curator: KEY,
+                ..SubspaceMod::global_params()
+            }),
+            pallet_subspace::Error::<Test>::InvalidCurator
+        );
+
         test(SubspaceMod::global_params())
             .expect("failed to create proposal with valid parameters");
     });
@@ -232,6 +272,115 @@ fn global_proposal_is_refused_correctly() {
     });
 }
 
+#[test]
+fn governance_config_validates_thresholds() {
+    new_test_ext().execute_with(|| {
+        assert_err!(
+            GovernanceMod::update_global_governance_configuration(GovernanceConfiguration {
+                params_thresholds: ProposalThresholds::new(0, 50),
+                ..Default::default()
+            }),
+            Error::<Test>::InvalidProposalQuorum
+        );
+        assert_err!(
+            GovernanceMod::update_subnet_governance_configuration(
+                0,
+                GovernanceConfiguration {
+                    treasury_thresholds: ProposalThresholds::new(50, 49),
+                    ..Default::default()
+                }
+            ),
+            Error::<Test>::InvalidProposalApproval
+        );
+        assert_ok!(GovernanceMod::update_global_governance_configuration(
+            GovernanceConfiguration {
+                custom_thresholds: ProposalThresholds::new(10, 100),
+                ..Default::default()
+            }
+        ));
+    });
+}
+
+#[test]
+fn proposal_thresholds_decide_the_outcome() {
+    new_test_ext().execute_with(|| {
+        zero_min_burn();
+
+        register(0, 0, 0, to_nano(6));
+        register(1, 0, 1, to_nano(4));
+        register(2, 0, 2, to_nano(10));
+
+        GlobalGovernanceConfig::<Test>::set(GovernanceConfiguration {
+            proposal_cost: 1,
+            proposal_expiration: 100,
+            custom_thresholds: ProposalThresholds::new(50, 67),
+            ..Default::default()
+        });
+        SubnetGovernanceConfig::<Test>::insert(
+            0,
+            GovernanceConfiguration {
+                custom_thresholds: ProposalThresholds::new(10, 50),
+                ..Default::default()
+            },
+        );
+
+        for _ in 0..3 {
+            assert_ok!(GovernanceMod::add_global_custom_proposal(
+                get_origin(0),
+                vec![b'0'; 64]
+            ));
+        }
+        assert_ok!(GovernanceMod::add_subnet_custom_proposal(
+            get_origin(0),
+            0,
+            vec![b'0'; 64]
+        ));
+
+        // Reaches the quorum, but not the supermajority.
+        vote(0, 0, true);
+        vote(1, 0, false);
+        // Doesn't reach the quorum.
+        vote(0, 1, true);
+        // Reaches both.
+        vote(0, 2, true);
+        vote(1, 2, false);
+        vote(2, 2, true);
+        // Reaches the subnet's lower quorum.
+        vote(1, 3, true);
+
+        step_block(100);
+
+        assert_eq!(
+            Proposals::<Test>::get(0).unwrap().status,
+            ProposalStatus::Refused {
+                block: 100,
+                stake_for: to_nano(6),
+                stake_against: to_nano(4),
+            }
+        );
+        assert_eq!(
+            Proposals::<Test>::get(1).unwrap().status,
+            ProposalStatus::Expired
+        );
+        assert_eq!(
+            Proposals::<Test>::get(2).unwrap().status,
+            ProposalStatus::Accepted {
+                block: 100,
+                stake_for: to_nano(16),
+                stake_against: to_nano(4),
+            }
+        );
+        assert_eq!(
+            Proposals::<Test>::get(3).unwrap().status,
+            ProposalStatus::Accepted {
+                block: 100,
+                stake_for: to_nano(4),
+                stake_against: 0,
+            }
+        );
+    });
+}
+
 #[test]
 fn global_params_proposal_accepted() {
     new_test_ext().execute_with(|| {
@@ -251,6 +400,8 @@ fn global_params_proposal_accepted() {
             max_allowed_weights,
             floor_stake_delegation_fee,
             floor_validator_weight_fee,
+            ceiling_stake_delegation_fee,
+            ceiling_validator_weight_fee,
             floor_founder_share,
             min_weight_stake,
             curator,
@@ -259,6 +410,7 @@ fn global_params_proposal_accepted() {
             rho,
             kappa,
             subnet_immunity_period,
+            fee_announcement_period,
         } = SubspaceMod::global_params();
 
         governance_config.proposal_cost = 69_420;
@@ -283,6 +435,9 @@ fn global_params_proposal_accepted() {
             kappa,
             rho,
             subnet_immunity_period,
+            ceiling_stake_delegation_fee,
+            ceiling_validator_weight_fee,
+            fee_announcement_period,
         )
         .unwrap();
 
@@ -335,6 +490,8 @@ fn subnet_params_proposal_accepted() {
             use_weights_encryption,
             copier_margin,
             max_encryption_period,
+            pruning_policy,
+            registration_mode,
             ..
         } = SubspaceMod::subnet_params(0);
 
@@ -364,6 +521,8 @@ fn subnet_params_proposal_accepted() {
             use_weights_encryption,
             copier_margin,
             max_encryption_period,
+            pruning_policy,
+            registration_mode,
         )
         .unwrap();
 
@@ -377,6 +536,124 @@ fn subnet_params_proposal_accepted() {
     });
 }
 
+#[test]
+fn subnet_action_proposals_execute_founder_actions() {
+    new_test_ext().execute_with(|| {
+        zero_min_burn();
+
+        register(0, 0, 0, to_nano(10));
+        ModuleBurnConfig::<Test>::mutate(0, |config| {
+            config.registration_cost = RegistrationCost::Deposit {
+                min_tenure: 0,
+                refund: Percent::from_percent(100),
+            };
+        });
+        Burn::<Test>::insert(0, to_nano(5));
+        add_balance(1, to_nano(10));
+        register(1, 0, 1, to_nano(1));
+        assert!(RegistrationDeposits::<Test>::contains_key(0, 1));
+        config(1, 100);
+
+        let propose = |action| {
+            GovernanceMod::add_subnet_action_proposal(get_origin(0), 0, vec![b'0'; 64], action)
+        };
+
+        assert_err!(
+            propose(SubnetAction::SetFounderShare(10)),
+            Error::<Test>::NotVoteMode
+        );
+
+        SubnetChangeset::update(
+            0,
+            SubnetParams {
+                governance_config: Default::default(),
+                ..SubspaceMod::subnet_params(0)
+            },
+        )
+        .unwrap()
+        .apply(0)
+        .unwrap();
+
+        assert_err!(
+            propose(SubnetAction::SetFounderShare(101)),
+            pallet_subspace::Error::<Test>::InvalidFounderShare
+        );
+        assert_err!(
+            propose(SubnetAction::DeregisterModule(99)),
+            pallet_subspace::Error::<Test>::ModuleDoesNotExist
+        );
+
+        let use_weights_encryption = SubspaceMod::subnet_params(0).use_weights_encryption;
+        assert_ok!(propose(SubnetAction::ReplaceFounder(7)));
+        assert_ok!(propose(SubnetAction::SetUseWeightsEncryption(
+            !use_weights_encryption
+        )));
+        assert_ok!(propose(SubnetAction::DeregisterModule(1)));
+        for id in 0..3 {
+            vote(0, id, true);
+        }
+
+        let treasury = get_balance(DaoTreasuryAddress::<Test>::get());
+        let module_balance = get_balance(1);
+        step_block(100);
+
+        // The deregistered module forfeits its deposit, even though it would get all of it back
+        // if it left on its own
+        assert!(!RegistrationDeposits::<Test>::contains_key(0, 1));
+        assert!(get_balance(DaoTreasuryAddress::<Test>::get()) >= treasury + to_nano(5));
+        assert_eq!(get_balance(1), module_balance);
+        assert_eq!(pallet_balances::Pallet::<Test>::reserved_balance(1), 0);
+
+        let params = SubspaceMod::subnet_params(0);
+        assert_eq!(params.founder, 7);
+        assert_eq!(params.use_weights_encryption, !use_weights_encryption);
+        assert_eq!(SubspaceMod::get_uid_for_key(0, &1), None);
+        assert_eq!(SubspaceMod::get_uid_for_key(0, &0), Some(0));
+    });
+}
+
+#[test]
+fn subnet_action_proposals_dont_execute_in_authority_mode() {
+    new_test_ext().execute_with(|| {
+        zero_min_burn();
+
+        register(0, 0, 0, to_nano(10));
+        config(1, 100);
+
+        let set_vote_mode = |vote_mode| {
+            SubnetChangeset::update(
+                0,
+                SubnetParams {
+                    governance_config: GovernanceConfiguration {
+                        vote_mode,
+                        ..Default::default()
+                    },
+                    ..SubspaceMod::subnet_params(0)
+                },
+            )
+            .unwrap()
+            .apply(0)
+            .unwrap();
+        };
+
+        set_vote_mode(VoteMode::Vote);
+        assert_ok!(GovernanceMod::add_subnet_action_proposal(
+            get_origin(0),
+            0,
+            vec![b'0'; 64],
+            SubnetAction::ReplaceFounder(7)
+        ));
+        vote(0, 0, true);
+
+        // The founder takes the subnet back before the proposal is executed
+        set_vote_mode(VoteMode::Authority);
+        step_block(100);
+
+        assert_eq!(SubspaceMod::subnet_params(0).founder, 0);
+        assert!(Proposals::<Test>::get(0).unwrap().is_active());
+    });
+}
+
 #[test]
 fn global_proposals_counts_delegated_stake() {
     new_test_ext().execute_with(|| {
@@ -475,158 +752,1160 @@ fn subnet_proposals_counts_delegated_stake() {
 }
 
 #[test]
-fn creates_treasury_transfer_proposal_and_transfers() {
+fn pool_members_vote_with_their_pooled_stake() {
     new_test_ext().execute_with(|| {
         zero_min_burn();
 
-        let origin = get_origin(0);
-        GovernanceMod::add_transfer_dao_treasury_proposal(
-            origin.clone(),
-            vec![b'0'; 64],
-            to_nano(5),
-            0,
-        )
-        .expect_err("proposal should not be created when treasury does not have enough money");
+        const MODULE: u32 = 0;
+        const VOTER: u32 = 1;
+        const DELEGATOR: u32 = 2;
 
-        add_balance(DaoTreasuryAddress::<Test>::get(), to_nano(10));
-        add_balance(0, to_nano(3));
-        register(0, 0, 0, to_nano(1));
-        config(to_nano(1), 100);
+        register(MODULE, 0, MODULE, to_nano(10));
+        assert_ok!(SubspaceMod::create_pool(get_origin(MODULE), vec![MODULE]));
 
-        GovernanceMod::add_transfer_dao_treasury_proposal(origin, vec![b'0'; 64], to_nano(5), 0)
-            .expect("proposal should be created");
-        vote(0, 0, true);
+        for member in [VOTER, DELEGATOR] {
+            add_balance(member, to_nano(11));
+            assert_ok!(SubspaceMod::join_pool(get_origin(member), 0, to_nano(10)));
+        }
+        assert_ok!(GovernanceMod::disable_vote_power_delegation(get_origin(
+            VOTER
+        )));
 
-        step_block(100);
+        config(1, 100);
 
-        assert_eq!(get_balance(DaoTreasuryAddress::<Test>::get()), to_nano(5));
-        assert_eq!(get_balance(0), to_nano(8));
-    });
-}
+        assert_ok!(GovernanceMod::do_add_global_custom_proposal(
+            get_origin(MODULE),
+            vec![b'0'; 64]
+        ));
 
-/// This test, observes the distribution of governance reward logic over time.
-#[test]
-fn rewards_wont_exceed_treasury() {
-    new_test_ext().execute_with(|| {
-        zero_min_burn();
+        vote(VOTER, 0, true);
+        vote(MODULE, 0, false);
 
-        // Fill the governance address with 1 mil so we are not limited by the max allocation
-        let amount = to_nano(1_000_000_000);
-        let key = DaoTreasuryAddress::<Test>::get();
-        add_balance(key, amount);
+        step_block(100);
 
-        let governance_config: GovernanceConfiguration = GlobalGovernanceConfig::<Test>::get();
-        let n = 0;
-        let allocation = get_reward_allocation::<Test>(&governance_config, n).unwrap();
+        // the module only gets the pooled stake of the member who delegates
         assert_eq!(
-            FixedI128::<U32>::saturating_from_num(allocation),
-            governance_config.max_proposal_reward_treasury_allocation
+            Proposals::<Test>::get(0).unwrap().status,
+            ProposalStatus::Refused {
+                block: 100,
+                stake_for: 10_000_000_000,
+                stake_against: 20_000_000_000,
+            }
         );
     });
 }
 
 #[test]
-fn whitelist_executes_application_correctly() {
+fn pool_members_delegating_votes_are_only_counted_once() {
     new_test_ext().execute_with(|| {
-        let key = 0;
-        let adding_key = 1;
-        let mut params = SubspaceMod::global_params();
-        params.curator = key;
-        assert_ok!(SubspaceMod::set_global_params(params));
-
-        let proposal_cost = GeneralSubnetApplicationCost::<Test>::get();
-        let data = "test".as_bytes().to_vec();
-
-        add_balance(key, proposal_cost + 1);
-        // first submit an application
-        let balance_before = SubspaceMod::get_balance_u64(&key);
+        zero_min_burn();
 
-        assert_ok!(GovernanceMod::add_dao_application(
-            get_origin(key),
-            adding_key,
-            data.clone(),
-        ));
+        const MODULE: u32 = 0;
+        const VOTER: u32 = 1;
+        const DELEGATOR: u32 = 2;
+        const REPRESENTATIVE: u32 = 3;
 
-        let balance_after = SubspaceMod::get_balance_u64(&key);
-        assert_eq!(balance_after, balance_before - proposal_cost);
+        register(MODULE, 0, MODULE, to_nano(10));
+        assert_ok!(SubspaceMod::create_pool(get_origin(MODULE), vec![MODULE]));
 
-        // Assert that the proposal is initially in the Pending status
-        for (_, value) in CuratorApplications::<Test>::iter() {
-            assert_eq!(value.status, ApplicationStatus::Pending);
-            assert_eq!(value.user_id, adding_key);
-            assert_eq!(value.data, data);
+        for member in [VOTER, DELEGATOR] {
+            add_balance(member, to_nano(11));
+            assert_ok!(SubspaceMod::join_pool(get_origin(member), 0, to_nano(10)));
         }
+        assert_ok!(GovernanceMod::disable_vote_power_delegation(get_origin(
+            VOTER
+        )));
+        assert_ok!(GovernanceMod::delegate_votes(
+            get_origin(DELEGATOR),
+            REPRESENTATIVE,
+            None
+        ));
 
-        // add key to whitelist
-        assert_ok!(GovernanceMod::add_to_whitelist(get_origin(key), adding_key,));
+        config(1, 100);
 
-        let balance_after_accept = SubspaceMod::get_balance_u64(&key);
+        assert_ok!(GovernanceMod::do_add_global_custom_proposal(
+            get_origin(MODULE),
+            vec![b'0'; 64]
+        ));
 
-        assert_eq!(balance_after_accept, balance_before);
+        vote(VOTER, 0, true);
+        vote(REPRESENTATIVE, 0, true);
+        vote(MODULE, 0, false);
 
-        // Assert that the proposal is now in the Accepted status
-        for (_, value) in CuratorApplications::<Test>::iter() {
-            assert_eq!(value.status, ApplicationStatus::Accepted);
-            assert_eq!(value.user_id, adding_key);
-            assert_eq!(value.data, data);
-        }
+        step_block(100);
 
-        assert!(GovernanceMod::is_in_legit_whitelist(&adding_key));
+        // The delegator's pooled stake goes to the representative only, so the tally still adds
+        // up to the 30 tokens staked.
+        assert_eq!(
+            Proposals::<Test>::get(0).unwrap().status,
+            ProposalStatus::Accepted {
+                block: 100,
+                stake_for: to_nano(20),
+                stake_against: to_nano(10),
+            }
+        );
     });
 }
 
-// ----------------
-// Registration
-// ----------------
-
 #[test]
-fn user_is_removed_from_whitelist() {
+fn delegated_votes_count_for_the_representative() {
     new_test_ext().execute_with(|| {
-        let whitelist_key = 0;
-        let module_key = 1;
-        Curator::<Test>::put(whitelist_key);
+        zero_min_burn();
 
-        let proposal_cost = Test::get_global_governance_configuration().proposal_cost;
-        let data = "test".as_bytes().to_vec();
+        const STAKER: u32 = 1;
+        const REPRESENTATIVE: u32 = 2;
+        const AGAINST: u32 = 3;
+        const VALIDATOR: u32 = 10;
 
-        // apply
-        add_balance(whitelist_key, proposal_cost + 1);
-        // first submit an application
-        assert_ok!(GovernanceMod::add_dao_application(
-            get_origin(whitelist_key),
-            module_key,
-            data.clone(),
-        ));
+        register(VALIDATOR, 0, VALIDATOR, to_nano(4));
+        register(AGAINST, 0, AGAINST, to_nano(5));
+        SubspaceMod::increase_stake(&STAKER, &VALIDATOR, to_nano(6));
+        config(1, 100);
 
-        // Add the module_key to the whitelist
-        assert_ok!(GovernanceMod::add_to_whitelist(
-            get_origin(whitelist_key),
-            module_key,
+        assert_ok!(GovernanceMod::add_global_custom_proposal(
+            get_origin(VALIDATOR),
+            vec![b'0'; 64]
         ));
-        assert!(GovernanceMod::is_in_legit_whitelist(&module_key));
 
-        // Remove the module_key from the whitelist
-        assert_ok!(GovernanceMod::remove_from_whitelist(
-            get_origin(whitelist_key),
-            module_key
+        assert_err!(
+            GovernanceMod::vote_proposal(get_origin(REPRESENTATIVE), 0, true),
+            Error::<Test>::InsufficientStake
+        );
+        assert_err!(
+            GovernanceMod::delegate_votes(get_origin(STAKER), STAKER, None),
+            Error::<Test>::SelfVoteDelegation
+        );
+        assert_ok!(GovernanceMod::delegate_votes(
+            get_origin(STAKER),
+            REPRESENTATIVE,
+            None
         ));
-        assert!(!GovernanceMod::is_in_legit_whitelist(&module_key));
+        assert_err!(
+            GovernanceMod::vote_proposal(get_origin(STAKER), 0, true),
+            Error::<Test>::VotesDelegatedToAccount
+        );
+
+        vote(REPRESENTATIVE, 0, true);
+        vote(AGAINST, 0, false);
+
+        step_block(100);
+
+        assert_eq!(
+            Proposals::<Test>::get(0).unwrap().status,
+            ProposalStatus::Accepted {
+                block: 100,
+                stake_for: to_nano(6),
+                stake_against: to_nano(5),
+            }
+        );
+
+        // The staker is rewarded for their own stake.
+        let unrewarded = UnrewardedProposals::<Test>::get(0).unwrap();
+        assert_eq!(unrewarded.votes_for.get(&STAKER), Some(&to_nano(6)));
+        assert_eq!(unrewarded.votes_for.get(&REPRESENTATIVE), Some(&0));
     });
 }
 
 #[test]
-fn whitelist_curator_must_be_a_valid_key() {
+fn vote_delegation_rejects_cycles_and_caps_hops() {
     new_test_ext().execute_with(|| {
-        let whitelist_key = 0;
-        let invalid_key = 1;
-        let module_key = 2;
-        Curator::<Test>::put(whitelist_key);
+        zero_min_burn();
 
-        // Try to add to whitelist with an invalid curator key
-        assert_noop!(
-            GovernanceMod::add_to_whitelist(get_origin(invalid_key), module_key),
-            Error::<Test>::NotCurator
+        const VALIDATOR: u32 = 10;
+
+        register(VALIDATOR, 0, VALIDATOR, to_nano(1));
+        config(1, 100);
+
+        for delegator in 1..=4 {
+            SubspaceMod::increase_stake(&delegator, &VALIDATOR, to_nano(1));
+            assert_ok!(GovernanceMod::delegate_votes(
+                get_origin(delegator),
+                delegator + 1,
+                None
+            ));
+        }
+
+        assert_err!(
+            GovernanceMod::delegate_votes(get_origin(5), 1, None),
+            Error::<Test>::VoteDelegationCycle
+        );
+        assert_err!(
+            GovernanceMod::delegate_votes(get_origin(5), 3, Some(0)),
+            Error::<Test>::VoteDelegationCycle
+        );
+
+        assert_ok!(GovernanceMod::add_global_custom_proposal(
+            get_origin(VALIDATOR),
+            vec![b'0'; 64]
+        ));
+        vote(5, 0, true);
+
+        step_block(100);
+
+        // Account 1 is four delegations away from the voter.
+        assert_eq!(
+            Proposals::<Test>::get(0).unwrap().status,
+            ProposalStatus::Accepted {
+                block: 100,
+                stake_for: to_nano(3),
+                stake_against: 0,
+            }
+        );
+        let unrewarded = UnrewardedProposals::<Test>::get(0).unwrap();
+        assert!(!unrewarded.votes_for.contains_key(&1));
+    });
+}
+
+#[test]
+fn subnet_vote_delegation_overrides_the_global_one() {
+    new_test_ext().execute_with(|| {
+        zero_min_burn();
+
+        const STAKER: u32 = 1;
+        const GLOBAL_REPRESENTATIVE: u32 = 2;
+        const SUBNET_REPRESENTATIVE: u32 = 3;
+        const VALIDATOR: u32 = 10;
+
+        register(VALIDATOR, 0, VALIDATOR, to_nano(4));
+        SubspaceMod::increase_stake(&STAKER, &VALIDATOR, to_nano(6));
+        config(1, 100);
+
+        assert_ok!(GovernanceMod::delegate_votes(
+            get_origin(STAKER),
+            GLOBAL_REPRESENTATIVE,
+            None
+        ));
+        assert_ok!(GovernanceMod::delegate_votes(
+            get_origin(STAKER),
+            SUBNET_REPRESENTATIVE,
+            Some(0)
+        ));
+
+        assert_ok!(GovernanceMod::add_global_custom_proposal(
+            get_origin(VALIDATOR),
+            vec![b'0'; 64]
+        ));
+        assert_ok!(GovernanceMod::add_subnet_custom_proposal(
+            get_origin(VALIDATOR),
+            0,
+            vec![b'0'; 64]
+        ));
+
+        assert_err!(
+            GovernanceMod::vote_proposal(get_origin(SUBNET_REPRESENTATIVE), 0, true),
+            Error::<Test>::InsufficientStake
+        );
+        assert_err!(
+            GovernanceMod::vote_proposal(get_origin(GLOBAL_REPRESENTATIVE), 1, true),
+            Error::<Test>::InsufficientStake
+        );
+        vote(GLOBAL_REPRESENTATIVE, 0, true);
+        vote(SUBNET_REPRESENTATIVE, 1, true);
+
+        step_block(100);
+
+        for proposal_id in [0, 1] {
+            assert_eq!(
+                Proposals::<Test>::get(proposal_id).unwrap().status,
+                ProposalStatus::Accepted {
+                    block: 100,
+                    stake_for: to_nano(6),
+                    stake_against: 0,
+                }
+            );
+        }
+
+        assert_ok!(GovernanceMod::undelegate_votes(get_origin(STAKER), Some(0)));
+        assert_eq!(
+            GovernanceMod::vote_delegation(&STAKER, Some(0)),
+            Some(GLOBAL_REPRESENTATIVE)
+        );
+        assert_err!(
+            GovernanceMod::undelegate_votes(get_origin(STAKER), Some(0)),
+            Error::<Test>::VoteDelegationNotFound
+        );
+    });
+}
+
+#[test]
+fn subnet_consensus_proposal_schedules_the_change() {
+    new_test_ext().execute_with(|| {
+        zero_min_burn();
+
+        register(0, 0, 0, to_nano(10));
+        config(1, 100);
+
+        assert_err!(
+            GovernanceMod::add_subnet_consensus_proposal(
+                get_origin(0),
+                0,
+                vec![b'0'; 64],
+                SubnetConsensus::Root
+            ),
+            pallet_subspace::Error::<Test>::InvalidConsensusChange
+        );
+        assert_ok!(GovernanceMod::add_subnet_consensus_proposal(
+            get_origin(0),
+            0,
+            vec![b'0'; 64],
+            SubnetConsensus::Linear
+        ));
+
+        vote(0, 0, true);
+        step_block(100);
+
+        assert!(matches!(
+            Proposals::<Test>::get(0).unwrap().status,
+            ProposalStatus::Accepted { .. }
+        ));
+        assert_eq!(
+            PendingConsensusChange::<Test>::get(0),
+            Some(SubnetConsensus::Linear)
+        );
+    });
+}
+
+#[test]
+fn deposit_policy_refunds_by_outcome() {
+    new_test_ext().execute_with(|| {
+        zero_min_burn();
+
+        const PROPOSER: u32 = 3;
+        let treasury = DaoTreasuryAddress::<Test>::get();
+
+        register(0, 0, 0, to_nano(6));
+        register(1, 0, 1, to_nano(4));
+        add_balance(PROPOSER, to_nano(41));
+
+        let deposit_policy = DepositPolicy {
+            accepted_refund: Percent::from_percent(100),
+            refused_refund: Percent::from_percent(50),
+            expired_refund: Percent::from_percent(20),
+            forfeit_destination: ForfeitDestination::Treasury,
+            max_open_proposals_per_account: 3,
+        };
+        assert_err!(
+            GovernanceMod::update_global_governance_configuration(GovernanceConfiguration {
+                deposit_policy: DepositPolicy {
+                    max_open_proposals_per_account: 0,
+                    ..deposit_policy
+                },
+                ..Default::default()
+            }),
+            Error::<Test>::InvalidMaxOpenProposals
+        );
+        GlobalGovernanceConfig::<Test>::set(GovernanceConfiguration {
+            proposal_cost: to_nano(10),
+            proposal_expiration: 100,
+            deposit_policy,
+            ..Default::default()
+        });
+
+        for _ in 0..3 {
+            assert_ok!(GovernanceMod::add_global_custom_proposal(
+                get_origin(PROPOSER),
+                vec![b'0'; 64]
+            ));
+        }
+        assert_err!(
+            GovernanceMod::add_global_custom_proposal(get_origin(PROPOSER), vec![b'0'; 64]),
+            Error::<Test>::TooManyOpenProposals
+        );
+        assert_eq!(get_balance(PROPOSER), to_nano(11));
+        let treasury_balance = get_balance(treasury);
+
+        // Accepted.
+        vote(0, 0, true);
+        // Refused.
+        vote(0, 1, false);
+        vote(1, 1, true);
+        // Proposal 2 expires without votes.
+
+        step_block(100);
+
+        assert!(matches!(
+            Proposals::<Test>::get(0).unwrap().status,
+            ProposalStatus::Accepted { .. }
+        ));
+        assert!(matches!(
+            Proposals::<Test>::get(1).unwrap().status,
+            ProposalStatus::Refused { .. }
+        ));
+        assert_eq!(
+            Proposals::<Test>::get(2).unwrap().status,
+            ProposalStatus::Expired
+        );
+
+        assert_eq!(get_balance(PROPOSER), to_nano(11 + 10 + 5 + 2));
+        assert_eq!(get_balance(treasury), treasury_balance + to_nano(5 + 8));
+
+        // Finished proposals don't count towards the cap.
+        assert_ok!(GovernanceMod::add_global_custom_proposal(
+            get_origin(PROPOSER),
+            vec![b'0'; 64]
+        ));
+    });
+}
+
+#[test]
+fn subnet_proposals_follow_the_subnet_deposit_policy() {
+    new_test_ext().execute_with(|| {
+        zero_min_burn();
+
+        const PROPOSER: u32 = 3;
+
+        register(0, 0, 0, to_nano(10));
+        add_balance(PROPOSER, to_nano(20));
+        config(to_nano(10), 100);
+        SubnetGovernanceConfig::<Test>::mutate(0, |config| {
+            config.vote_mode = VoteMode::Vote;
+            config.deposit_policy = DepositPolicy {
+                refused_refund: Percent::from_percent(100),
+                max_open_proposals_per_account: 1,
+                ..Default::default()
+            };
+        });
+
+        assert_ok!(GovernanceMod::add_subnet_custom_proposal(
+            get_origin(PROPOSER),
+            0,
+            vec![b'0'; 64]
+        ));
+        assert_err!(
+            GovernanceMod::add_subnet_custom_proposal(get_origin(PROPOSER), 0, vec![b'0'; 64]),
+            Error::<Test>::TooManyOpenProposals
+        );
+        // The global cap still allows global proposals.
+        assert_ok!(GovernanceMod::add_global_custom_proposal(
+            get_origin(PROPOSER),
+            vec![b'0'; 64]
+        ));
+        assert_eq!(OpenProposalCount::<Test>::get(PROPOSER), 2);
+
+        vote(0, 0, false);
+        step_block(100);
+
+        assert!(matches!(
+            Proposals::<Test>::get(0).unwrap().status,
+            ProposalStatus::Refused { .. }
+        ));
+        assert_eq!(
+            Proposals::<Test>::get(1).unwrap().status,
+            ProposalStatus::Expired
+        );
+        // Only the refused subnet proposal is refunded.
+        assert_eq!(get_balance(PROPOSER), to_nano(10));
+        assert!(!OpenProposalCount::<Test>::contains_key(PROPOSER));
+    });
+}
+
+#[test]
+fn finished_proposals_are_pruned_after_the_retention_period() {
+    new_test_ext().execute_with(|| {
+        add_balance(0, to_nano(10));
+        config(1, 100);
+        ProposalRetentionPeriod::<Test>::set(200);
+
+        assert_ok!(GovernanceMod::add_global_custom_proposal(
+            get_origin(0),
+            vec![b'0'; 64]
+        ));
+        assert_ok!(GovernanceMod::add_global_custom_proposal(
+            get_origin(0),
+            vec![b'0'; 64]
+        ));
+        assert_eq!(ActiveProposals::<Test>::iter_keys().count(), 2);
+
+        step_block(100);
+        assert_eq!(ActiveProposals::<Test>::iter_keys().count(), 0);
+        assert!(Proposals::<Test>::contains_key(0));
+
+        // Proposal 0 got its rewards, 1 is still waiting for them.
+        UnrewardedProposals::<Test>::remove(0);
+
+        step_block(100);
+        assert!(Proposals::<Test>::contains_key(0));
+
+        step_block(100);
+        assert!(!Proposals::<Test>::contains_key(0));
+        assert!(Proposals::<Test>::contains_key(1));
+        assert_eq!(ProposalPruneCursor::<Test>::get(), 1);
+
+        // Ids aren't reused after pruning.
+        assert_ok!(GovernanceMod::add_global_custom_proposal(
+            get_origin(0),
+            vec![b'0'; 64]
+        ));
+        assert!(Proposals::<Test>::contains_key(2));
+        assert!(ActiveProposals::<Test>::contains_key(2));
+    });
+}
+
+#[test]
+fn index_migration_populates_indexes_across_blocks() {
+    new_test_ext().execute_with(|| {
+        add_balance(0, to_nano(100));
+        config(1, 1_000);
+        GlobalGovernanceConfig::<Test>::mutate(|config| {
+            config.deposit_policy.max_open_proposals_per_account = 100
+        });
+        assert_ok!(GovernanceMod::set_curator_council(vec![0], 1));
+        GeneralSubnetApplicationCost::<Test>::set(to_nano(1));
+
+        for _ in 0..70 {
+            assert_ok!(GovernanceMod::add_global_custom_proposal(
+                get_origin(0),
+                vec![b'0'; 64]
+            ));
+        }
+        assert_ok!(GovernanceMod::add_dao_application(
+            get_origin(0),
+            1,
+            b"test".to_vec()
+        ));
+
+        // Back to the state before the indexes existed.
+        let _ = ActiveProposals::<Test>::clear(u32::MAX, None);
+        let _ = CuratorApplicationsByUser::<Test>::clear(u32::MAX, None);
+        NextProposalId::<Test>::kill();
+        NextApplicationId::<Test>::kill();
+        StorageVersion::new(2).put::<GovernanceMod>();
+
+        MigrateToV3::<Test>::on_runtime_upgrade();
+        assert_eq!(NextProposalId::<Test>::get(), 70);
+        assert_eq!(NextApplicationId::<Test>::get(), 1);
+        assert!(GovernanceMod::indexes_migrating());
+
+        // Lookups keep working while the indexes are incomplete.
+        assert!(GovernanceMod::curator_application_exists(&1));
+
+        step_block(1);
+        assert!(GovernanceMod::indexes_migrating());
+        assert_eq!(ActiveProposals::<Test>::iter_keys().count(), 64);
+
+        step_block(2);
+        assert!(!GovernanceMod::indexes_migrating());
+        assert_eq!(ActiveProposals::<Test>::iter_keys().count(), 70);
+        assert_eq!(CuratorApplicationsByUser::<Test>::get(1), Some(0));
+
+        assert_ok!(GovernanceMod::add_to_whitelist(get_origin(0), 1));
+        assert_eq!(
+            CuratorApplications::<Test>::get(0).unwrap().status,
+            ApplicationStatus::Accepted
+        );
+    });
+}
+
+#[test]
+fn testnet_migration_sets_up_the_indexes() {
+    new_test_ext().execute_with(|| {
+        add_balance(0, to_nano(100));
+        config(1, 1_000);
+
+        for _ in 0..3 {
+            assert_ok!(GovernanceMod::add_global_custom_proposal(
+                get_origin(0),
+                vec![b'0'; 64]
+            ));
+        }
+
+        // Testnet's version 4 is the layout mainnet had at version 2.
+        let _ = ActiveProposals::<Test>::clear(u32::MAX, None);
+        NextProposalId::<Test>::kill();
+        let governance_config = GlobalGovernanceConfig::<Test>::get();
+        frame_support::storage::unhashed::put(
+            &GlobalGovernanceConfig::<Test>::hashed_key(),
+            &old_governance_config(&governance_config),
+        );
+        v5::old_storage::Curator::<Test>::put(7);
+        StorageVersion::new(4).put::<GovernanceMod>();
+
+        testnet::MigrateToV5::<Test>::on_runtime_upgrade();
+        assert_eq!(StorageVersion::get::<GovernanceMod>(), 5);
+        assert_eq!(NextProposalId::<Test>::get(), 3);
+
+        // The single curator becomes a council of its own.
+        assert!(v5::old_storage::Curator::<Test>::get().is_none());
+        assert!(GovernanceMod::is_curator(&7));
+        assert_eq!(CuratorApprovalThreshold::<Test>::get(), 1);
+
+        let migrated_config = GlobalGovernanceConfig::<Test>::get();
+        assert_eq!(
+            migrated_config.proposal_cost,
+            governance_config.proposal_cost
+        );
+        assert_eq!(
+            migrated_config.proposal_expiration,
+            governance_config.proposal_expiration
+        );
+        assert_eq!(
+            migrated_config.params_thresholds,
+            ProposalThresholds::new(40, 50)
+        );
+
+        step_block(1);
+        assert!(!GovernanceMod::indexes_migrating());
+        assert_eq!(ActiveProposals::<Test>::iter_keys().count(), 3);
+
+        // Running it again is a no-op.
+        NextProposalId::<Test>::set(10);
+        testnet::MigrateToV5::<Test>::on_runtime_upgrade();
+        assert_eq!(NextProposalId::<Test>::get(), 10);
+    });
+}
+
+fn old_governance_config(
+    config: &GovernanceConfiguration,
+) -> v4::old_storage::GovernanceConfiguration {
+    v4::old_storage::GovernanceConfiguration {
+        proposal_cost: config.proposal_cost,
+        proposal_expiration: config.proposal_expiration,
+        vote_mode: config.vote_mode,
+        proposal_reward_treasury_allocation: config.proposal_reward_treasury_allocation,
+        max_proposal_reward_treasury_allocation: config.max_proposal_reward_treasury_allocation,
+        proposal_reward_interval: config.proposal_reward_interval,
+    }
+}
+
+fn store_old_proposal(id: u64, data: v6::old_storage::ProposalData<Test>) {
+    let proposal = v6::old_storage::Proposal::<Test> {
+        id,
+        proposer: 0,
+        expiration_block: 100,
+        data,
+        status: ProposalStatus::Open {
+            votes_for: Default::default(),
+            votes_against: Default::default(),
+            stake_for: 0,
+            stake_against: 0,
+        },
+        metadata: Default::default(),
+        proposal_cost: 0,
+        creation_block: 0,
+    };
+    frame_support::storage::unhashed::put(&Proposals::<Test>::hashed_key_for(id), &proposal);
+}
+
+#[test]
+fn v6_migration_re_encodes_param_proposals() {
+    new_test_ext().execute_with(|| {
+        zero_min_burn();
+        register(0, 0, 0, to_nano(1));
+
+        let subnet_params = SubspaceMod::subnet_params(0);
+        let burn = &subnet_params.module_burn_config;
+        store_old_proposal(
+            0,
+            v6::old_storage::ProposalData::SubnetParams {
+                subnet_id: 0,
+                params: v6::old_storage::SubnetParams {
+                    founder: subnet_params.founder,
+                    founder_share: subnet_params.founder_share,
+                    immunity_period: subnet_params.immunity_period,
+                    incentive_ratio: subnet_params.incentive_ratio,
+                    max_allowed_uids: subnet_params.max_allowed_uids,
+                    max_allowed_weights: subnet_params.max_allowed_weights,
+                    min_allowed_weights: subnet_params.min_allowed_weights,
+                    max_weight_age: subnet_params.max_weight_age,
+                    name: subnet_params.name.clone(),
+                    metadata: subnet_params.metadata.clone(),
+                    tempo: subnet_params.tempo,
+                    maximum_set_weight_calls_per_epoch: subnet_params
+                        .maximum_set_weight_calls_per_epoch,
+                    bonds_ma: subnet_params.bonds_ma,
+                    module_burn_config: v16::old_storage::GeneralBurnConfiguration {
+                        min_burn: burn.min_burn,
+                        max_burn: burn.max_burn,
+                        adjustment_alpha: burn.adjustment_alpha,
+                        target_registrations_interval: burn.target_registrations_interval,
+                        target_registrations_per_interval: burn.target_registrations_per_interval,
+                        max_registrations_per_interval: burn.max_registrations_per_interval,
+                    },
+                    min_validator_stake: subnet_params.min_validator_stake,
+                    max_allowed_validators: subnet_params.max_allowed_validators,
+                    governance_config: old_governance_config(&subnet_params.governance_config),
+                    use_weights_encryption: subnet_params.use_weights_encryption,
+                    copier_margin: subnet_params.copier_margin,
+                    max_encryption_period: subnet_params.max_encryption_period,
+                },
+            },
+        );
+
+        let global_params = SubspaceMod::global_params();
+        store_old_proposal(
+            1,
+            v6::old_storage::ProposalData::GlobalParams(v6::old_storage::GlobalParams {
+                max_name_length: global_params.max_name_length,
+                min_name_length: global_params.min_name_length,
+                max_allowed_subnets: global_params.max_allowed_subnets,
+                max_allowed_modules: global_params.max_allowed_modules,
+                max_registrations_per_block: global_params.max_registrations_per_block,
+                max_allowed_weights: global_params.max_allowed_weights,
+                floor_stake_delegation_fee: global_params.floor_stake_delegation_fee,
+                floor_validator_weight_fee: global_params.floor_validator_weight_fee,
+                floor_founder_share: global_params.floor_founder_share,
+                min_weight_stake: global_params.min_weight_stake,
+                curator: global_params.curator,
+                general_subnet_application_cost: global_params.general_subnet_application_cost,
+                subnet_immunity_period: global_params.subnet_immunity_period,
+                governance_config: old_governance_config(&global_params.governance_config),
+                kappa: global_params.kappa,
+                rho: global_params.rho,
+            }),
+        );
+        store_old_proposal(2, v6::old_storage::ProposalData::GlobalCustom);
+
+        StorageVersion::new(5).put::<GovernanceMod>();
+        MigrateToV6::<Test>::on_runtime_upgrade();
+        assert_eq!(StorageVersion::get::<GovernanceMod>(), 6);
+        assert_eq!(OpenProposalCount::<Test>::get(0), 3);
+
+        // The params added since take their current values.
+        assert_eq!(
+            Proposals::<Test>::get(0).unwrap().data,
+            ProposalData::SubnetParams {
+                subnet_id: 0,
+                params: subnet_params
+            }
+        );
+        assert_eq!(
+            Proposals::<Test>::get(1).unwrap().data,
+            ProposalData::GlobalParams(global_params)
+        );
+        assert_eq!(
+            Proposals::<Test>::get(2).unwrap().data,
+            ProposalData::GlobalCustom
+        );
+    });
+}
+
+#[test]
+fn creates_treasury_transfer_proposal_and_transfers() {
+    new_test_ext().execute_with(|| {
+        zero_min_burn();
+
+        let origin = get_origin(0);
+        GovernanceMod::add_transfer_dao_treasury_proposal(
+            origin.clone(),
+            vec![b'0'; 64],
+            to_nano(5),
+            0,
+        )
+        .expect_err("proposal should not be created when treasury does not have enough money");
+
+        add_balance(DaoTreasuryAddress::<Test>::get(), to_nano(10));
+        add_balance(0, to_nano(3));
+        register(0, 0, 0, to_nano(1));
+        config(to_nano(1), 100);
+
+        GovernanceMod::add_transfer_dao_treasury_proposal(origin, vec![b'0'; 64], to_nano(5), 0)
+            .expect("proposal should be created");
+        vote(0, 0, true);
+
+        step_block(100);
+
+        assert_eq!(get_balance(DaoTreasuryAddress::<Test>::get()), to_nano(5));
+        assert_eq!(get_balance(0), to_nano(8));
+    });
+}
+
+fn grant_payments() -> Vec<(u64, AccountId, u64)> {
+    System::events()
+        .into_iter()
+        .filter_map(|record| match record.event {
+            RuntimeEvent::GovernanceMod(pallet_governance::Event::GrantPaid(
+                id,
+                recipient,
+                amount,
+            )) => Some((id, recipient, amount)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn treasury_grant_pays_out_every_period() {
+    new_test_ext().execute_with(|| {
+        zero_min_burn();
+
+        add_balance(DaoTreasuryAddress::<Test>::get(), to_nano(100));
+        register(0, 0, 0, to_nano(10));
+        config(1, 100);
+
+        assert_err!(
+            GovernanceMod::add_treasury_grant_proposal(
+                get_origin(0),
+                vec![b'0'; 64],
+                5,
+                to_nano(2),
+                0,
+                3,
+                150
+            ),
+            Error::<Test>::InvalidTreasuryGrant
+        );
+        assert_ok!(GovernanceMod::add_treasury_grant_proposal(
+            get_origin(0),
+            vec![b'0'; 64],
+            5,
+            to_nano(2),
+            10,
+            3,
+            150
+        ));
+        vote(0, 0, true);
+
+        step_block(100);
+
+        let grant = TreasuryGrants::<Test>::get(0).expect("grant should be created");
+        assert_eq!(grant.proposal_id, 0);
+        assert_eq!(grant.next_payout_block(), 150);
+        assert!(TreasuryGrantPayouts::<Test>::contains_key(150, 0));
+        assert_eq!(GovernanceMod::treasury_grant_liabilities(), to_nano(6));
+
+        run_to_block(149);
+        assert_eq!(get_balance(5), 0);
+
+        run_to_block(150);
+        assert_eq!(get_balance(5), to_nano(2));
+        assert_eq!(GovernanceMod::treasury_grant_liabilities(), to_nano(4));
+
+        run_to_block(200);
+        assert_eq!(get_balance(5), to_nano(6));
+        assert!(TreasuryGrants::<Test>::get(0).is_none());
+        assert_eq!(GovernanceMod::treasury_grant_liabilities(), 0);
+        assert_eq!(grant_payments(), vec![(0, 5, to_nano(2)); 3]);
+    });
+}
+
+#[test]
+fn treasury_grant_with_a_past_start_block_catches_up() {
+    new_test_ext().execute_with(|| {
+        zero_min_burn();
+
+        add_balance(DaoTreasuryAddress::<Test>::get(), to_nano(100));
+        register(0, 0, 0, to_nano(10));
+        config(1, 100);
+
+        assert_ok!(GovernanceMod::add_treasury_grant_proposal(
+            get_origin(0),
+            vec![b'0'; 64],
+            5,
+            to_nano(2),
+            10,
+            3,
+            1
+        ));
+        vote(0, 0, true);
+
+        step_block(100);
+        assert!(TreasuryGrants::<Test>::get(0).is_some());
+
+        step_block(3);
+        assert_eq!(get_balance(5), to_nano(6));
+        assert!(TreasuryGrants::<Test>::get(0).is_none());
+        assert_eq!(TreasuryGrantPayouts::<Test>::iter().count(), 0);
+    });
+}
+
+#[test]
+fn treasury_grant_is_cancelled_through_a_proposal() {
+    new_test_ext().execute_with(|| {
+        zero_min_burn();
+
+        add_balance(DaoTreasuryAddress::<Test>::get(), to_nano(100));
+        register(0, 0, 0, to_nano(10));
+        config(1, 100);
+
+        assert_err!(
+            GovernanceMod::add_cancel_treasury_grant_proposal(get_origin(0), vec![b'0'; 64], 0),
+            Error::<Test>::TreasuryGrantNotFound
+        );
+        assert_ok!(GovernanceMod::add_treasury_grant_proposal(
+            get_origin(0),
+            vec![b'0'; 64],
+            5,
+            to_nano(2),
+            100,
+            10,
+            150
+        ));
+        vote(0, 0, true);
+
+        run_to_block(150);
+        assert_eq!(get_balance(5), to_nano(2));
+
+        assert_ok!(GovernanceMod::add_cancel_treasury_grant_proposal(
+            get_origin(0),
+            vec![b'0'; 64],
+            0
+        ));
+        vote(0, 1, true);
+
+        run_to_block(300);
+        assert!(matches!(
+            Proposals::<Test>::get(1).unwrap().status,
+            ProposalStatus::Accepted { .. }
+        ));
+        assert!(TreasuryGrants::<Test>::get(0).is_none());
+
+        run_to_block(500);
+        assert_eq!(get_balance(5), to_nano(4));
+    });
+}
+
+/// This test, observes the distribution of governance reward logic over time.
+#[test]
+fn rewards_wont_exceed_treasury() {
+    new_test_ext().execute_with(|| {
+        zero_min_burn();
+
+        // Fill the governance address with 1 mil so we are not limited by the max allocation
+        let amount = to_nano(1_000_000_000);
+        let key = DaoTreasuryAddress::<Test>::get();
+        add_balance(key, amount);
+
+        let governance_config: GovernanceConfiguration = GlobalGovernanceConfig::<Test>::get();
+        let n = 0;
+        let allocation = get_reward_allocation::<Test>(&governance_config, n).unwrap();
+        assert_eq!(
+            FixedI128::<U32>::saturating_from_num(allocation),
+            governance_config.max_proposal_reward_treasury_allocation
+        );
+    });
+}
+
+#[test]
+fn whitelist_executes_application_correctly() {
+    new_test_ext().execute_with(|| {
+        let key = 0;
+        let adding_key = 1;
+        assert_ok!(GovernanceMod::set_curator_council(vec![key], 1));
+
+        let proposal_cost = GeneralSubnetApplicationCost::<Test>::get();
+        let data = "test".as_bytes().to_vec();
+
+        add_balance(key, proposal_cost + 1);
+        // first submit an application
+        let balance_before = SubspaceMod::get_balance_u64(&key);
+
+        assert_ok!(GovernanceMod::add_dao_application(
+            get_origin(key),
+            adding_key,
+            data.clone(),
+        ));
+
+        let balance_after = SubspaceMod::get_balance_u64(&key);
+        assert_eq!(balance_after, balance_before - proposal_cost);
+
+        // Assert that the proposal is initially in the Pending status
+        for (_, value) in CuratorApplications::<Test>::iter() {
+            assert_eq!(value.status, ApplicationStatus::Pending);
+            assert_eq!(value.user_id, adding_key);
+            assert_eq!(value.data, data);
+        }
+
+        // add key to whitelist
+        assert_ok!(GovernanceMod::add_to_whitelist(get_origin(key), adding_key,));
+
+        let balance_after_accept = SubspaceMod::get_balance_u64(&key);
+
+        assert_eq!(balance_after_accept, balance_before);
+
+        // Assert that the proposal is now in the Accepted status
+        for (_, value) in CuratorApplications::<Test>::iter() {
+            assert_eq!(value.status, ApplicationStatus::Accepted);
+            assert_eq!(value.user_id, adding_key);
+            assert_eq!(value.data, data);
+        }
+
+        assert!(GovernanceMod::is_in_legit_whitelist(&adding_key));
+    });
+}
+
+// ----------------
+// Registration
+// ----------------
+
+#[test]
+fn user_is_removed_from_whitelist() {
+    new_test_ext().execute_with(|| {
+        let whitelist_key = 0;
+        let module_key = 1;
+        assert_ok!(GovernanceMod::set_curator_council(vec![whitelist_key], 1));
+
+        let proposal_cost = Test::get_global_governance_configuration().proposal_cost;
+        let data = "test".as_bytes().to_vec();
+
+        // apply
+        add_balance(whitelist_key, proposal_cost + 1);
+        // first submit an application
+        assert_ok!(GovernanceMod::add_dao_application(
+            get_origin(whitelist_key),
+            module_key,
+            data.clone(),
+        ));
+
+        // Add the module_key to the whitelist
+        assert_ok!(GovernanceMod::add_to_whitelist(
+            get_origin(whitelist_key),
+            module_key,
+        ));
+        assert!(GovernanceMod::is_in_legit_whitelist(&module_key));
+
+        // Remove the module_key from the whitelist
+        assert_ok!(GovernanceMod::remove_from_whitelist(
+            get_origin(whitelist_key),
+            module_key
+        ));
+        assert!(!GovernanceMod::is_in_legit_whitelist(&module_key));
+    });
+}
+
+#[test]
+fn whitelist_curator_must_be_a_valid_key() {
+    new_test_ext().execute_with(|| {
+        let whitelist_key = 0;
+        let invalid_key = 1;
+        let module_key = 2;
+        assert_ok!(GovernanceMod::set_curator_council(vec![whitelist_key], 1));
+
+        // Try to add to whitelist with an invalid curator key
+        assert_noop!(
+            GovernanceMod::add_to_whitelist(get_origin(invalid_key), module_key),
+            Error::<Test>::NotCurator
         );
         assert!(!GovernanceMod::is_in_legit_whitelist(&module_key));
     });
 }
+
+#[test]
+fn curator_council_actions_need_the_approval_threshold() {
+    new_test_ext().execute_with(|| {
+        const APPLICANT: u32 = 10;
+        GeneralSubnetApplicationCost::<Test>::set(to_nano(1));
+        add_balance(APPLICANT, to_nano(10));
+
+        assert_err!(
+            GovernanceMod::set_curator_council(vec![0, 1], 3),
+            Error::<Test>::InvalidCuratorCouncil
+        );
+        assert_ok!(GovernanceMod::set_curator_council(vec![0, 1, 2], 2));
+
+        assert_ok!(GovernanceMod::add_dao_application(
+            get_origin(APPLICANT),
+            5,
+            b"test".to_vec()
+        ));
+        assert_ok!(GovernanceMod::add_dao_application(
+            get_origin(APPLICANT),
+            6,
+            b"test".to_vec()
+        ));
+
+        assert_ok!(GovernanceMod::add_to_whitelist(get_origin(0), 5));
+        assert!(!GovernanceMod::is_in_legit_whitelist(&5));
+        assert_err!(
+            GovernanceMod::add_to_whitelist(get_origin(0), 5),
+            Error::<Test>::AlreadyApproved
+        );
+        assert_err!(
+            GovernanceMod::add_to_whitelist(get_origin(3), 5),
+            Error::<Test>::NotCurator
+        );
+        assert_ok!(GovernanceMod::add_to_whitelist(get_origin(1), 5));
+        assert!(GovernanceMod::is_in_legit_whitelist(&5));
+
+        assert_ok!(GovernanceMod::refuse_dao_application(get_origin(2), 1));
+        assert_eq!(
+            CuratorApplications::<Test>::get(1).unwrap().status,
+            ApplicationStatus::Pending
+        );
+        assert_ok!(GovernanceMod::refuse_dao_application(get_origin(0), 1));
+        assert_eq!(
+            CuratorApplications::<Test>::get(1).unwrap().status,
+            ApplicationStatus::Refused
+        );
+
+        assert_ok!(GovernanceMod::remove_from_whitelist(get_origin(2), 5));
+        assert!(GovernanceMod::is_in_legit_whitelist(&5));
+        assert_ok!(GovernanceMod::remove_from_whitelist(get_origin(1), 5));
+        assert!(!GovernanceMod::is_in_legit_whitelist(&5));
+    });
+}
+
+#[test]
+fn curator_approvals_expire() {
+    new_test_ext().execute_with(|| {
+        const APPLICANT: u32 = 10;
+        GeneralSubnetApplicationCost::<Test>::set(to_nano(1));
+        add_balance(APPLICANT, to_nano(10));
+        CuratorApprovalExpiration::<Test>::set(10);
+
+        assert_ok!(GovernanceMod::set_curator_council(vec![0, 1, 2], 2));
+        assert_ok!(GovernanceMod::add_dao_application(
+            get_origin(APPLICANT),
+            5,
+            b"test".to_vec()
+        ));
+
+        assert_ok!(GovernanceMod::add_to_whitelist(get_origin(0), 5));
+        step_block(10);
+        assert!(CuratorApprovals::<Test>::iter().next().is_none());
+
+        // the late approval starts over instead of joining the expired one
+        assert_ok!(GovernanceMod::add_to_whitelist(get_origin(1), 5));
+        assert!(!GovernanceMod::is_in_legit_whitelist(&5));
+        assert_ok!(GovernanceMod::add_to_whitelist(get_origin(0), 5));
+        assert!(GovernanceMod::is_in_legit_whitelist(&5));
+    });
+}
+
+#[test]
+fn curator_council_is_replaced_through_global_proposals() {
+    new_test_ext().execute_with(|| {
+        zero_min_burn();
+
+        register(0, 0, 0, to_nano(10));
+        config(1, 100);
+        assert_ok!(GovernanceMod::set_curator_council(vec![0], 1));
+
+        assert_err!(
+            GovernanceMod::add_curator_council_proposal(get_origin(0), vec![b'0'; 64], vec![], 1),
+            Error::<Test>::InvalidCuratorCouncil
+        );
+        assert_ok!(GovernanceMod::add_curator_council_proposal(
+            get_origin(0),
+            vec![b'0'; 64],
+            vec![7, 8],
+            2
+        ));
+        vote(0, 0, true);
+
+        step_block(100);
+
+        assert!(matches!(
+            Proposals::<Test>::get(0).unwrap().status,
+            ProposalStatus::Accepted { .. }
+        ));
+        assert!(!GovernanceMod::is_curator(&0));
+        assert!(GovernanceMod::is_curator(&7) && GovernanceMod::is_curator(&8));
+        assert_eq!(CuratorApprovalThreshold::<Test>::get(), 2);
+
+        // The council is seen as a single account from the outside, and global params can't
+        // collapse it back to a single curator.
+        assert_eq!(
+            Test::get_curator(),
+            GovernanceMod::curator_council_account()
+        );
+        assert_err!(
+            SubspaceMod::set_global_params(GlobalParams {
+                curator: 7,
+                ..SubspaceMod::global_params()
+            }),
+            pallet_subspace::Error::<Test>::InvalidCurator
+        );
+        assert_ok!(SubspaceMod::set_global_params(SubspaceMod::global_params()));
+        assert!(GovernanceMod::is_curator(&7) && GovernanceMod::is_curator(&8));
+    });
+}
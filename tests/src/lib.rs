@@ -7,6 +7,8 @@
 #[cfg(test)]
 pub mod encryption;
 #[cfg(test)]
+pub mod faucet;
+#[cfg(test)]
 pub mod governance;
 #[cfg(test)]
 pub mod mock;
@@ -18,12 +18,12 @@ use pallet_offworker::crypto::Signature;
 use pallet_subnet_emission_api::{SubnetConsensus, SubnetEmissionApi};
 use pallet_subspace::{
     params::subnet::SubnetChangeset, Address, DefaultKey, DefaultSubnetParams, Dividends, Emission,
-    Incentive, LastUpdate, MaxRegistrationsPerBlock, Name, StakeFrom, StakeTo, SubnetBurn,
+    ImmunityPeriod, Incentive, LastUpdate, MaxRegistrationsPerBlock, MinimumAllowedStake, Name, StakeFrom, StakeTo, SubnetBurn,
     SubnetParams, Tempo, TotalStake, Uids, N,
 };
 use parity_scale_codec::{Decode, Encode};
 use rand::rngs::OsRng;
-use rsa::{traits::PublicKeyParts, Pkcs1v15Encrypt};
+use rsa::traits::PublicKeyParts;
 use scale_info::{prelude::collections::BTreeSet, TypeInfo};
 
 use sp_core::{sr25519, ConstU16, ConstU64, H256};
@@ -34,10 +34,7 @@ use sp_runtime::{
     },
     BuildStorage, DispatchError, DispatchResult, KeyTypeId,
 };
-use std::{
-    cell::RefCell,
-    io::{Cursor, Read},
-};
+use std::cell::RefCell;
 
 frame_support::construct_runtime!(
     pub enum Test {
@@ -47,6 +44,7 @@ frame_support::construct_runtime!(
         SubspaceMod: pallet_subspace,
         GovernanceMod: pallet_governance,
         OffWorkerMod: pallet_offworker,
+        FaucetMod: pallet_faucet,
     }
 );
 
@@ -75,6 +73,7 @@ parameter_types! {
     pub const ExistentialDeposit: Balance = 1;
     pub const MaxLocks: u32 = 50;
     pub const MaxReserves: u32 = 50;
+    pub static MaxEpochsPerBlock: u16 = u16::MAX;
 }
 
 pub const PALLET_ID: PalletId = PalletId(*b"py/subsp");
@@ -190,11 +189,9 @@ impl GovernanceApi<<Test as frame_system::Config>::AccountId> for Test {
     }
 
     fn get_curator() -> <Test as frame_system::Config>::AccountId {
-        AccountId::default()
+        pallet_governance::Pallet::<Test>::curator_council_account()
     }
 
-    fn set_curator(_key: &<Test as frame_system::Config>::AccountId) {}
-
     fn set_general_subnet_application_cost(_amount: u64) {}
 
     fn clear_subnet_includes(netuid: u16) {
@@ -242,6 +239,20 @@ impl SubnetEmissionApi<<Test as frame_system::Config>::AccountId> for Test {
         pallet_subnet_emission::SubnetConsensusType::<Test>::set(netuid, subnet_consensus)
     }
 
+    fn validate_consensus_change(
+        netuid: u16,
+        subnet_consensus: pallet_subnet_emission_api::SubnetConsensus,
+    ) -> DispatchResult {
+        pallet_subnet_emission::Pallet::<Test>::validate_consensus_change(netuid, subnet_consensus)
+    }
+
+    fn schedule_consensus_change(
+        netuid: u16,
+        subnet_consensus: pallet_subnet_emission_api::SubnetConsensus,
+    ) -> DispatchResult {
+        pallet_subnet_emission::Pallet::<Test>::schedule_consensus_change(netuid, subnet_consensus)
+    }
+
     fn get_weights(netuid: u16, uid: u16) -> Option<Vec<(u16, u16)>> {
         pallet_subnet_emission::Weights::<Test>::get(netuid, uid)
     }
@@ -276,6 +287,7 @@ impl SubnetEmissionApi<<Test as frame_system::Config>::AccountId> for Test {
             &module_key,
             &replace_key,
         )?;
+        SubnetEmissionMod::remove_fixed_allocation(netuid, module_key);
         Ok(())
     }
 }
@@ -293,6 +305,7 @@ impl pallet_subnet_emission::Config for Test {
     type MissedPingsForInactivity = ConstU8<{ u8::MAX }>;
     type PingInterval = ConstU64<50>;
     type EncryptionPeriodBuffer = ConstU64<100>;
+    type MaxEpochsPerBlock = MaxEpochsPerBlock;
     type WeightInfo = ();
 }
 
@@ -303,6 +316,11 @@ impl pallet_governance::Config for Test {
     type WeightInfo = ();
 }
 
+impl pallet_faucet::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
+    type Currency = Balances;
+}
+
 impl pallet_balances::Config for Test {
     type RuntimeEvent = RuntimeEvent;
     type AccountStore = System;
@@ -525,15 +543,12 @@ pub fn increase_stake(key: AccountId, stake: u64) {
 
 // Sets all key's stake to 0 and increases delegated stake to desired amount
 pub fn make_keys_all_stake_be(account: AccountId, stake: u64) {
-    let _ = StakeFrom::<Test>::clear_prefix(&account, u32::MAX, None);
-    let _ = StakeTo::<Test>::clear_prefix(&account, u32::MAX, None);
-
-    let keys_total_stake =
-        SubspaceMod::get_delegated_stake(&account) + SubspaceMod::get_owned_stake(&account);
-
-    TotalStake::<Test>::mutate(|total_stake| {
-        *total_stake = total_stake.saturating_sub(keys_total_stake).saturating_add(stake);
-    });
+    for (staker, amount) in StakeFrom::<Test>::iter_prefix(account).collect::<Vec<_>>() {
+        SubspaceMod::decrease_stake(&staker, &account, amount);
+    }
+    for (staked, amount) in StakeTo::<Test>::iter_prefix(account).collect::<Vec<_>>() {
+        SubspaceMod::decrease_stake(&account, &staked, amount);
+    }
 
     increase_stake(account, stake);
 }
@@ -573,11 +588,20 @@ pub fn get_total_subnet_balance(netuid: u16) -> u64 {
     keys.iter().map(SubspaceMod::get_balance_u64).sum()
 }
 
+/// Checks the storage invariants of the pallets, ran at the end of every block.
+fn try_state(block: u64) {
+    <SubspaceMod as Hooks<u64>>::try_state(block).expect("pallet_subspace invariants broken");
+    <SubnetEmissionMod as Hooks<u64>>::try_state(block)
+        .expect("pallet_subnet_emission invariants broken");
+    <GovernanceMod as Hooks<u64>>::try_state(block).expect("pallet_governance invariants broken");
+}
+
 #[allow(dead_code)]
 pub(crate) fn step_block(n: u16) {
     for _ in 0..n {
         SubspaceMod::on_finalize(System::block_number());
         System::on_finalize(System::block_number());
+        try_state(System::block_number());
         System::set_block_number(System::block_number() + 1);
         System::on_initialize(System::block_number());
         SubspaceMod::on_initialize(System::block_number());
@@ -591,6 +615,7 @@ pub(crate) fn run_to_block(n: u64) {
     while System::block_number() < n {
         SubspaceMod::on_finalize(System::block_number());
         System::on_finalize(System::block_number());
+        try_state(System::block_number());
         System::set_block_number(System::block_number() + 1);
         System::on_initialize(System::block_number());
         SubspaceMod::on_initialize(System::block_number());
@@ -841,6 +866,17 @@ pub fn register_root_validator(key: AccountId, stake: u64) -> Result<u16, Dispat
     pallet_subspace::Uids::<Test>::get(netuid, key).ok_or("uid is missing".into())
 }
 
+/// Registers `founder` on subnet `netuid` with no burn, no minimum stake and no immunity
+/// period, so the tests control who gets registered and pruned.
+#[allow(dead_code)]
+pub fn setup_subnet(netuid: u16, founder: AccountId, stake: u64) {
+    zero_min_burn();
+    MinimumAllowedStake::<Test>::set(0);
+
+    assert_ok!(register_module(netuid, founder, stake, false));
+    ImmunityPeriod::<Test>::insert(netuid, 0);
+}
+
 pub fn get_total_issuance() -> u64 {
     let total_staked_balance = TotalStake::<Test>::get();
     let total_free_balance = pallet_balances::Pallet::<Test>::total_issuance();
@@ -956,42 +992,7 @@ impl ow_extensions::OffworkerExtension for Decrypter {
             return None;
         };
 
-        let Some(vec) = encrypted
-            .chunks(dbg!(key.size()))
-            .map(|chunk| match key.decrypt(Pkcs1v15Encrypt, &chunk) {
-                Ok(decrypted) => Some(decrypted),
-                Err(_) => None,
-            })
-            .collect::<Option<Vec<Vec<u8>>>>()
-        else {
-            return None;
-        };
-
-        let decrypted = vec.into_iter().flat_map(|vec| vec).collect::<Vec<_>>();
-
-        let mut res = Vec::new();
-
-        let mut cursor = Cursor::new(&decrypted);
-
-        let Some(length) = read_u32(&mut cursor) else {
-            return None;
-        };
-        for _ in 0..length {
-            let Some(uid) = read_u16(&mut cursor) else {
-                return None;
-            };
-
-            let Some(weight) = read_u16(&mut cursor) else {
-                return None;
-            };
-
-            res.push((uid, weight));
-        }
-
-        let mut key = Vec::new();
-        cursor.read_to_end(&mut key).ok()?;
-
-        Some((res, key))
+        ow_extensions::envelope::decrypt_weights(key, &encrypted).ok()
     }
 
     fn is_decryption_node(&self) -> bool {
@@ -1006,26 +1007,15 @@ impl ow_extensions::OffworkerExtension for Decrypter {
         let public = rsa::RsaPublicKey::from(key);
         Some((public.n().to_bytes_be(), public.e().to_bytes_be()))
     }
-}
 
-fn read_u32(cursor: &mut Cursor<&Vec<u8>>) -> Option<u32> {
-    let mut buf: [u8; 4] = [0u8; 4];
-    match cursor.read_exact(&mut buf[..]) {
-        Ok(()) => Some(u32::from_be_bytes(buf)),
-        Err(err) => {
-            dbg!(&err);
-            None
-        }
-    }
-}
+    fn decrypt_share(&self, encrypted: Vec<u8>, index: u8) -> Option<Vec<u8>> {
+        let Some(key) = &self.key else {
+            return None;
+        };
 
-fn read_u16(cursor: &mut Cursor<&Vec<u8>>) -> Option<u16> {
-    let mut buf = [0u8; 2];
-    match cursor.read_exact(&mut buf[..]) {
-        Ok(()) => Some(u16::from_be_bytes(buf)),
-        Err(err) => {
-            dbg!(&err);
-            None
-        }
+        let envelope = pallet_subnet_emission::threshold::parse_envelope(&encrypted)?;
+        let share = envelope.shares.get(index as usize)?;
+
+        key.decrypt(rsa::Oaep::new::<sha2::Sha256>(), share).ok()
     }
 }
@@ -1,14 +1,21 @@
-use ow_extensions::OffworkerExtension;
-use rand::rngs::OsRng;
+use aes_gcm::{
+    aead::{Aead, AeadCore},
+    Aes256Gcm, KeyInit,
+};
+use ow_extensions::{
+    envelope::{self, HYBRID_ENVELOPE_VERSION},
+    OffworkerExtension,
+};
+use pallet_subnet_emission::threshold::{self, THRESHOLD_ENVELOPE_VERSION};
+use rand::{rngs::OsRng, RngCore};
 use rsa::{
     pkcs1::{DecodeRsaPrivateKey, EncodeRsaPrivateKey},
     traits::PublicKeyParts,
-    BigUint, Pkcs1v15Encrypt, RsaPrivateKey, RsaPublicKey,
+    BigUint, Oaep, Pkcs1v15Encrypt, RsaPrivateKey, RsaPublicKey,
 };
 use sp_core::{sr25519, Pair};
 use sp_keystore::{testing::MemoryKeystore, Keystore};
 use sp_runtime::KeyTypeId;
-use std::io::{Cursor, Read};
 
 pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"wcs!");
 
@@ -57,42 +64,7 @@ impl ow_extensions::OffworkerExtension for MockOffworkerExt {
             return None;
         };
 
-        let Some(vec) = encrypted
-            .chunks(key.size())
-            .map(|chunk| match key.decrypt(Pkcs1v15Encrypt, &chunk) {
-                Ok(decrypted) => Some(decrypted),
-                Err(_) => None,
-            })
-            .collect::<Option<Vec<Vec<u8>>>>()
-        else {
-            return None;
-        };
-
-        let decrypted = vec.into_iter().flatten().collect::<Vec<_>>();
-
-        let mut weights = Vec::new();
-
-        let mut cursor = Cursor::new(&decrypted);
-
-        let Some(length) = read_u32(&mut cursor) else {
-            return None;
-        };
-        for _ in 0..length {
-            let Some(uid) = read_u16(&mut cursor) else {
-                return None;
-            };
-
-            let Some(weight) = read_u16(&mut cursor) else {
-                return None;
-            };
-
-            weights.push((uid, weight));
-        }
-
-        let mut key = Vec::new();
-        cursor.read_to_end(&mut key).ok()?;
-
-        Some((weights, key))
+        envelope::decrypt_weights(key, &encrypted).ok()
     }
 
     fn is_decryption_node(&self) -> bool {
@@ -107,21 +79,16 @@ impl ow_extensions::OffworkerExtension for MockOffworkerExt {
         let public = rsa::RsaPublicKey::from(key);
         Some((public.n().to_bytes_be(), public.e().to_bytes_be()))
     }
-}
 
-fn read_u32(cursor: &mut Cursor<&Vec<u8>>) -> Option<u32> {
-    let mut buf: [u8; 4] = [0u8; 4];
-    match cursor.read_exact(&mut buf[..]) {
-        Ok(()) => Some(u32::from_be_bytes(buf)),
-        Err(_) => None,
-    }
-}
+    fn decrypt_share(&self, encrypted: Vec<u8>, index: u8) -> Option<Vec<u8>> {
+        let Some(key) = &self.key else {
+            return None;
+        };
+
+        let envelope = threshold::parse_envelope(&encrypted)?;
+        let share = envelope.shares.get(index as usize)?;
 
-fn read_u16(cursor: &mut Cursor<&Vec<u8>>) -> Option<u16> {
-    let mut buf = [0u8; 2];
-    match cursor.read_exact(&mut buf[..]) {
-        Ok(()) => Some(u16::from_be_bytes(buf)),
-        Err(_) => None,
+        key.decrypt(Oaep::new::<sha2::Sha256>(), share).ok()
     }
 }
 
@@ -141,15 +108,8 @@ fn weights_to_blob(weights: &[(u16, u16)]) -> Vec<u8> {
     encoded
 }
 
-// the key needs to be retrieved from the blockchain
-pub fn encrypt(key: (Vec<u8>, Vec<u8>), data: Vec<(u16, u16)>, validator_key: Vec<u8>) -> Vec<u8> {
-    let rsa_key = RsaPublicKey::new(
-        BigUint::from_bytes_be(&key.0),
-        BigUint::from_bytes_be(&key.1),
-    )
-    .expect("Failed to create RSA key");
-
-    let encoded = [
+fn encode_payload(data: Vec<(u16, u16)>, validator_key: Vec<u8>) -> Vec<u8> {
+    [
         (data.len() as u32).to_be_bytes().to_vec(),
         data.into_iter()
             .flat_map(|(uid, weight)| {
@@ -158,7 +118,21 @@ pub fn encrypt(key: (Vec<u8>, Vec<u8>), data: Vec<(u16, u16)>, validator_key: Ve
             .collect(),
         validator_key,
     ]
-    .concat();
+    .concat()
+}
+
+fn rsa_public_key(key: (Vec<u8>, Vec<u8>)) -> RsaPublicKey {
+    RsaPublicKey::new(
+        BigUint::from_bytes_be(&key.0),
+        BigUint::from_bytes_be(&key.1),
+    )
+    .expect("Failed to create RSA key")
+}
+
+// the key needs to be retrieved from the blockchain
+pub fn encrypt(key: (Vec<u8>, Vec<u8>), data: Vec<(u16, u16)>, validator_key: Vec<u8>) -> Vec<u8> {
+    let rsa_key = rsa_public_key(key);
+    let encoded = encode_payload(data, validator_key);
 
     let max_chunk_size = rsa_key.size() - 11; // 11 bytes for PKCS1v15 padding
 
@@ -170,6 +144,95 @@ pub fn encrypt(key: (Vec<u8>, Vec<u8>), data: Vec<(u16, u16)>, validator_key: Ve
         .collect()
 }
 
+/// Encrypts the weights in the versioned hybrid envelope: a random AES-256 key wrapped with
+/// RSA-OAEP, followed by the AES-GCM encrypted payload.
+pub fn encrypt_hybrid(
+    key: (Vec<u8>, Vec<u8>),
+    data: Vec<(u16, u16)>,
+    validator_key: Vec<u8>,
+) -> Vec<u8> {
+    let rsa_key = rsa_public_key(key);
+    let encoded = encode_payload(data, validator_key);
+
+    let aes_key = Aes256Gcm::generate_key(&mut OsRng);
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = Aes256Gcm::new(&aes_key)
+        .encrypt(&nonce, &encoded[..])
+        .expect("Encryption failed");
+
+    let wrapped_key = rsa_key
+        .encrypt(&mut OsRng, Oaep::new::<sha2::Sha256>(), &aes_key)
+        .expect("Encryption failed");
+
+    [
+        vec![HYBRID_ENVELOPE_VERSION],
+        wrapped_key,
+        nonce.to_vec(),
+        ciphertext,
+    ]
+    .concat()
+}
+
+/// Splits `secret` into `share_count` Shamir shares over GF(256), any `threshold` of which
+/// recover it. Shares are encoded as `x || y`.
+pub fn split_secret(secret: &[u8], threshold: u8, share_count: u8) -> Vec<Vec<u8>> {
+    let coefficients: Vec<Vec<u8>> = secret
+        .iter()
+        .map(|byte| {
+            let mut coefficients = vec![0u8; threshold as usize];
+            OsRng.fill_bytes(&mut coefficients);
+            coefficients[0] = *byte;
+            coefficients
+        })
+        .collect();
+
+    (1..=share_count)
+        .map(|x| {
+            let y = coefficients.iter().map(|coefficients| {
+                coefficients.iter().rev().fold(0u8, |acc, coefficient| {
+                    threshold::gf256_mul(acc, x) ^ coefficient
+                })
+            });
+
+            std::iter::once(x).chain(y).collect()
+        })
+        .collect()
+}
+
+/// Encrypts the weights to a decryption committee: the AES-256 key is split into one Shamir
+/// share per member, each wrapped with the member's RSA key and committed to with its hash.
+pub fn encrypt_threshold(
+    keys: Vec<(Vec<u8>, Vec<u8>)>,
+    threshold: u8,
+    data: Vec<(u16, u16)>,
+    validator_key: Vec<u8>,
+) -> Vec<u8> {
+    let encoded = encode_payload(data, validator_key);
+
+    let aes_key = Aes256Gcm::generate_key(&mut OsRng);
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = Aes256Gcm::new(&aes_key)
+        .encrypt(&nonce, &encoded[..])
+        .expect("Encryption failed");
+
+    let shares = split_secret(&aes_key, threshold, keys.len() as u8);
+
+    let mut envelope = vec![THRESHOLD_ENVELOPE_VERSION, keys.len() as u8];
+    for (key, share) in keys.into_iter().zip(&shares) {
+        let wrapped_share = rsa_public_key(key)
+            .encrypt(&mut OsRng, Oaep::new::<sha2::Sha256>(), share)
+            .expect("Encryption failed");
+
+        envelope.extend((wrapped_share.len() as u16).to_be_bytes());
+        envelope.extend(wrapped_share);
+    }
+    for share in &shares {
+        envelope.extend(sp_io::hashing::blake2_256(share));
+    }
+
+    [envelope, nonce.to_vec(), ciphertext].concat()
+}
+
 #[test]
 fn encrypt_and_decrypt() {
     // use rsa::traits::PrivateKeyParts;
@@ -246,3 +309,208 @@ fvRuW5JF+WZtGddyU4751JNNNhmwbwGmsmphy7EOHHaC
     assert_eq!(decrypted_weights, weights.clone());
     assert_eq!(decrypted_key, validator_key.clone());
 }
+
+#[test]
+fn hybrid_encrypt_and_decrypt() {
+    let weights = vec![(1, 2), (3, 4), (65535, 0)];
+    let validator_key = vec![11, 22, 33, 44];
+
+    let rsa_key = RsaPrivateKey::new(&mut OsRng, 1024).unwrap();
+    let mock_offworker_ext = MockOffworkerExt {
+        key: Some(rsa_key.clone()),
+    };
+
+    let pub_key = rsa_key.to_public_key();
+    let pub_key_tp = (pub_key.n().to_bytes_be(), pub_key.e().to_bytes_be());
+
+    let encrypted = encrypt_hybrid(pub_key_tp, weights.clone(), validator_key.clone());
+    assert_eq!(encrypted[0], HYBRID_ENVELOPE_VERSION);
+
+    let (decrypted_weights, decrypted_key) = mock_offworker_ext.decrypt_weight(encrypted).unwrap();
+
+    assert_eq!(decrypted_weights, weights);
+    assert_eq!(decrypted_key, validator_key);
+}
+
+#[test]
+fn hybrid_envelope_is_smaller_for_many_weights() {
+    let weights = (0..512u16).map(|uid| (uid, uid.wrapping_mul(7))).collect::<Vec<_>>();
+    let validator_key = vec![7; 32];
+
+    let rsa_key = RsaPrivateKey::new(&mut OsRng, 1024).unwrap();
+    let mock_offworker_ext = MockOffworkerExt {
+        key: Some(rsa_key.clone()),
+    };
+
+    let pub_key = rsa_key.to_public_key();
+    let pub_key_tp = (pub_key.n().to_bytes_be(), pub_key.e().to_bytes_be());
+
+    let legacy = encrypt(pub_key_tp.clone(), weights.clone(), validator_key.clone());
+    let hybrid = encrypt_hybrid(pub_key_tp, weights.clone(), validator_key.clone());
+
+    assert!(hybrid.len() < legacy.len());
+
+    // both formats are accepted by the same decrypter
+    for encrypted in [legacy, hybrid] {
+        let (decrypted_weights, decrypted_key) =
+            mock_offworker_ext.decrypt_weight(encrypted).unwrap();
+
+        assert_eq!(decrypted_weights, weights);
+        assert_eq!(decrypted_key, validator_key);
+    }
+}
+
+#[test]
+fn hybrid_empty_weights_round_trip() {
+    let rsa_key = RsaPrivateKey::new(&mut OsRng, 1024).unwrap();
+    let mock_offworker_ext = MockOffworkerExt {
+        key: Some(rsa_key.clone()),
+    };
+
+    let pub_key = rsa_key.to_public_key();
+    let pub_key_tp = (pub_key.n().to_bytes_be(), pub_key.e().to_bytes_be());
+
+    let encrypted = encrypt_hybrid(pub_key_tp, vec![], vec![1, 2, 3]);
+    let (decrypted_weights, decrypted_key) = mock_offworker_ext.decrypt_weight(encrypted).unwrap();
+
+    assert!(decrypted_weights.is_empty());
+    assert_eq!(decrypted_key, vec![1, 2, 3]);
+}
+
+#[test]
+fn hybrid_tampered_envelope_is_rejected() {
+    let rsa_key = RsaPrivateKey::new(&mut OsRng, 1024).unwrap();
+    let mock_offworker_ext = MockOffworkerExt {
+        key: Some(rsa_key.clone()),
+    };
+
+    let pub_key = rsa_key.to_public_key();
+    let pub_key_tp = (pub_key.n().to_bytes_be(), pub_key.e().to_bytes_be());
+
+    let mut encrypted = encrypt_hybrid(pub_key_tp, vec![(1, 2)], vec![1, 2, 3]);
+    let last = encrypted.len() - 1;
+    encrypted[last] ^= 0xff;
+
+    assert!(mock_offworker_ext.decrypt_weight(encrypted).is_none());
+}
+
+#[test]
+fn hybrid_envelope_for_other_key_is_rejected() {
+    let rsa_key = RsaPrivateKey::new(&mut OsRng, 1024).unwrap();
+    let other_key = RsaPrivateKey::new(&mut OsRng, 1024).unwrap();
+    let mock_offworker_ext = MockOffworkerExt { key: Some(rsa_key) };
+
+    let pub_key = other_key.to_public_key();
+    let pub_key_tp = (pub_key.n().to_bytes_be(), pub_key.e().to_bytes_be());
+
+    let encrypted = encrypt_hybrid(pub_key_tp, vec![(1, 2)], vec![1, 2, 3]);
+
+    assert!(mock_offworker_ext.decrypt_weight(encrypted).is_none());
+}
+
+#[test]
+fn unprefixed_legacy_ciphertexts_still_decrypt() {
+    let rsa_key = RsaPrivateKey::new(&mut OsRng, 1024).unwrap();
+
+    let pub_key = rsa_key.to_public_key();
+    let pub_key_tp = (pub_key.n().to_bytes_be(), pub_key.e().to_bytes_be());
+
+    // RSA blocks start with any byte, including the hybrid version
+    let legacy = (0..10_000)
+        .map(|_| encrypt(pub_key_tp.clone(), vec![(1, 2)], vec![1, 2, 3]))
+        .find(|encrypted| encrypted[0] == HYBRID_ENVELOPE_VERSION)
+        .expect("no legacy ciphertext started with the hybrid version");
+    assert_eq!(
+        envelope::decrypt_weights(&rsa_key, &legacy),
+        Ok((vec![(1, 2)], vec![1, 2, 3]))
+    );
+
+    for version in [0, THRESHOLD_ENVELOPE_VERSION, u8::MAX] {
+        let mut hybrid = encrypt_hybrid(pub_key_tp.clone(), vec![(1, 2)], vec![1, 2, 3]);
+        hybrid[0] = version;
+
+        assert!(envelope::decrypt_envelope(&rsa_key, &hybrid).is_err());
+    }
+
+    assert_eq!(
+        envelope::decrypt_envelope(&rsa_key, &[]),
+        Err("envelope is empty".to_string())
+    );
+}
+
+fn committee(size: usize) -> Vec<MockOffworkerExt> {
+    (0..size)
+        .map(|_| MockOffworkerExt {
+            key: Some(RsaPrivateKey::new(&mut OsRng, 1024).unwrap()),
+        })
+        .collect()
+}
+
+fn committee_keys(committee: &[MockOffworkerExt]) -> Vec<(Vec<u8>, Vec<u8>)> {
+    committee.iter().map(|member| member.get_encryption_key().unwrap()).collect()
+}
+
+#[test]
+fn shamir_shares_recover_secret() {
+    let secret = (0..32).collect::<Vec<u8>>();
+    let shares = split_secret(&secret, 3, 5);
+
+    assert_eq!(threshold::combine_shares(&shares[..3]).unwrap(), secret);
+    assert_eq!(threshold::combine_shares(&shares[2..]).unwrap(), secret);
+    assert_eq!(
+        threshold::combine_shares(&[shares[0].clone(), shares[4].clone(), shares[2].clone()])
+            .unwrap(),
+        secret
+    );
+    assert_ne!(threshold::combine_shares(&shares[..2]).unwrap(), secret);
+
+    // duplicated coordinates are rejected
+    assert!(threshold::combine_shares(&[shares[0].clone(), shares[0].clone()]).is_none());
+}
+
+#[test]
+fn threshold_encrypt_and_decrypt() {
+    let committee = committee(3);
+    let weights = vec![(1, 2), (3, 4), (5, 6)];
+
+    let encrypted = encrypt_threshold(committee_keys(&committee), 2, weights.clone(), vec![7, 8]);
+
+    let shares = [0u8, 2]
+        .iter()
+        .map(|index| committee[*index as usize].decrypt_share(encrypted.clone(), *index).unwrap())
+        .collect::<Vec<_>>();
+
+    let aes_key = threshold::combine_shares(&shares).unwrap();
+    let envelope = threshold::parse_envelope(&encrypted).unwrap();
+    let (decrypted_weights, decrypted_key) =
+        threshold::decrypt_envelope(&aes_key, &envelope).unwrap();
+
+    assert_eq!(decrypted_weights, weights);
+    assert_eq!(decrypted_key, vec![7, 8]);
+}
+
+#[test]
+fn threshold_members_only_decrypt_their_share() {
+    let committee = committee(3);
+    let encrypted = encrypt_threshold(committee_keys(&committee), 2, vec![(1, 2)], vec![]);
+
+    assert!(committee[0].decrypt_share(encrypted.clone(), 1).is_none());
+    assert!(committee[1].decrypt_share(encrypted.clone(), 3).is_none());
+    // a single member can't open the envelope as a regular hybrid envelope either
+    assert!(committee[0].decrypt_weight(encrypted).is_none());
+}
+
+#[test]
+fn threshold_below_threshold_fails() {
+    let committee = committee(3);
+    let encrypted = encrypt_threshold(committee_keys(&committee), 3, vec![(1, 2)], vec![1]);
+
+    let shares = (0..2u8)
+        .map(|index| committee[index as usize].decrypt_share(encrypted.clone(), index).unwrap())
+        .collect::<Vec<_>>();
+
+    let aes_key = threshold::combine_shares(&shares).unwrap();
+    let envelope = threshold::parse_envelope(&encrypted).unwrap();
+
+    assert!(threshold::decrypt_envelope(&aes_key, &envelope).is_none());
+}
@@ -6,7 +6,7 @@ use pallet_subnet_emission::{
 use pallet_subnet_emission_api::{SubnetConsensus, SubnetEmissionApi};
 use pallet_subspace::{
     Error, Kappa, Keys, MaxAllowedUids, MaxAllowedValidators, MaxRegistrationsPerBlock,
-    MinimumAllowedStake, ModuleBurnConfig, Rho, StakeFrom, Tempo,
+    MinimumAllowedStake, ModuleBurnConfig, Rho, Tempo,
 };
 
 pub use crate::mock::*;
@@ -281,7 +281,7 @@ fn test_rootnet_registration_requirements() {
         let more_stake = initial_stake + to_nano(last_stake as u64);
 
         // Manually increase the stake from on the non-registered key
-        StakeFrom::<Test>::insert(6, 6, more_stake);
+        SubspaceMod::increase_stake(&6, &6, more_stake);
         assert_ok!(register_root_validator(6, 0));
 
         // Make sure the first key to register has no stake, as it should be deregistered
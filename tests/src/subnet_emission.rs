@@ -1,11 +1,13 @@
-use crate::mock::*;
+use crate::{mock::*, offworker::encryption::encrypt_threshold};
 use pallet_subnet_emission::{
     subnet_consensus::util::{
         consensus::EmissionMap,
         params::{AccountKey, ModuleKey},
     },
-    types::SubnetDecryptionInfo,
-    BannedDecryptionNodes, Weights,
+    threshold,
+    types::{SubnetDecryptionInfo, ThresholdConfig},
+    BannedDecryptionNodes, DecryptionShares, SubnetDecryptionCommittee, ThresholdDecryptionConfig,
+    Weights,
 };
 
 use pallet_subspace::{Active, Consensus, Founder, PruningScores, Rank, Trust, ValidatorTrust};
@@ -15,17 +17,22 @@ use rsa::{traits::PublicKeyParts, RsaPrivateKey};
 use sp_runtime::Percent;
 use std::collections::BTreeMap;
 
-use frame_support::{assert_ok, traits::Currency};
+use frame_support::{
+    assert_err, assert_ok,
+    traits::{Currency, Hooks},
+    weights::Weight,
+};
 use log::info;
 use pallet_governance::DaoTreasuryAddress;
 use pallet_subnet_emission::{
     subnet_consensus::{util::params::ConsensusParams, yuma::YumaEpoch},
-    PendingEmission, SubnetConsensusType, SubnetEmission, UnitEmission,
+    DeferredEpochs, FixedAllocations, PendingConsensusChange, PendingEmission, SubnetConsensusType,
+    SubnetEmission, UnitEmission,
 };
 
 use pallet_subnet_emission_api::SubnetConsensus;
 use pallet_subspace::{
-    Dividends, Emission, FloorFounderShare, FounderShare, ImmunityPeriod, Incentive,
+    Bonds, Dividends, Emission, FloorFounderShare, FounderShare, ImmunityPeriod, Incentive,
     MaxAllowedModules, MaxAllowedSubnets, MaxAllowedValidators, MaxRegistrationsPerBlock,
     MaxWeightAge, MinValidatorStake, MinimumAllowedStake, Pallet as SubspaceMod, RegistrationBlock,
     SubnetImmunityPeriod, SubnetNames, Tempo, ValidatorPermits, N,
@@ -1603,6 +1610,8 @@ fn weight_setting_delegation() {
 
         PendingEmission::<Test>::set(NETUID, to_nano(1000));
 
+        // The fee increase takes effect on the next block
+        pallet_subspace::FeeAnnouncementPeriod::<Test>::set(0);
         pallet_subspace::Pallet::<Test>::update_module(
             get_origin(parent_key),
             NETUID,
@@ -1637,6 +1646,661 @@ fn weight_setting_delegation() {
     });
 }
 
+#[test]
+fn threshold_decryption_config_is_validated() {
+    new_test_ext().execute_with(|| {
+        zero_min_burn();
+        let netuid = 0;
+        let founder = 0;
+        assert_ok!(register_module(netuid, founder, to_nano(10), false));
+
+        let config = ThresholdConfig {
+            committee_size: 3,
+            threshold: 2,
+        };
+
+        assert_err!(
+            SubnetEmissionMod::set_threshold_decryption(get_origin(1), netuid, Some(config)),
+            pallet_subspace::Error::<Test>::NotFounder
+        );
+
+        for invalid in [
+            ThresholdConfig {
+                committee_size: 3,
+                threshold: 0,
+            },
+            ThresholdConfig {
+                committee_size: 2,
+                threshold: 3,
+            },
+            ThresholdConfig {
+                committee_size: u8::MAX,
+                threshold: 2,
+            },
+        ] {
+            assert_err!(
+                SubnetEmissionMod::set_threshold_decryption(
+                    get_origin(founder),
+                    netuid,
+                    Some(invalid)
+                ),
+                pallet_subspace::Error::<Test>::InvalidThresholdDecryptionConfig
+            );
+        }
+
+        assert_ok!(SubnetEmissionMod::set_threshold_decryption(
+            get_origin(founder),
+            netuid,
+            Some(config)
+        ));
+        assert_eq!(ThresholdDecryptionConfig::<Test>::get(netuid), Some(config));
+
+        assert_ok!(SubnetEmissionMod::set_threshold_decryption(
+            get_origin(founder),
+            netuid,
+            None
+        ));
+        assert_eq!(ThresholdDecryptionConfig::<Test>::get(netuid), None);
+    });
+}
+
+/// Registers a validator and a miner on a Yuma subnet using threshold decryption, and assigns it a
+/// committee of three nodes. Returns the RSA keys of the members, in committee order.
+fn setup_threshold_committee(netuid: u16) -> Vec<RsaPrivateKey> {
+    zero_min_burn();
+    assert_ok!(register_module(netuid, 0, to_nano(10), false));
+    assert_ok!(register_module(netuid, 1, to_nano(10), false));
+    SubnetConsensusType::<Test>::insert(netuid, SubnetConsensus::Yuma);
+    pallet_subspace::MinAllowedWeights::<Test>::insert(netuid, 1);
+    pallet_subspace::UseWeightsEncryption::<Test>::set(netuid, true);
+
+    assert_ok!(SubnetEmissionMod::set_threshold_decryption(
+        get_origin(0),
+        netuid,
+        Some(ThresholdConfig {
+            committee_size: 3,
+            threshold: 2,
+        })
+    ));
+
+    let block = SubspaceMod::get_current_block_number();
+    let keys = (0..3)
+        .map(|_| RsaPrivateKey::new(&mut OsRng, 1024).unwrap())
+        .collect::<Vec<_>>();
+    let nodes = keys
+        .iter()
+        .enumerate()
+        .map(|(i, key)| {
+            let key = key.to_public_key();
+            SubnetDecryptionInfo {
+                node_id: 1001 + i as u32,
+                node_public_key: (key.n().to_bytes_be(), key.e().to_bytes_be()),
+                validity_block: None,
+                last_keep_alive: block,
+                rotating_from: None,
+            }
+        })
+        .collect::<Vec<_>>();
+    pallet_subnet_emission::DecryptionNodes::<Test>::set(nodes.clone());
+
+    SubnetEmissionMod::distribute_subnets_to_nodes(block);
+
+    let committee = SubnetDecryptionCommittee::<Test>::get(netuid).unwrap();
+    assert_eq!(committee.threshold, 2);
+    assert_eq!(committee.members.len(), 3);
+    assert!(pallet_subnet_emission::SubnetDecryptionData::<Test>::get(netuid).is_none());
+
+    committee
+        .members
+        .iter()
+        .map(|(member, _)| {
+            let index = nodes.iter().position(|node| node.node_id == *member).unwrap();
+            keys[index].clone()
+        })
+        .collect()
+}
+
+/// Stores an epoch at `block` where the validator encrypted `weights` to the committee.
+fn insert_threshold_epoch(netuid: u16, block: u64, weights: Vec<(u16, u16)>) -> Vec<u8> {
+    let committee = SubnetDecryptionCommittee::<Test>::get(netuid).unwrap();
+    let validator = 0;
+    let encrypted = encrypt_threshold(
+        committee.members.iter().map(|(_, key)| key.clone()).collect(),
+        committee.threshold,
+        weights.clone(),
+        validator.encode(),
+    );
+
+    let mut params = ConsensusParams::<Test>::new(netuid, to_nano(1)).unwrap();
+    let module = params.modules.get_mut(&ModuleKey(validator)).unwrap();
+    module.weight_encrypted = encrypted.clone();
+    module.weight_hash = crate::offworker::encryption::hash(weights);
+    pallet_subnet_emission::ConsensusParameters::<Test>::insert(netuid, block, params);
+
+    encrypted
+}
+
+/// Decrypts the share of the committee member at `index`, as its offworker would submit it.
+fn threshold_share_of(
+    member_keys: &[RsaPrivateKey],
+    encrypted: &[u8],
+    index: usize,
+    block: u64,
+) -> Vec<(u64, Vec<(u16, Vec<u8>)>)> {
+    let envelope = threshold::parse_envelope(encrypted).unwrap();
+    let share = member_keys[index]
+        .decrypt(rsa::Oaep::new::<sha2::Sha256>(), envelope.shares[index])
+        .unwrap();
+    vec![(block, vec![(0, share)])]
+}
+
+#[test]
+fn threshold_decryption_combines_committee_shares() {
+    new_test_ext().execute_with(|| {
+        let netuid = 0;
+        let (validator_uid, miner_uid) = (0, 1);
+        let member_keys = setup_threshold_committee(netuid);
+        let committee = SubnetDecryptionCommittee::<Test>::get(netuid).unwrap();
+
+        let block = SubspaceMod::get_current_block_number();
+        let encrypted = insert_threshold_epoch(netuid, block, vec![(miner_uid, 1)]);
+        let share_of = |index: usize| threshold_share_of(&member_keys, &encrypted, index, block);
+
+        assert_err!(
+            SubnetEmissionMod::handle_decryption_shares(netuid, &2000, share_of(0)),
+            pallet_subspace::Error::<Test>::NotDecryptionCommitteeMember
+        );
+
+        let (first, _) = &committee.members[0];
+        let mut tampered = share_of(0);
+        tampered[0].1[0].1[1] ^= 1;
+        assert_err!(
+            SubnetEmissionMod::handle_decryption_shares(netuid, first, tampered),
+            pallet_subspace::Error::<Test>::InvalidDecryptionShare
+        );
+        assert_err!(
+            SubnetEmissionMod::handle_decryption_shares(netuid, first, share_of(1)),
+            pallet_subspace::Error::<Test>::InvalidDecryptionShare
+        );
+
+        assert_ok!(SubnetEmissionMod::handle_decryption_shares(
+            netuid,
+            first,
+            share_of(0)
+        ));
+        assert!(DecryptionShares::<Test>::contains_key(netuid, first));
+
+        let (third, _) = &committee.members[2];
+        assert_ok!(SubnetEmissionMod::handle_decryption_shares(
+            netuid,
+            third,
+            share_of(2)
+        ));
+        assert_eq!(DecryptionShares::<Test>::iter_prefix(netuid).count(), 0);
+        assert_eq!(
+            Weights::<Test>::get(netuid, validator_uid),
+            Some(vec![(miner_uid, 1)])
+        );
+        assert!(SubnetDecryptionCommittee::<Test>::contains_key(netuid));
+    });
+}
+
+#[test]
+fn threshold_decryption_keeps_epochs_waiting_for_shares() {
+    new_test_ext().execute_with(|| {
+        let netuid = 0;
+        let member_keys = setup_threshold_committee(netuid);
+        let committee = SubnetDecryptionCommittee::<Test>::get(netuid).unwrap();
+        let member = |index: usize| committee.members[index].0;
+
+        let first_block = SubspaceMod::get_current_block_number();
+        let first = insert_threshold_epoch(netuid, first_block, vec![(1, 1)]);
+        let share_of_first =
+            |index: usize| threshold_share_of(&member_keys, &first, index, first_block);
+
+        // The first member reports before the second epoch comes in
+        assert_ok!(SubnetEmissionMod::handle_decryption_shares(
+            netuid,
+            &member(0),
+            share_of_first(0)
+        ));
+
+        let second_block = first_block + 1;
+        let second = insert_threshold_epoch(netuid, second_block, vec![(1, 2)]);
+        let share_of_second =
+            |index: usize| threshold_share_of(&member_keys, &second, index, second_block);
+
+        assert_ok!(SubnetEmissionMod::handle_decryption_shares(
+            netuid,
+            &member(1),
+            [share_of_first(1), share_of_second(1)].concat()
+        ));
+
+        assert_eq!(Weights::<Test>::get(netuid, 0), Some(vec![(1, 1)]));
+        assert!(
+            !pallet_subnet_emission::ConsensusParameters::<Test>::contains_key(netuid, first_block)
+        );
+        assert!(
+            pallet_subnet_emission::ConsensusParameters::<Test>::contains_key(netuid, second_block)
+        );
+        assert!(!DecryptionShares::<Test>::contains_key(netuid, member(0)));
+        assert_eq!(
+            DecryptionShares::<Test>::get(netuid, member(1)),
+            Some(share_of_second(1))
+        );
+
+        assert_ok!(SubnetEmissionMod::handle_decryption_shares(
+            netuid,
+            &member(2),
+            share_of_second(2)
+        ));
+
+        assert_eq!(Weights::<Test>::get(netuid, 0), Some(vec![(1, 2)]));
+        assert_eq!(
+            pallet_subnet_emission::ConsensusParameters::<Test>::iter_prefix(netuid).count(),
+            0
+        );
+        assert_eq!(DecryptionShares::<Test>::iter_prefix(netuid).count(), 0);
+    });
+}
+
+#[test]
+fn threshold_decryption_committee_rotates_after_interval() {
+    use sp_core::Get;
+    new_test_ext().execute_with(|| {
+        let netuid = 0;
+        let member_keys = setup_threshold_committee(netuid);
+        let committee = SubnetDecryptionCommittee::<Test>::get(netuid).unwrap();
+
+        let block = committee.formed_at
+            + <Test as pallet_subnet_emission::Config>::DecryptionNodeRotationInterval::get();
+        System::set_block_number(block);
+        let encrypted = insert_threshold_epoch(netuid, block, vec![(1, 1)]);
+
+        for index in [0, 1] {
+            let (member, _) = &committee.members[index];
+            assert_ok!(SubnetEmissionMod::handle_decryption_shares(
+                netuid,
+                member,
+                threshold_share_of(&member_keys, &encrypted, index, block)
+            ));
+        }
+
+        assert_eq!(Weights::<Test>::get(netuid, 0), Some(vec![(1, 1)]));
+        assert!(!SubnetDecryptionCommittee::<Test>::contains_key(netuid));
+        System::assert_last_event(RuntimeEvent::SubnetEmissionMod(
+            pallet_subnet_emission::Event::DecryptionCommitteeRotated { subnet_id: netuid },
+        ));
+    });
+}
+
+#[test]
+fn threshold_decryption_committee_expires_and_bans_silent_members() {
+    use sp_core::Get;
+    new_test_ext().execute_with(|| {
+        let netuid = 0;
+        let member_keys = setup_threshold_committee(netuid);
+        let committee = SubnetDecryptionCommittee::<Test>::get(netuid).unwrap();
+
+        let block = SubspaceMod::get_current_block_number();
+        let encrypted = insert_threshold_epoch(netuid, block, vec![(1, 1)]);
+
+        let (first, _) = &committee.members[0];
+        assert_ok!(SubnetEmissionMod::handle_decryption_shares(
+            netuid,
+            first,
+            threshold_share_of(&member_keys, &encrypted, 0, block)
+        ));
+
+        let timeout = SubnetEmissionMod::get_max_encryption_interval(&netuid)
+            + <Test as pallet_subnet_emission::Config>::EncryptionPeriodBuffer::get();
+        SubnetEmissionMod::cancel_expired_committees(block + timeout);
+        assert!(SubnetDecryptionCommittee::<Test>::contains_key(netuid));
+
+        let pending_emission = PendingEmission::<Test>::get(netuid);
+        SubnetEmissionMod::cancel_expired_committees(block + timeout + 1);
+
+        assert!(!SubnetDecryptionCommittee::<Test>::contains_key(netuid));
+        assert_eq!(
+            pallet_subnet_emission::ConsensusParameters::<Test>::iter_prefix(netuid).count(),
+            0
+        );
+        assert_eq!(DecryptionShares::<Test>::iter_prefix(netuid).count(), 0);
+        assert_eq!(
+            PendingEmission::<Test>::get(netuid),
+            pending_emission + to_nano(1)
+        );
+
+        assert!(!BannedDecryptionNodes::<Test>::contains_key(first));
+        for (member, _) in &committee.members[1..] {
+            assert!(BannedDecryptionNodes::<Test>::contains_key(member));
+        }
+    });
+}
+
+fn setup_colliding_subnets(netuids: &[u16]) {
+    for &netuid in netuids {
+        assert_ok!(register_module(
+            netuid,
+            u32::from(netuid),
+            to_nano(100),
+            false
+        ));
+        // netuids congruent modulo the tempo share the same epoch block
+        Tempo::<Test>::insert(netuid, 10);
+    }
+}
+
+fn epoch_deferred_events() -> Vec<u16> {
+    System::events()
+        .into_iter()
+        .filter_map(|record| match record.event {
+            RuntimeEvent::SubnetEmissionMod(pallet_subnet_emission::Event::EpochDeferred(
+                netuid,
+            )) => Some(netuid),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn subnets_sharing_a_tempo_are_phased_by_netuid() {
+    new_test_ext().execute_with(|| {
+        Tempo::<Test>::insert(1, 10);
+        Tempo::<Test>::insert(2, 10);
+
+        let epoch_blocks = |netuid| {
+            (0..10u64)
+                .filter(|block| SubspaceMod::blocks_until_next_epoch(netuid, *block) == 0)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(epoch_blocks(1), vec![9]);
+        assert_eq!(epoch_blocks(2), vec![8]);
+    });
+}
+
+#[test]
+fn epochs_over_block_limit_are_deferred() {
+    new_test_ext().execute_with(|| {
+        MaxEpochsPerBlock::set(1);
+        setup_colliding_subnets(&[0, 10, 20]);
+
+        run_to_block(10);
+        assert_eq!(DeferredEpochs::<Test>::get(), vec![10, 20]);
+        assert_eq!(epoch_deferred_events(), vec![10, 20]);
+
+        step_block(1);
+        assert_eq!(DeferredEpochs::<Test>::get(), vec![20]);
+
+        step_block(1);
+        assert!(DeferredEpochs::<Test>::get().is_empty());
+        // Deferring is only announced once per epoch
+        assert_eq!(epoch_deferred_events(), vec![10, 20]);
+    });
+}
+
+#[test]
+fn deferred_epochs_are_not_queued_twice() {
+    new_test_ext().execute_with(|| {
+        MaxEpochsPerBlock::set(0);
+        setup_colliding_subnets(&[0, 10, 20]);
+
+        run_to_block(10);
+        assert_eq!(DeferredEpochs::<Test>::get(), vec![0, 10, 20]);
+
+        run_to_block(20);
+        assert_eq!(DeferredEpochs::<Test>::get(), vec![0, 10, 20]);
+        assert_eq!(epoch_deferred_events(), vec![0, 10, 20]);
+
+        MaxEpochsPerBlock::set(2);
+        step_block(1);
+        assert_eq!(DeferredEpochs::<Test>::get(), vec![20]);
+    });
+}
+
+#[test]
+fn deferred_epochs_of_removed_subnets_are_dropped() {
+    new_test_ext().execute_with(|| {
+        MaxEpochsPerBlock::set(1);
+        setup_colliding_subnets(&[0, 10, 20]);
+
+        run_to_block(10);
+        assert_eq!(DeferredEpochs::<Test>::get(), vec![10, 20]);
+
+        SubspaceMod::remove_subnet(10);
+        step_block(1);
+        assert!(DeferredEpochs::<Test>::get().is_empty());
+    });
+}
+
+#[test]
+fn hooks_report_consumed_weight() {
+    new_test_ext().execute_with(|| {
+        setup_colliding_subnets(&[0, 10]);
+        PendingEmission::<Test>::insert(0, to_nano(10));
+        PendingEmission::<Test>::insert(10, to_nano(10));
+        System::set_block_number(10);
+
+        assert!(SubspaceMod::on_initialize(10) != Weight::zero());
+        let weight = SubnetEmissionMod::on_initialize(10);
+        assert!(weight != Weight::zero());
+
+        // An epoch block is heavier than a regular one
+        assert!(SubnetEmissionMod::on_initialize(11).ref_time() < weight.ref_time());
+    });
+}
+
+fn setup_fixed_allocation_subnet() {
+    setup_colliding_subnets(&[0]);
+    for key in 1..3 {
+        assert_ok!(register_module(0, key, to_nano(100), false));
+    }
+    SubnetConsensusType::<Test>::set(0, Some(SubnetConsensus::FixedAllocation));
+}
+
+#[test]
+fn fixed_allocation_is_validated() {
+    new_test_ext().execute_with(|| {
+        setup_fixed_allocation_subnet();
+
+        let set = |key, allocation: &[(u16, u8)]| {
+            SubnetEmissionMod::set_fixed_allocation(
+                get_origin(key),
+                0,
+                allocation
+                    .iter()
+                    .map(|(uid, share)| (*uid, Percent::from_parts(*share)))
+                    .collect(),
+            )
+        };
+
+        assert_err!(
+            set(1, &[(1, 100)]),
+            pallet_subspace::Error::<Test>::NotFounder
+        );
+        for allocation in [
+            &[(1, 60), (2, 30)][..],
+            &[(1, 50), (1, 50)],
+            &[(1, 50), (3, 50)],
+            &[],
+        ] {
+            assert_err!(
+                set(0, allocation),
+                pallet_subspace::Error::<Test>::InvalidFixedAllocation
+            );
+        }
+
+        assert_ok!(set(0, &[(1, 75), (2, 25)]));
+        assert_eq!(
+            FixedAllocations::<Test>::get(0),
+            vec![(1, Percent::from_parts(75)), (2, Percent::from_parts(25))]
+        );
+    });
+}
+
+#[test]
+fn fixed_allocation_distributes_emission_by_shares() {
+    new_test_ext().execute_with(|| {
+        setup_fixed_allocation_subnet();
+        assert_ok!(SubnetEmissionMod::set_fixed_allocation(
+            get_origin(0),
+            0,
+            vec![(1, Percent::from_parts(75)), (2, Percent::from_parts(25))]
+        ));
+
+        PendingEmission::<Test>::insert(0, to_nano(100));
+        run_to_block(10);
+        assert_eq!(PendingEmission::<Test>::get(0), 0);
+
+        let emission = Emission::<Test>::get(0);
+        assert_eq!(emission[0], 0);
+        assert!(emission[1].abs_diff(emission[2] * 3) < to_nano(1));
+        assert!(Dividends::<Test>::get(0).iter().all(|dividends| *dividends == 0));
+        assert_eq!(
+            SubspaceMod::get_delegated_stake(&1),
+            to_nano(100) + emission[1]
+        );
+    });
+}
+
+#[test]
+fn fixed_allocation_is_redistributed_when_a_module_leaves() {
+    new_test_ext().execute_with(|| {
+        setup_fixed_allocation_subnet();
+        assert_ok!(register_module(0, 3, to_nano(100), false));
+        assert_ok!(SubnetEmissionMod::set_fixed_allocation(
+            get_origin(0),
+            0,
+            vec![
+                (1, Percent::from_parts(50)),
+                (2, Percent::from_parts(30)),
+                (3, Percent::from_parts(20))
+            ]
+        ));
+
+        // the shares left are scaled back up to 100%, the rounding goes to the largest one
+        assert_ok!(SubspaceMod::deregister(get_origin(3), 0));
+        assert_eq!(
+            FixedAllocations::<Test>::get(0),
+            vec![(1, Percent::from_parts(63)), (2, Percent::from_parts(37))]
+        );
+
+        assert_ok!(SubspaceMod::deregister(get_origin(2), 0));
+        assert_eq!(
+            FixedAllocations::<Test>::get(0),
+            vec![(1, Percent::from_parts(100))]
+        );
+
+        PendingEmission::<Test>::insert(0, to_nano(100));
+        run_to_block(10);
+        assert_eq!(PendingEmission::<Test>::get(0), 0);
+        assert!(SubspaceMod::get_delegated_stake(&1) > to_nano(100));
+    });
+}
+
+#[test]
+fn fixed_allocation_waits_for_a_valid_allocation() {
+    new_test_ext().execute_with(|| {
+        setup_fixed_allocation_subnet();
+        assert_ok!(SubnetEmissionMod::set_fixed_allocation(
+            get_origin(0),
+            0,
+            vec![(1, Percent::from_parts(100))]
+        ));
+        assert_ok!(SubspaceMod::deregister(get_origin(1), 0));
+        assert!(!FixedAllocations::<Test>::contains_key(0));
+
+        PendingEmission::<Test>::insert(0, to_nano(100));
+        run_to_block(10);
+
+        assert!(PendingEmission::<Test>::get(0) >= to_nano(100));
+        assert_eq!(SubspaceMod::get_delegated_stake(&2), to_nano(100));
+    });
+}
+
+fn consensus_changed_events() -> Vec<(u16, SubnetConsensus, SubnetConsensus)> {
+    System::events()
+        .into_iter()
+        .filter_map(|record| match record.event {
+            RuntimeEvent::SubnetEmissionMod(pallet_subnet_emission::Event::ConsensusChanged {
+                subnet_id,
+                previous,
+                consensus,
+            }) => Some((subnet_id, previous, consensus)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn consensus_change_is_applied_after_the_epoch() {
+    new_test_ext().execute_with(|| {
+        setup_colliding_subnets(&[0]);
+        assert_ok!(register_module(0, 1, to_nano(100), false));
+        Bonds::<Test>::insert(0, 0, vec![(1, 100)]);
+        ValidatorPermits::<Test>::insert(0, vec![true, true]);
+
+        assert_ok!(SubnetEmissionMod::schedule_consensus_change(
+            0,
+            SubnetConsensus::Linear
+        ));
+
+        run_to_block(9);
+        assert_eq!(
+            SubnetConsensusType::<Test>::get(0),
+            Some(SubnetConsensus::Yuma)
+        );
+        assert!(consensus_changed_events().is_empty());
+
+        step_block(1);
+        assert_eq!(
+            SubnetConsensusType::<Test>::get(0),
+            Some(SubnetConsensus::Linear)
+        );
+        assert!(!PendingConsensusChange::<Test>::contains_key(0));
+        assert_eq!(Bonds::<Test>::iter_prefix(0).count(), 0);
+        assert_eq!(ValidatorPermits::<Test>::get(0), vec![false, false]);
+        assert_eq!(
+            consensus_changed_events(),
+            vec![(0, SubnetConsensus::Yuma, SubnetConsensus::Linear)]
+        );
+    });
+}
+
+#[test]
+fn consensus_change_is_validated() {
+    new_test_ext().execute_with(|| {
+        setup_colliding_subnets(&[0, 1]);
+        SubnetConsensusType::<Test>::set(1, Some(SubnetConsensus::Treasury));
+
+        for consensus in [
+            SubnetConsensus::Yuma,
+            SubnetConsensus::Root,
+            SubnetConsensus::Treasury,
+        ] {
+            assert_err!(
+                SubnetEmissionMod::schedule_consensus_change(0, consensus),
+                pallet_subspace::Error::<Test>::InvalidConsensusChange
+            );
+        }
+        assert_err!(
+            SubnetEmissionMod::schedule_consensus_change(2, SubnetConsensus::Linear),
+            pallet_subspace::Error::<Test>::NetworkDoesNotExist
+        );
+
+        SubnetConsensusType::<Test>::set(1, Some(SubnetConsensus::Root));
+        assert_err!(
+            SubnetEmissionMod::schedule_consensus_change(1, SubnetConsensus::Yuma),
+            pallet_subspace::Error::<Test>::InvalidConsensusChange
+        );
+
+        assert_ok!(SubnetEmissionMod::schedule_consensus_change(
+            0,
+            SubnetConsensus::Treasury
+        ));
+    });
+}
+
 // #[test]
 // fn receive_empty_weights() {
 //     new_test_ext().execute_with(|| {
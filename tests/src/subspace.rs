@@ -1,4 +1,7 @@
 mod burn;
+mod fees;
+mod operator;
+mod pool;
 mod registration;
 mod staking;
 mod subnet;
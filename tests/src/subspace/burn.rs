@@ -1,9 +1,13 @@
 use std::u32;
 
 use crate::mock::*;
-use frame_support::assert_ok;
+use frame_support::{
+    assert_ok,
+    traits::{OnRuntimeUpgrade, StorageVersion},
+};
 use pallet_subspace::*;
 use params::burn::GeneralBurnConfiguration;
+use sp_runtime::Percent;
 
 #[test]
 fn module_registration_burn_increases() {
@@ -119,3 +123,230 @@ fn subnet_registration_burn_increases() {
         );
     });
 }
+
+#[test]
+fn testnet_migration_adds_registration_cost() {
+    new_test_ext().execute_with(|| {
+        let old = migrations::v16::old_storage::GeneralBurnConfiguration {
+            min_burn: 1,
+            max_burn: 2,
+            adjustment_alpha: 3,
+            target_registrations_interval: 4,
+            target_registrations_per_interval: 5,
+            max_registrations_per_interval: 6,
+        };
+        frame_support::storage::unhashed::put(&ModuleBurnConfig::<Test>::hashed_key_for(0), &old);
+
+        StorageVersion::new(34).put::<SubspaceMod>();
+        migrations::testnet::MigrateToV35::<Test>::on_runtime_upgrade();
+        assert_eq!(StorageVersion::get::<SubspaceMod>(), 35);
+
+        let config = ModuleBurnConfig::<Test>::get(0);
+        assert_eq!(config.max_registrations_per_interval, 6);
+        assert_eq!(config.registration_cost, RegistrationCost::Burn);
+    });
+}
+
+mod registration_deposit {
+    use super::*;
+    use frame_support::assert_err;
+    use pallet_governance_api::GovernanceApi;
+
+    const NETUID: u16 = 1;
+    const FOUNDER: AccountId = 0;
+    const MODULE: AccountId = 1;
+    const DEPOSIT: u64 = to_nano(10);
+    const MIN_TENURE: u64 = 10;
+
+    fn setup_deposit_subnet() {
+        setup_subnet(NETUID, FOUNDER, to_nano(10));
+        ModuleBurnConfig::<Test>::mutate(NETUID, |config| {
+            config.registration_cost = RegistrationCost::Deposit {
+                min_tenure: MIN_TENURE,
+                refund: Percent::from_percent(80),
+            };
+        });
+        Burn::<Test>::insert(NETUID, DEPOSIT);
+    }
+
+    fn register(key: AccountId) {
+        SubspaceMod::add_balance_to_account(&key, to_nano(20));
+        assert_ok!(SubspaceMod::register(
+            get_origin(key),
+            format!("test{NETUID}").into_bytes(),
+            format!("module{key}").into_bytes(),
+            b"0.0.0.0:30333".to_vec(),
+            key,
+            None,
+        ));
+    }
+
+    fn reserved(key: AccountId) -> u64 {
+        pallet_balances::Pallet::<Test>::reserved_balance(key)
+    }
+
+    fn treasury_balance() -> u64 {
+        SubspaceMod::get_balance_u64(
+            &<Test as GovernanceApi<AccountId>>::get_dao_treasury_address(),
+        )
+    }
+
+    #[test]
+    fn deposit_mode_reserves_registration_cost() {
+        new_test_ext().execute_with(|| {
+            setup_deposit_subnet();
+            let total_issuance = get_total_issuance();
+
+            register(MODULE);
+
+            assert_eq!(reserved(MODULE), DEPOSIT);
+            assert_eq!(SubspaceMod::get_balance_u64(&MODULE), to_nano(10));
+            assert_eq!(get_total_issuance(), total_issuance + to_nano(20));
+
+            let deposit = RegistrationDeposits::<Test>::get(NETUID, MODULE).unwrap();
+            assert_eq!(deposit.payer, MODULE);
+            assert_eq!(deposit.amount, DEPOSIT);
+            assert_eq!(deposit.min_tenure, MIN_TENURE);
+        });
+    }
+
+    #[test]
+    fn not_enough_balance_for_deposit_fails() {
+        new_test_ext().execute_with(|| {
+            setup_deposit_subnet();
+
+            SubspaceMod::add_balance_to_account(&MODULE, DEPOSIT / 2);
+            assert_err!(
+                SubspaceMod::register(
+                    get_origin(MODULE),
+                    format!("test{NETUID}").into_bytes(),
+                    b"module".to_vec(),
+                    b"0.0.0.0:30333".to_vec(),
+                    MODULE,
+                    None,
+                ),
+                Error::<Test>::NotEnoughBalanceToRegister
+            );
+        });
+    }
+
+    #[test]
+    fn deregister_after_min_tenure_refunds_deposit() {
+        new_test_ext().execute_with(|| {
+            setup_deposit_subnet();
+            register(MODULE);
+            let treasury = treasury_balance();
+
+            step_block(MIN_TENURE as u16);
+            assert_ok!(SubspaceMod::deregister(get_origin(MODULE), NETUID));
+
+            let refund = DEPOSIT * 80 / 100;
+            assert_eq!(reserved(MODULE), 0);
+            assert_eq!(SubspaceMod::get_balance_u64(&MODULE), to_nano(10) + refund);
+            assert_eq!(treasury_balance(), treasury + DEPOSIT - refund);
+            assert!(!RegistrationDeposits::<Test>::contains_key(NETUID, MODULE));
+        });
+    }
+
+    #[test]
+    fn deregister_before_min_tenure_forfeits_deposit() {
+        new_test_ext().execute_with(|| {
+            setup_deposit_subnet();
+            register(MODULE);
+            let treasury = treasury_balance();
+
+            assert_ok!(SubspaceMod::deregister(get_origin(MODULE), NETUID));
+
+            assert_eq!(reserved(MODULE), 0);
+            assert_eq!(SubspaceMod::get_balance_u64(&MODULE), to_nano(10));
+            assert_eq!(treasury_balance(), treasury + DEPOSIT);
+        });
+    }
+
+    #[test]
+    fn pruned_module_forfeits_deposit() {
+        new_test_ext().execute_with(|| {
+            setup_deposit_subnet();
+            register(MODULE);
+            step_block(MIN_TENURE as u16);
+            let treasury = treasury_balance();
+
+            MaxAllowedUids::<Test>::insert(NETUID, 2);
+            ValidatorPermits::<Test>::insert(NETUID, vec![true, false]);
+            register(2);
+
+            assert!(!Uids::<Test>::contains_key(NETUID, MODULE));
+            assert_eq!(reserved(MODULE), 0);
+            assert_eq!(SubspaceMod::get_balance_u64(&MODULE), to_nano(10));
+            assert_eq!(treasury_balance(), treasury + DEPOSIT);
+            assert_eq!(reserved(2), DEPOSIT);
+        });
+    }
+
+    #[test]
+    fn module_removed_without_pruning_gets_deposit_back() {
+        new_test_ext().execute_with(|| {
+            setup_deposit_subnet();
+            register(MODULE);
+            let treasury = treasury_balance();
+
+            // e.g. a governance deregistration
+            let uid = SubspaceMod::get_uid_for_key(NETUID, &MODULE).unwrap();
+            assert_ok!(SubspaceMod::remove_module(NETUID, uid, false));
+
+            assert_eq!(reserved(MODULE), 0);
+            assert_eq!(SubspaceMod::get_balance_u64(&MODULE), to_nano(20));
+            assert_eq!(treasury_balance(), treasury);
+            assert!(!RegistrationDeposits::<Test>::contains_key(NETUID, MODULE));
+        });
+    }
+
+    #[test]
+    fn deposit_terms_are_kept_from_registration() {
+        new_test_ext().execute_with(|| {
+            setup_deposit_subnet();
+            register(MODULE);
+
+            ModuleBurnConfig::<Test>::mutate(NETUID, |config| {
+                config.registration_cost = RegistrationCost::Burn;
+            });
+            step_block(MIN_TENURE as u16);
+            assert_ok!(SubspaceMod::deregister(get_origin(MODULE), NETUID));
+
+            let refund = DEPOSIT * 80 / 100;
+            assert_eq!(SubspaceMod::get_balance_u64(&MODULE), to_nano(10) + refund);
+        });
+    }
+
+    #[test]
+    fn removed_subnet_refunds_deposits() {
+        new_test_ext().execute_with(|| {
+            setup_deposit_subnet();
+            register(MODULE);
+
+            SubspaceMod::remove_subnet(NETUID);
+
+            assert_eq!(reserved(MODULE), 0);
+            assert_eq!(SubspaceMod::get_balance_u64(&MODULE), to_nano(20));
+        });
+    }
+
+    #[test]
+    fn rejected_request_unreserves_deposit() {
+        new_test_ext().execute_with(|| {
+            setup_deposit_subnet();
+            update_params!(NETUID => { registration_mode: RegistrationMode::FounderApproval });
+            register(MODULE);
+
+            assert_eq!(reserved(MODULE), DEPOSIT);
+            assert_ok!(SubspaceMod::reject_registration(
+                get_origin(FOUNDER),
+                NETUID,
+                MODULE
+            ));
+
+            assert_eq!(reserved(MODULE), 0);
+            assert_eq!(SubspaceMod::get_balance_u64(&MODULE), to_nano(20));
+        });
+    }
+}
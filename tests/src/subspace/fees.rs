@@ -0,0 +1,200 @@
+use crate::mock::*;
+use frame_support::{assert_err, dispatch::DispatchResult};
+use pallet_subspace::*;
+use sp_runtime::Percent;
+
+const NETUID: u16 = 0;
+const KEY: AccountId = 0;
+const PERIOD: u64 = 100;
+
+fn setup() {
+    zero_min_burn();
+    MinimumAllowedStake::<Test>::set(0);
+    FeeAnnouncementPeriod::<Test>::set(PERIOD);
+
+    assert_ok!(register_module(NETUID, KEY, to_nano(10), false));
+    // events are only recorded after genesis
+    step_block(1);
+}
+
+fn fees(stake_delegation_fee: u8, validator_weight_fee: u8) -> ValidatorFees {
+    ValidatorFees {
+        stake_delegation_fee: Percent::from_percent(stake_delegation_fee),
+        validator_weight_fee: Percent::from_percent(validator_weight_fee),
+    }
+}
+
+fn update_fees(fees: ValidatorFees) -> DispatchResult {
+    let uid = SubspaceMod::get_uid_for_key(NETUID, &KEY).unwrap();
+    let params = SubspaceMod::module_params(NETUID, &KEY, uid);
+    SubspaceMod::update_module(
+        get_origin(KEY),
+        NETUID,
+        params.name,
+        params.address,
+        Some(fees.stake_delegation_fee),
+        Some(fees.validator_weight_fee),
+        None,
+    )
+}
+
+fn fee_events() -> Vec<pallet_subspace::Event<Test>> {
+    System::events()
+        .into_iter()
+        .filter_map(|record| match record.event {
+            RuntimeEvent::SubspaceMod(
+                event @ (pallet_subspace::Event::ValidatorFeesAnnounced(..)
+                | pallet_subspace::Event::ValidatorFeesUpdated(..)),
+            ) => Some(event),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn increases_wait_for_the_announcement_period() {
+    new_test_ext().execute_with(|| {
+        setup();
+        let activation_block = System::block_number() + PERIOD;
+
+        assert_ok!(update_fees(fees(10, 10)));
+        assert_eq!(
+            ValidatorFeeConfig::<Test>::get(KEY),
+            ValidatorFees::default()
+        );
+        assert_eq!(
+            PendingValidatorFees::<Test>::get(KEY),
+            Some(PendingFeeChange {
+                fees: fees(10, 10),
+                activation_block,
+            })
+        );
+
+        run_to_block(activation_block - 1);
+        assert_eq!(
+            ValidatorFeeConfig::<Test>::get(KEY),
+            ValidatorFees::default()
+        );
+
+        run_to_block(activation_block);
+        assert_eq!(ValidatorFeeConfig::<Test>::get(KEY), fees(10, 10));
+        assert!(!PendingValidatorFees::<Test>::contains_key(KEY));
+
+        assert_eq!(
+            fee_events(),
+            vec![
+                pallet_subspace::Event::ValidatorFeesAnnounced(KEY, fees(10, 10), activation_block),
+                pallet_subspace::Event::ValidatorFeesUpdated(KEY, fees(10, 10)),
+            ]
+        );
+    });
+}
+
+#[test]
+fn decreases_are_instant() {
+    new_test_ext().execute_with(|| {
+        setup();
+        MinFees::<Test>::put(MinimumFees {
+            stake_delegation_fee: Percent::zero(),
+            validator_weight_fee: Percent::zero(),
+        });
+
+        assert_ok!(update_fees(fees(1, 1)));
+        assert_eq!(ValidatorFeeConfig::<Test>::get(KEY), fees(1, 1));
+        assert!(!PendingValidatorFees::<Test>::contains_key(KEY));
+        assert_eq!(
+            fee_events(),
+            vec![pallet_subspace::Event::ValidatorFeesUpdated(
+                KEY,
+                fees(1, 1)
+            )]
+        );
+    });
+}
+
+#[test]
+fn mixed_changes_lower_now_and_raise_later() {
+    new_test_ext().execute_with(|| {
+        setup();
+        MinFees::<Test>::put(MinimumFees {
+            stake_delegation_fee: Percent::zero(),
+            validator_weight_fee: Percent::zero(),
+        });
+
+        assert_ok!(update_fees(fees(1, 20)));
+        assert_eq!(ValidatorFeeConfig::<Test>::get(KEY), fees(1, 4));
+        assert_eq!(
+            PendingValidatorFees::<Test>::get(KEY).map(|pending| pending.fees),
+            Some(fees(1, 20))
+        );
+
+        step_block(PERIOD as u16);
+        assert_eq!(ValidatorFeeConfig::<Test>::get(KEY), fees(1, 20));
+    });
+}
+
+#[test]
+fn new_updates_replace_the_announcement() {
+    new_test_ext().execute_with(|| {
+        setup();
+        let activation_block = System::block_number() + PERIOD;
+
+        assert_ok!(update_fees(fees(10, 10)));
+        step_block(10);
+
+        // announcing the same fees again keeps the original activation
+        assert_ok!(update_fees(fees(10, 10)));
+        assert_eq!(
+            PendingValidatorFees::<Test>::get(KEY).map(|pending| pending.activation_block),
+            Some(activation_block)
+        );
+
+        // going back to the current fees cancels the announcement
+        assert_ok!(update_fees(ValidatorFees::default()));
+        assert!(!PendingValidatorFees::<Test>::contains_key(KEY));
+
+        run_to_block(activation_block);
+        assert_eq!(
+            ValidatorFeeConfig::<Test>::get(KEY),
+            ValidatorFees::default()
+        );
+    });
+}
+
+#[test]
+fn fees_are_capped_by_the_maximum() {
+    new_test_ext().execute_with(|| {
+        setup();
+        MaxFees::<Test>::put(MaximumFees {
+            stake_delegation_fee: Percent::from_percent(20),
+            validator_weight_fee: Percent::from_percent(20),
+        });
+
+        assert_err!(
+            update_fees(fees(30, 10)),
+            Error::<Test>::InvalidMaxDelegationFee
+        );
+
+        assert_ok!(update_fees(fees(20, 20)));
+        MaxFees::<Test>::put(MaximumFees {
+            stake_delegation_fee: Percent::from_percent(15),
+            validator_weight_fee: Percent::from_percent(20),
+        });
+
+        step_block(PERIOD as u16);
+        assert_eq!(ValidatorFeeConfig::<Test>::get(KEY), fees(15, 20));
+    });
+}
+
+#[test]
+fn deregistration_drops_the_announcement() {
+    new_test_ext().execute_with(|| {
+        setup();
+        assert_ok!(register_module(NETUID, 1, to_nano(10), false));
+
+        assert_ok!(update_fees(fees(10, 10)));
+        assert_ok!(SubspaceMod::remove_module(NETUID, 0, false));
+
+        assert!(!PendingValidatorFees::<Test>::contains_key(KEY));
+    });
+}
@@ -0,0 +1,195 @@
+use crate::mock::*;
+use frame_support::{assert_err, dispatch::DispatchResult};
+use pallet_subnet_emission::Weights;
+use pallet_subspace::*;
+
+const NETUID: u16 = 1;
+const OWNER: AccountId = 0;
+const OPERATOR: AccountId = 10;
+
+fn setup() {
+    zero_min_validator_stake();
+    zero_min_burn();
+    MinimumAllowedStake::<Test>::set(0);
+
+    for key in 0..3 {
+        assert_ok!(register_module(NETUID, key, to_nano(100), false));
+    }
+}
+
+fn grant(
+    owner: AccountId,
+    operator: AccountId,
+    permissions: &[OperatorPermission],
+) -> DispatchResult {
+    SubspaceMod::set_operator_key(get_origin(owner), operator, permissions.to_vec())?;
+    if PendingOperatorKeys::<Test>::contains_key(owner, operator) {
+        SubspaceMod::accept_operator_key(get_origin(operator), owner)?;
+    }
+    Ok(())
+}
+
+fn set_weights_as(key: AccountId) -> DispatchResult {
+    SubnetEmissionMod::set_weights(get_origin(key), NETUID, vec![1], vec![1])
+}
+
+fn rename_as(key: AccountId, name: &[u8]) -> DispatchResult {
+    SubspaceMod::update_module(
+        get_origin(key),
+        NETUID,
+        name.to_vec(),
+        b"0.0.0.0:30333".to_vec(),
+        None,
+        None,
+        None,
+    )
+}
+
+#[test]
+fn operator_sets_weights_for_its_module() {
+    new_test_ext().execute_with(|| {
+        setup();
+        let uid = SubspaceMod::get_uid_for_key(NETUID, &OWNER).unwrap();
+
+        assert_ok!(grant(OWNER, OPERATOR, &[OperatorPermission::SetWeights]));
+        assert_ok!(set_weights_as(OPERATOR));
+
+        assert_eq!(Weights::<Test>::get(NETUID, uid), Some(vec![(1, u16::MAX)]));
+    });
+}
+
+#[test]
+fn operator_calls_are_scoped_by_permission() {
+    new_test_ext().execute_with(|| {
+        setup();
+        let uid = SubspaceMod::get_uid_for_key(NETUID, &OWNER).unwrap();
+
+        assert_ok!(grant(OWNER, OPERATOR, &[OperatorPermission::UpdateModule]));
+
+        assert_err!(set_weights_as(OPERATOR), Error::<Test>::NotModuleOperator);
+        assert_ok!(rename_as(OPERATOR, b"renamed"));
+        assert_eq!(Name::<Test>::get(NETUID, uid), b"renamed".to_vec());
+
+        // granting again replaces the previous permissions
+        assert_ok!(grant(OWNER, OPERATOR, &[OperatorPermission::SetWeights]));
+        assert_ok!(set_weights_as(OPERATOR));
+        assert_err!(
+            rename_as(OPERATOR, b"renamed2"),
+            Error::<Test>::NotModuleOperator
+        );
+    });
+}
+
+#[test]
+fn operators_cannot_deregister_or_manage_operators() {
+    new_test_ext().execute_with(|| {
+        setup();
+
+        assert_ok!(grant(
+            OWNER,
+            OPERATOR,
+            &[
+                OperatorPermission::SetWeights,
+                OperatorPermission::UpdateModule,
+            ]
+        ));
+
+        assert_err!(
+            SubspaceMod::deregister(get_origin(OPERATOR), NETUID),
+            Error::<Test>::ModuleDoesNotExist
+        );
+        assert_err!(
+            grant(OPERATOR, 11, &[OperatorPermission::SetWeights]),
+            Error::<Test>::ModuleDoesNotExist
+        );
+        assert!(Uids::<Test>::contains_key(NETUID, OWNER));
+    });
+}
+
+#[test]
+fn operator_keys_must_accept_the_offer() {
+    new_test_ext().execute_with(|| {
+        setup();
+
+        assert_ok!(SubspaceMod::set_operator_key(
+            get_origin(OWNER),
+            OPERATOR,
+            vec![OperatorPermission::SetWeights]
+        ));
+        assert_err!(set_weights_as(OPERATOR), Error::<Test>::ModuleDoesNotExist);
+        assert_err!(
+            SubspaceMod::accept_operator_key(get_origin(OPERATOR), 1),
+            Error::<Test>::OperatorKeyNotFound
+        );
+
+        // an offer the key didn't accept doesn't keep it from registering
+        assert_ok!(register_module(NETUID, OPERATOR, to_nano(100), false));
+        assert_err!(
+            SubspaceMod::accept_operator_key(get_origin(OPERATOR), OWNER),
+            Error::<Test>::OperatorKeyInUse
+        );
+        assert!(!OperatedModule::<Test>::contains_key(OPERATOR));
+    });
+}
+
+#[test]
+fn operator_keys_are_exclusive() {
+    new_test_ext().execute_with(|| {
+        setup();
+
+        assert_err!(
+            grant(OWNER, OWNER, &[OperatorPermission::SetWeights]),
+            Error::<Test>::OperatorKeyInUse
+        );
+        assert_err!(
+            grant(OWNER, 1, &[OperatorPermission::SetWeights]),
+            Error::<Test>::OperatorKeyInUse
+        );
+        assert_err!(
+            grant(OWNER, OPERATOR, &[]),
+            Error::<Test>::InvalidOperatorPermissions
+        );
+
+        assert_ok!(grant(OWNER, OPERATOR, &[OperatorPermission::SetWeights]));
+        assert_err!(
+            grant(1, OPERATOR, &[OperatorPermission::SetWeights]),
+            Error::<Test>::OperatorKeyInUse
+        );
+        assert_err!(
+            register_module(NETUID, OPERATOR, to_nano(100), false),
+            Error::<Test>::OperatorKeyInUse
+        );
+    });
+}
+
+#[test]
+fn removed_operators_lose_access() {
+    new_test_ext().execute_with(|| {
+        setup();
+
+        assert_ok!(grant(OWNER, OPERATOR, &[OperatorPermission::SetWeights]));
+        assert_ok!(SubspaceMod::remove_operator_key(
+            get_origin(OWNER),
+            OPERATOR
+        ));
+
+        assert_err!(set_weights_as(OPERATOR), Error::<Test>::ModuleDoesNotExist);
+        assert_err!(
+            SubspaceMod::remove_operator_key(get_origin(OWNER), OPERATOR),
+            Error::<Test>::OperatorKeyNotFound
+        );
+    });
+}
+
+#[test]
+fn deregistration_clears_operator_keys() {
+    new_test_ext().execute_with(|| {
+        setup();
+
+        assert_ok!(grant(OWNER, OPERATOR, &[OperatorPermission::SetWeights]));
+        assert_ok!(SubspaceMod::deregister(get_origin(OWNER), NETUID));
+
+        assert!(!OperatorKeys::<Test>::contains_key(OWNER, OPERATOR));
+        assert!(!OperatedModule::<Test>::contains_key(OPERATOR));
+    });
+}
@@ -0,0 +1,187 @@
+use crate::mock::*;
+use frame_support::assert_err;
+use pallet_subspace::*;
+
+const NETUID: u16 = 0;
+const MANAGER: AccountId = 10;
+const ALICE: AccountId = 11;
+const BOB: AccountId = 12;
+
+fn setup() -> NominationPool<Test> {
+    zero_min_burn();
+    MinimumAllowedStake::<Test>::set(0);
+
+    for key in 0..2 {
+        assert_ok!(register_module(NETUID, key, to_nano(100), false));
+    }
+    for key in [ALICE, BOB] {
+        add_balance(key, to_nano(1_000));
+    }
+
+    assert_ok!(SubspaceMod::create_pool(get_origin(MANAGER), vec![0, 1]));
+    NominationPools::<Test>::get(0).unwrap()
+}
+
+#[test]
+fn joining_stakes_evenly_across_pool_modules() {
+    new_test_ext().execute_with(|| {
+        let pool = setup();
+
+        assert_ok!(SubspaceMod::join_pool(get_origin(ALICE), 0, to_nano(100)));
+
+        assert_eq!(StakeTo::<Test>::get(pool.account, 0), to_nano(50));
+        assert_eq!(StakeTo::<Test>::get(pool.account, 1), to_nano(50));
+        assert_eq!(get_balance(ALICE), to_nano(900));
+        assert_eq!(
+            PoolMembers::<Test>::get(ALICE, 0).map(|member| member.shares),
+            Some(to_nano(100))
+        );
+        assert_eq!(SubspaceMod::get_pooled_stake(&ALICE), to_nano(100));
+    });
+}
+
+#[test]
+fn shares_track_compounded_rewards() {
+    new_test_ext().execute_with(|| {
+        let pool = setup();
+
+        assert_ok!(SubspaceMod::join_pool(get_origin(ALICE), 0, to_nano(100)));
+        SubspaceMod::increase_stake(&pool.account, &0, to_nano(50));
+
+        // the pool is worth 150 for 100 shares, so 150 tokens buy 100 shares
+        assert_ok!(SubspaceMod::join_pool(get_origin(BOB), 0, to_nano(150)));
+
+        assert_eq!(
+            PoolMembers::<Test>::get(BOB, 0).map(|member| member.shares),
+            Some(to_nano(100))
+        );
+        assert_eq!(SubspaceMod::get_pooled_stake(&ALICE), to_nano(150));
+        assert_eq!(SubspaceMod::get_pooled_stake(&BOB), to_nano(150));
+    });
+}
+
+#[test]
+fn leaving_pays_out_the_member_value() {
+    new_test_ext().execute_with(|| {
+        let pool = setup();
+
+        assert_ok!(SubspaceMod::join_pool(get_origin(ALICE), 0, to_nano(100)));
+        SubspaceMod::increase_stake(&pool.account, &0, to_nano(50));
+
+        assert_err!(
+            SubspaceMod::leave_pool(get_origin(ALICE), 0, to_nano(101)),
+            Error::<Test>::NotEnoughPoolShares
+        );
+        assert_ok!(SubspaceMod::leave_pool(get_origin(ALICE), 0, to_nano(100)));
+
+        assert_eq!(get_balance(ALICE), to_nano(1_050));
+        assert_eq!(SubspaceMod::get_owned_stake(&pool.account), 0);
+        assert!(!PoolMembers::<Test>::contains_key(ALICE, 0));
+        assert_eq!(NominationPools::<Test>::get(0).unwrap().total_shares, 0);
+    });
+}
+
+#[test]
+fn claiming_pays_out_only_rewards() {
+    new_test_ext().execute_with(|| {
+        let pool = setup();
+
+        assert_ok!(SubspaceMod::join_pool(get_origin(ALICE), 0, to_nano(100)));
+        assert_err!(
+            SubspaceMod::claim_pool_rewards(get_origin(ALICE), 0),
+            Error::<Test>::NothingToClaim
+        );
+
+        SubspaceMod::increase_stake(&pool.account, &0, to_nano(100));
+        assert_ok!(SubspaceMod::claim_pool_rewards(get_origin(ALICE), 0));
+
+        assert_eq!(get_balance(ALICE), to_nano(1_000));
+        assert_eq!(SubspaceMod::get_pooled_stake(&ALICE), to_nano(100));
+        assert_err!(
+            SubspaceMod::claim_pool_rewards(get_origin(ALICE), 0),
+            Error::<Test>::NothingToClaim
+        );
+        assert_err!(
+            SubspaceMod::claim_pool_rewards(get_origin(BOB), 0),
+            Error::<Test>::NotPoolMember
+        );
+    });
+}
+
+#[test]
+fn manager_moves_pool_stake_to_new_modules() {
+    new_test_ext().execute_with(|| {
+        let pool = setup();
+        assert_ok!(register_module(NETUID, 2, to_nano(100), false));
+
+        assert_ok!(SubspaceMod::join_pool(get_origin(ALICE), 0, to_nano(100)));
+
+        assert_err!(
+            SubspaceMod::set_pool_modules(get_origin(ALICE), 0, vec![2]),
+            Error::<Test>::NotPoolManager
+        );
+        assert_err!(
+            SubspaceMod::set_pool_modules(get_origin(MANAGER), 0, vec![2, 2]),
+            Error::<Test>::InvalidPoolModules
+        );
+        assert_err!(
+            SubspaceMod::set_pool_modules(get_origin(MANAGER), 0, vec![3]),
+            Error::<Test>::ModuleDoesNotExist
+        );
+        assert_ok!(SubspaceMod::set_pool_modules(
+            get_origin(MANAGER),
+            0,
+            vec![2]
+        ));
+
+        assert_eq!(StakeTo::<Test>::get(pool.account, 0), 0);
+        assert_eq!(StakeTo::<Test>::get(pool.account, 1), 0);
+        assert_eq!(StakeTo::<Test>::get(pool.account, 2), to_nano(100));
+        assert_eq!(SubspaceMod::get_pooled_stake(&ALICE), to_nano(100));
+    });
+}
+
+#[test]
+fn stake_of_deregistered_modules_stays_in_the_pool() {
+    new_test_ext().execute_with(|| {
+        let pool = setup();
+
+        assert_ok!(SubspaceMod::join_pool(get_origin(ALICE), 0, to_nano(100)));
+        assert_ok!(SubspaceMod::deregister(get_origin(1), NETUID));
+
+        assert_eq!(get_balance(pool.account), to_nano(50));
+        let pool = NominationPools::<Test>::get(0).unwrap();
+        assert_eq!(pool.idle, to_nano(50));
+        assert_eq!(SubspaceMod::get_pool_value(&pool), to_nano(100));
+
+        // new members only stake to the modules left
+        assert_ok!(SubspaceMod::join_pool(get_origin(BOB), 0, to_nano(100)));
+        assert_eq!(StakeTo::<Test>::get(pool.account, 0), to_nano(150));
+
+        assert_ok!(SubspaceMod::leave_pool(get_origin(ALICE), 0, to_nano(100)));
+        assert_eq!(get_balance(ALICE), to_nano(1_000));
+    });
+}
+
+#[test]
+fn donations_to_the_pool_account_dont_move_the_share_price() {
+    new_test_ext().execute_with(|| {
+        let pool = setup();
+
+        assert_ok!(SubspaceMod::join_pool(get_origin(ALICE), 0, to_nano(100)));
+        add_balance(pool.account, to_nano(500));
+        assert_eq!(SubspaceMod::get_pool_value(&pool), to_nano(100));
+
+        // the next member still gets shares at the deposited value
+        assert_ok!(SubspaceMod::join_pool(get_origin(BOB), 0, to_nano(100)));
+        assert_eq!(
+            PoolMembers::<Test>::get(BOB, 0).map(|member| member.shares),
+            Some(to_nano(100))
+        );
+
+        // and the donation isn't paid out to the members
+        assert_ok!(SubspaceMod::leave_pool(get_origin(ALICE), 0, to_nano(100)));
+        assert_eq!(get_balance(ALICE), to_nano(1_000));
+        assert_eq!(get_balance(pool.account), to_nano(500));
+    });
+}
@@ -1,7 +1,7 @@
 use std::collections::BTreeSet;
 
 use crate::mock::*;
-use frame_support::{assert_err, assert_noop, dispatch::DispatchResult};
+use frame_support::{assert_err, assert_noop, dispatch::DispatchResult, traits::Hooks};
 use pallet_subnet_emission::SubnetConsensusType;
 use pallet_subnet_emission_api::SubnetConsensus;
 use pallet_subspace::*;
@@ -622,3 +622,435 @@ fn test_subnet_immunity() {
         );
     });
 }
+
+mod pruning_policy {
+    use super::*;
+    use pallet_subspace::params::subnet::SubnetChangeset;
+
+    const NETUID: u16 = 0;
+
+    /// Registers modules `0..4` one block apart, so the module with key 0 is the oldest.
+    /// Stake, trust and emission are set so each policy prunes a different module.
+    fn setup_modules() {
+        setup_subnet(NETUID, 0, to_nano(40));
+        step_block(1);
+
+        let stakes = [to_nano(10), to_nano(30), to_nano(20)];
+        for (key, stake) in (1..).zip(stakes) {
+            assert_ok!(register_module(NETUID, key, stake, false));
+            step_block(1);
+        }
+
+        Emission::<Test>::insert(NETUID, vec![100, 100, 100, 100]);
+        Dividends::<Test>::insert(NETUID, vec![0, u16::MAX, 0, u16::MAX]);
+        Incentive::<Test>::insert(NETUID, vec![u16::MAX, 0, u16::MAX, 0]);
+        Trust::<Test>::insert(NETUID, vec![40, 30, 10, 20]);
+        ValidatorPermits::<Test>::insert(NETUID, vec![false; 4]);
+    }
+
+    fn set_policy(pruning_policy: PruningPolicy) {
+        update_params!(NETUID => { pruning_policy: pruning_policy });
+    }
+
+    fn lowest_key(ignore_immunity: bool) -> Option<AccountId> {
+        SubspaceMod::get_lowest_uid(NETUID, ignore_immunity)
+            .and_then(|uid| Keys::<Test>::get(NETUID, uid))
+    }
+
+    #[test]
+    fn default_policy_keeps_previous_emission_score() {
+        new_test_ext().execute_with(|| {
+            assert_eq!(
+                PruningPolicy::default(),
+                PruningPolicy::EmissionScore {
+                    dividend_weight: Percent::from_percent(30),
+                    incentive_weight: Percent::from_percent(100),
+                }
+            );
+
+            setup_modules();
+
+            // Dividends weight less than incentives, so the oldest validator goes first.
+            assert_eq!(lowest_key(false), Some(1));
+        });
+    }
+
+    #[test]
+    fn emission_score_uses_custom_weights() {
+        new_test_ext().execute_with(|| {
+            setup_modules();
+
+            set_policy(PruningPolicy::EmissionScore {
+                dividend_weight: Percent::from_percent(100),
+                incentive_weight: Percent::from_percent(10),
+            });
+            assert_eq!(lowest_key(false), Some(0));
+
+            set_policy(PruningPolicy::EmissionScore {
+                dividend_weight: Percent::from_percent(0),
+                incentive_weight: Percent::from_percent(50),
+            });
+            assert_eq!(lowest_key(false), Some(1));
+        });
+    }
+
+    #[test]
+    fn emission_score_prunes_oldest_without_emission() {
+        new_test_ext().execute_with(|| {
+            setup_modules();
+            Emission::<Test>::insert(NETUID, vec![100, 100, 0, 0]);
+            Dividends::<Test>::insert(NETUID, vec![u16::MAX, u16::MAX, 0, 0]);
+            Incentive::<Test>::insert(NETUID, vec![u16::MAX, u16::MAX, 0, 0]);
+
+            assert_eq!(lowest_key(false), Some(2));
+        });
+    }
+
+    #[test]
+    fn lowest_stake_prunes_least_staked_module() {
+        new_test_ext().execute_with(|| {
+            setup_modules();
+            set_policy(PruningPolicy::LowestStake);
+
+            assert_eq!(lowest_key(false), Some(1));
+
+            SubspaceMod::increase_stake(&1, &1, to_nano(100));
+            assert_eq!(lowest_key(false), Some(3));
+        });
+    }
+
+    #[test]
+    fn lowest_trust_prunes_least_trusted_module() {
+        new_test_ext().execute_with(|| {
+            setup_modules();
+            set_policy(PruningPolicy::LowestTrust);
+
+            assert_eq!(lowest_key(false), Some(2));
+        });
+    }
+
+    #[test]
+    fn oldest_first_prunes_oldest_module() {
+        new_test_ext().execute_with(|| {
+            setup_modules();
+            set_policy(PruningPolicy::OldestFirst);
+
+            assert_eq!(lowest_key(false), Some(0));
+        });
+    }
+
+    #[test]
+    fn ties_are_broken_by_registration_age() {
+        new_test_ext().execute_with(|| {
+            setup_modules();
+            set_policy(PruningPolicy::LowestTrust);
+
+            Trust::<Test>::insert(NETUID, vec![40, 10, 10, 10]);
+            assert_eq!(lowest_key(false), Some(1));
+        });
+    }
+
+    #[test]
+    fn policies_skip_validators_with_permits() {
+        new_test_ext().execute_with(|| {
+            setup_modules();
+            ValidatorPermits::<Test>::insert(NETUID, vec![true, true, true, false]);
+
+            for policy in [
+                PruningPolicy::default(),
+                PruningPolicy::LowestStake,
+                PruningPolicy::LowestTrust,
+                PruningPolicy::OldestFirst,
+            ] {
+                set_policy(policy);
+                assert_eq!(lowest_key(false), Some(3), "{policy:?}");
+            }
+
+            ValidatorPermits::<Test>::insert(NETUID, vec![true; 4]);
+            assert_eq!(lowest_key(true), None);
+        });
+    }
+
+    #[test]
+    fn policies_respect_immunity_period() {
+        new_test_ext().execute_with(|| {
+            setup_modules();
+            set_policy(PruningPolicy::LowestStake);
+
+            // Only the oldest module is out of the immunity period.
+            ImmunityPeriod::<Test>::insert(NETUID, 4);
+            assert_eq!(lowest_key(false), Some(0));
+            assert_eq!(lowest_key(true), Some(1));
+
+            ImmunityPeriod::<Test>::insert(NETUID, 100);
+            assert_eq!(lowest_key(false), None);
+        });
+    }
+
+    #[test]
+    fn rejects_emission_score_without_weights() {
+        new_test_ext().execute_with(|| {
+            setup_modules();
+
+            let params = SubnetParams {
+                pruning_policy: PruningPolicy::EmissionScore {
+                    dividend_weight: Percent::zero(),
+                    incentive_weight: Percent::zero(),
+                },
+                ..SubspaceMod::subnet_params(NETUID)
+            };
+            assert_err!(
+                SubnetChangeset::<Test>::update(NETUID, params),
+                Error::<Test>::InvalidPruningPolicy
+            );
+        });
+    }
+
+    #[test]
+    fn full_subnet_registration_prunes_by_policy() {
+        for (policy, pruned) in [
+            (PruningPolicy::default(), 1),
+            (PruningPolicy::LowestStake, 1),
+            (PruningPolicy::LowestTrust, 2),
+            (PruningPolicy::OldestFirst, 0),
+        ] {
+            new_test_ext().execute_with(|| {
+                setup_modules();
+                set_policy(policy);
+                MaxAllowedUids::<Test>::insert(NETUID, 4);
+
+                assert_ok!(register_module(NETUID, 10, to_nano(50), false));
+
+                assert_eq!(N::<Test>::get(NETUID), 4, "{policy:?}");
+                assert!(!Uids::<Test>::contains_key(NETUID, pruned), "{policy:?}");
+                assert!(Uids::<Test>::contains_key(NETUID, 10), "{policy:?}");
+            });
+        }
+    }
+}
+
+mod registration_mode {
+    use super::*;
+
+    const NETUID: u16 = 1;
+    const FOUNDER: AccountId = 0;
+    const BURN: u64 = to_nano(10);
+
+    fn setup_mode_subnet(mode: RegistrationMode) {
+        setup_subnet(NETUID, FOUNDER, to_nano(10));
+        update_params!(NETUID => { registration_mode: mode });
+        Burn::<Test>::insert(NETUID, BURN);
+    }
+
+    fn request(key: AccountId) -> DispatchResult {
+        SubspaceMod::add_balance_to_account(&key, to_nano(20));
+        SubspaceMod::register(
+            get_origin(key),
+            b"test1".to_vec(),
+            format!("module{key}").into_bytes(),
+            b"0.0.0.0:30333".to_vec(),
+            key,
+            None,
+        )
+    }
+
+    fn allow(key: AccountId) {
+        assert_ok!(SubspaceMod::add_to_registration_allowlist(
+            get_origin(FOUNDER),
+            NETUID,
+            key
+        ));
+    }
+
+    #[test]
+    fn open_mode_registers_anyone() {
+        new_test_ext().execute_with(|| {
+            setup_mode_subnet(RegistrationMode::Open);
+
+            assert_ok!(request(1));
+            assert!(Uids::<Test>::contains_key(NETUID, 1));
+        });
+    }
+
+    #[test]
+    fn allowlist_mode_only_registers_allowed_keys() {
+        new_test_ext().execute_with(|| {
+            setup_mode_subnet(RegistrationMode::Allowlist);
+
+            assert_err!(request(1), Error::<Test>::NotInRegistrationAllowlist);
+            assert_eq!(SubspaceMod::get_balance_u64(&1), to_nano(20));
+
+            allow(1);
+            assert_ok!(request(1));
+            assert!(Uids::<Test>::contains_key(NETUID, 1));
+            assert_eq!(SubspaceMod::get_balance_u64(&1), to_nano(40) - BURN);
+
+            assert_ok!(SubspaceMod::remove_from_registration_allowlist(
+                get_origin(FOUNDER),
+                NETUID,
+                1
+            ));
+            assert_err!(request(2), Error::<Test>::NotInRegistrationAllowlist);
+            // Removing a key from the allowlist keeps its module.
+            assert!(Uids::<Test>::contains_key(NETUID, 1));
+        });
+    }
+
+    #[test]
+    fn only_founder_manages_registrations() {
+        new_test_ext().execute_with(|| {
+            setup_mode_subnet(RegistrationMode::FounderApproval);
+            assert_ok!(request(1));
+
+            assert_err!(
+                SubspaceMod::add_to_registration_allowlist(get_origin(2), NETUID, 2),
+                Error::<Test>::NotFounder
+            );
+            assert_err!(
+                SubspaceMod::remove_from_registration_allowlist(get_origin(2), NETUID, 1),
+                Error::<Test>::NotFounder
+            );
+            assert_err!(
+                SubspaceMod::approve_registration(get_origin(2), NETUID, 1),
+                Error::<Test>::NotFounder
+            );
+            assert_err!(
+                SubspaceMod::reject_registration(get_origin(2), NETUID, 1),
+                Error::<Test>::NotFounder
+            );
+            assert_err!(
+                SubspaceMod::approve_registration(get_origin(FOUNDER), 99, 1),
+                Error::<Test>::NetuidDoesNotExist
+            );
+        });
+    }
+
+    #[test]
+    fn founder_approval_queues_until_approved() {
+        new_test_ext().execute_with(|| {
+            setup_mode_subnet(RegistrationMode::FounderApproval);
+
+            assert_ok!(request(1));
+            assert!(!Uids::<Test>::contains_key(NETUID, 1));
+            assert_eq!(SubspaceMod::get_balance_u64(&1), to_nano(20) - BURN);
+
+            let pending = PendingRegistrations::<Test>::get(NETUID, 1).unwrap();
+            assert_eq!(pending.payer, 1);
+            assert_eq!(pending.charge, RegistrationCharge::Burned(BURN));
+
+            assert_err!(request(1), Error::<Test>::RegistrationAlreadyPending);
+
+            assert_ok!(SubspaceMod::approve_registration(
+                get_origin(FOUNDER),
+                NETUID,
+                1
+            ));
+            assert!(Uids::<Test>::contains_key(NETUID, 1));
+            assert!(!PendingRegistrations::<Test>::contains_key(NETUID, 1));
+            // The burn was paid with the request.
+            assert_eq!(SubspaceMod::get_balance_u64(&1), to_nano(40) - BURN);
+
+            assert_err!(
+                SubspaceMod::approve_registration(get_origin(FOUNDER), NETUID, 1),
+                Error::<Test>::RegistrationNotPending
+            );
+        });
+    }
+
+    #[test]
+    fn founder_approval_skips_queue_for_allowed_keys() {
+        new_test_ext().execute_with(|| {
+            setup_mode_subnet(RegistrationMode::FounderApproval);
+
+            allow(1);
+            assert_ok!(request(1));
+            assert!(Uids::<Test>::contains_key(NETUID, 1));
+            assert!(!PendingRegistrations::<Test>::contains_key(NETUID, 1));
+        });
+    }
+
+    #[test]
+    fn rejected_registrations_are_refunded() {
+        new_test_ext().execute_with(|| {
+            setup_mode_subnet(RegistrationMode::FounderApproval);
+
+            assert_ok!(request(1));
+            assert_ok!(SubspaceMod::reject_registration(
+                get_origin(FOUNDER),
+                NETUID,
+                1
+            ));
+
+            assert!(!Uids::<Test>::contains_key(NETUID, 1));
+            assert!(!PendingRegistrations::<Test>::contains_key(NETUID, 1));
+            assert_eq!(SubspaceMod::get_balance_u64(&1), to_nano(20));
+
+            assert_err!(
+                SubspaceMod::reject_registration(get_origin(FOUNDER), NETUID, 1),
+                Error::<Test>::RegistrationNotPending
+            );
+        });
+    }
+
+    #[test]
+    fn unanswered_registrations_expire_and_are_refunded() {
+        new_test_ext().execute_with(|| {
+            setup_mode_subnet(RegistrationMode::FounderApproval);
+            PendingRegistrationTimeout::<Test>::set(10);
+
+            assert_ok!(request(1));
+            let expires_at = PendingRegistrations::<Test>::get(NETUID, 1).unwrap().expires_at;
+            assert_eq!(expires_at, System::block_number() + 10);
+
+            step_block(9);
+            assert!(PendingRegistrations::<Test>::contains_key(NETUID, 1));
+
+            step_block(1);
+            assert!(!PendingRegistrations::<Test>::contains_key(NETUID, 1));
+            assert!(!PendingRegistrationExpiries::<Test>::contains_key(
+                expires_at,
+                (NETUID, 1)
+            ));
+            assert!(!Uids::<Test>::contains_key(NETUID, 1));
+            assert_eq!(SubspaceMod::get_balance_u64(&1), to_nano(20));
+            assert_err!(
+                SubspaceMod::approve_registration(get_origin(FOUNDER), NETUID, 1),
+                Error::<Test>::RegistrationNotPending
+            );
+        });
+    }
+
+    #[test]
+    fn removed_subnet_refunds_pending_registrations() {
+        new_test_ext().execute_with(|| {
+            setup_mode_subnet(RegistrationMode::FounderApproval);
+
+            assert_ok!(request(1));
+            SubspaceMod::remove_subnet(NETUID);
+
+            assert!(!PendingRegistrations::<Test>::contains_key(NETUID, 1));
+            assert_eq!(SubspaceMod::get_balance_u64(&1), to_nano(20));
+        });
+    }
+}
+
+#[test]
+fn try_state_detects_inconsistent_module_storage() {
+    new_test_ext().execute_with(|| {
+        zero_min_burn();
+        assert_ok!(register_module(0, 0, to_nano(10), false));
+        assert_ok!(register_module(0, 1, to_nano(10), false));
+        assert_ok!(<SubspaceMod as Hooks<u64>>::try_state(0));
+
+        Emission::<Test>::mutate(0, |emission| emission.push(0));
+        assert!(<SubspaceMod as Hooks<u64>>::try_state(0).is_err());
+        Emission::<Test>::mutate(0, |emission| emission.pop());
+
+        N::<Test>::insert(0, 1);
+        assert!(<SubspaceMod as Hooks<u64>>::try_state(0).is_err());
+        N::<Test>::insert(0, 2);
+
+        pallet_subnet_emission::Weights::<Test>::insert(0, 2, vec![(0, 1)]);
+        assert!(<SubnetEmissionMod as Hooks<u64>>::try_state(0).is_err());
+    });
+}
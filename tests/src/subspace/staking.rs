@@ -1,5 +1,5 @@
 use crate::mock::*;
-use frame_support::assert_noop;
+use frame_support::{assert_noop, traits::Hooks};
 use pallet_subspace::*;
 use substrate_fixed::types::I64F64;
 
@@ -218,3 +218,19 @@ fn test_ownership_ratio() {
         }
     });
 }
+
+#[test]
+fn try_state_detects_stake_storage_drift() {
+    new_test_ext().execute_with(|| {
+        zero_min_burn();
+        assert_ok!(register_module(0, 0, to_nano(10), false));
+        assert_ok!(<SubspaceMod as Hooks<u64>>::try_state(0));
+
+        TotalStake::<Test>::mutate(|total| *total += 1);
+        assert!(<SubspaceMod as Hooks<u64>>::try_state(0).is_err());
+        TotalStake::<Test>::mutate(|total| *total -= 1);
+
+        StakeTo::<Test>::insert(1, 0, to_nano(1));
+        assert!(<SubspaceMod as Hooks<u64>>::try_state(0).is_err());
+    });
+}
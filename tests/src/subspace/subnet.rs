@@ -68,6 +68,7 @@ fn subnet_update_changes_all_parameter_values() {
                 proposal_reward_treasury_allocation: Percent::from_parts(20),
                 max_proposal_reward_treasury_allocation: 21,
                 proposal_reward_interval: 22,
+                ..Default::default()
             },
             module_burn_config: GeneralBurnConfiguration {
                 min_burn: to_nano(15),
@@ -81,6 +82,8 @@ fn subnet_update_changes_all_parameter_values() {
             use_weights_encryption: true,
             copier_margin: I64F64::from_num(0),
             max_encryption_period: Some(1000),
+            pruning_policy: PruningPolicy::LowestStake,
+            registration_mode: RegistrationMode::FounderApproval,
         };
 
         let SubnetParams {
@@ -104,6 +107,8 @@ fn subnet_update_changes_all_parameter_values() {
             use_weights_encryption,
             copier_margin,
             max_encryption_period,
+            pruning_policy,
+            registration_mode,
         } = params.clone();
 
         SubnetChangeset::<Test>::update(netuid, params).unwrap().apply(netuid).unwrap();
@@ -147,6 +152,11 @@ fn subnet_update_changes_all_parameter_values() {
             MaxEncryptionPeriod::<Test>::get(netuid),
             max_encryption_period
         );
+        assert_eq!(SubnetPruningPolicy::<Test>::get(netuid), pruning_policy);
+        assert_eq!(
+            SubnetRegistrationMode::<Test>::get(netuid),
+            registration_mode
+        );
     });
 }
 
@@ -253,6 +263,8 @@ fn update_subnet_verifies_names_uniquiness_integrity() {
                 params.use_weights_encryption,
                 params.copier_margin,
                 params.max_encryption_period,
+                params.pruning_policy,
+                params.registration_mode,
             )
         };
 
@@ -3,53 +3,76 @@ use std::path::PathBuf;
 xflags::xflags! {
     src "src/flags.rs"
 
-    /// Runs a substrate node.
-    cmd run {
-        /// Overrides the base path of the node. If not set,
-        /// a temporary directory will be used.
-        optional -p, --path path: PathBuf
-
-        /// Initiates the node with the Alice account.
-        /// The Alice node listens on TCP 30341 and RPC 9951.
-        optional --alice
-        /// Initiates the node with the Bob account.
-        /// The Bob node listens on TCP 30342 and RPC 9952.
-        optional --bob
-
-        /// If set, the node will be set to 0 Out/In peers.
-        optional --isolated
-
-        /// Overrides the default node name.
-        optional --node-name node_name: String
-        /// Overrides the default node key.
-        optional --node-key node_key: String
-        /// Overrides whether this node should run as a validator.
-        optional --node-validator node_validator: bool
-
-        /// Overrides the default TCP port.
-        optional --tcp-port tcp_port: u16
-        /// Overrides the default RPC port.
-        optional --rpc-port rpc_port: u16
-        /// Overrides the default bootnode list.
-        repeated --bootnodes bootnodes: String
-
-        /// Starts a local node based on the given chain spec.
-        cmd local {
-            /// The chain spec file. If missing, it will search
-            /// for a `spec.json` file on the current directory.
-            optional -c, --chain-spec chain_spec: PathBuf
-
-            /// The account SURI. The pattern is: `<mnemonic>//<seed>`.
-            optional --account-suri account_suri: String
+    cmd xtask {
+        /// Runs a substrate node.
+        cmd run {
+            /// Overrides the base path of the node. If not set,
+            /// a temporary directory will be used.
+            optional -p, --path path: PathBuf
+
+            /// Initiates the node with the Alice account.
+            /// The Alice node listens on TCP 30341 and RPC 9951.
+            optional --alice
+            /// Initiates the node with the Bob account.
+            /// The Bob node listens on TCP 30342 and RPC 9952.
+            optional --bob
+
+            /// If set, the node will be set to 0 Out/In peers.
+            optional --isolated
+
+            /// Overrides the default node name.
+            optional --node-name node_name: String
+            /// Overrides the default node key.
+            optional --node-key node_key: String
+            /// Overrides whether this node should run as a validator.
+            optional --node-validator node_validator: bool
+
+            /// Overrides the default TCP port.
+            optional --tcp-port tcp_port: u16
+            /// Overrides the default RPC port.
+            optional --rpc-port rpc_port: u16
+            /// Overrides the default bootnode list.
+            repeated --bootnodes bootnodes: String
+
+            /// Starts a local node based on the given chain spec.
+            cmd local {
+                /// The chain spec file. If missing, it will search
+                /// for a `spec.json` file on the current directory.
+                optional -c, --chain-spec chain_spec: PathBuf
+
+                /// The account SURI. The pattern is: `<mnemonic>//<seed>`.
+                optional --account-suri account_suri: String
+            }
+
+            /// Runs a mainnet replica with the latest state.
+            cmd replica {
+                /// Also write the generated spec file to disk.
+                optional -o, --output output: PathBuf
+
+                /// The Sudo address to use. SS58 Address.
+                optional --sudo sudo: String
+
+                /// Loads the state from a snapshot created by `xtask snapshot`
+                /// instead of the live mainnet, so no network access is needed.
+                optional --snapshot snapshot: PathBuf
+
+                /// Sets the free balance of a test account.
+                /// The pattern is: `<SS58 address>=<amount in nano>`.
+                repeated --balance balance: String
+            }
         }
 
-        /// Runs a mainnet replica with the latest state.
-        cmd replica {
-            /// Also write the generated spec file to disk.
-            optional -o, --output output: PathBuf
+        /// Saves the mainnet state into a remote-externalities snapshot file,
+        /// to be used with `xtask run replica --snapshot`.
+        cmd snapshot {
+            /// The snapshot file to write.
+            required -o, --output output: PathBuf
 
-            /// The Sudo address to use. SS58 Address.
-            optional --sudo sudo: String
+            /// The RPC node to download the state from.
+            optional --uri uri: String
+
+            /// Hash of the block to snapshot. Defaults to the best block.
+            optional --at at: String
         }
     }
 }
@@ -57,6 +80,17 @@ xflags::xflags! {
 // generated start
 // The following code is generated by `xflags` macro.
 // Run `env UPDATE_XFLAGS=1 cargo build` to regenerate.
+#[derive(Debug)]
+pub struct Xtask {
+    pub subcommand: XtaskCmd,
+}
+
+#[derive(Debug)]
+pub enum XtaskCmd {
+    Run(Run),
+    Snapshot(Snapshot),
+}
+
 #[derive(Debug)]
 pub struct Run {
     pub path: Option<PathBuf>,
@@ -88,9 +122,18 @@ pub struct Local {
 pub struct Replica {
     pub output: Option<PathBuf>,
     pub sudo: Option<String>,
+    pub snapshot: Option<PathBuf>,
+    pub balance: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct Snapshot {
+    pub output: PathBuf,
+    pub uri: Option<String>,
+    pub at: Option<String>,
 }
 
-impl Run {
+impl Xtask {
     #[allow(dead_code)]
     pub fn from_env_or_exit() -> Self {
         Self::from_env_or_exit_()
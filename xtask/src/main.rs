@@ -5,8 +5,11 @@ mod mainnet_spec;
 mod run;
 
 fn main() {
-    let flags = flags::Run::from_env_or_exit();
-    run::run(flags);
+    let flags = flags::Xtask::from_env_or_exit();
+    match flags.subcommand {
+        flags::XtaskCmd::Run(run) => run::run(run),
+        flags::XtaskCmd::Snapshot(snapshot) => mainnet_spec::snapshot(&snapshot),
+    }
 }
 
 #[derive(Clone)]
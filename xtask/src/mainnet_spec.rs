@@ -4,25 +4,43 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use frame_remote_externalities::OnlineConfig;
-use parity_scale_codec::Encode;
+use frame_remote_externalities::{Mode, OfflineConfig, OnlineConfig, SnapshotConfig};
+use parity_scale_codec::{Decode, Encode};
 use sc_client_api::StateBackend;
 
 use sc_service::ChainSpec;
 use serde_json::Value;
-use sp_core::crypto::Ss58Codec;
+use sp_core::{
+    crypto::{AccountId32, Ss58Codec},
+    H256,
+};
 use sp_runtime::{
     generic::{Block, Header},
     traits::BlakeTwo256,
     OpaqueExtrinsic,
 };
 
+const MAINNET_API: &str = "wss://api.communeai.net";
+
 pub fn mainnet_spec(flags: &crate::flags::Replica, dir: &Path) -> PathBuf {
+    let mode = match &flags.snapshot {
+        Some(snapshot) => {
+            if !snapshot.exists() {
+                panic!("Missing snapshot file. Create one with `xtask snapshot -o <file>`");
+            }
+
+            Mode::Offline(OfflineConfig {
+                state_snapshot: SnapshotConfig::new(snapshot),
+            })
+        }
+        None => Mode::Online(online_config(MAINNET_API.to_string(), None, None)),
+    };
+
     let spec = tokio::runtime::Builder::new_multi_thread()
         .enable_all()
         .build()
         .unwrap()
-        .block_on(create_mainnet_spec());
+        .block_on(create_mainnet_spec(mode));
 
     let spec = sc_service::chain_ops::build_spec(spec.as_ref() as &dyn ChainSpec, true).unwrap();
     let mut js: Value = serde_json::from_str(&spec).unwrap();
@@ -32,7 +50,9 @@ pub fn mainnet_spec(flags: &crate::flags::Replica, dir: &Path) -> PathBuf {
     aura(genesis);
     grandpa(genesis);
     sudo(genesis, flags.sudo.as_ref());
-    // balance(genesis, flags.sudo.as_ref());
+    for balance_override in &flags.balance {
+        balance(genesis, balance_override);
+    }
 
     let js = serde_json::to_string_pretty(&js).unwrap();
 
@@ -94,43 +114,51 @@ fn grandpa(genesis: &mut Value) {
     genesis[&key] = Value::String(format!("0x{}", hex::encode(buf)));
 }
 
-// fn balance(genesis: &mut Value, sudo: Option<&String>) {
-//     let sudo = sudo
-//         .map(|sudo| {
-//             sp_core::ed25519::Public::from_ss58check(&sudo)
-//                 .expect("invalid SS58 sudo address")
-//                 .0
-//         })
-//         .unwrap_or(KEYS[0]);
-
-//     let mut key = key_name(b"System", b"Account");
-//     key.push_str(&hex::encode(
-//         [
-//             sp_crypto_hashing::blake2_128(&sudo).as_slice(),
-//             sudo.as_slice(),
-//         ]
-//         .concat(),
-//     ));
-
-//     let info = AccountInfo {
-//         nonce: 0,
-//         consumers: 0,
-//         providers: 0,
-//         sufficients: 0,
-//         data: AccountData {
-//             free: 1_000_000_000_000_000,
-//             reserved: 0,
-//             frozen: 0,
-//             flags: ExtraFlags(IS_NEW_LOGIC),
-//         },
-//     };
-
-//     let mut buf = Cursor::new(vec![0; KEYS.size_hint()]);
-//     info.encode_to(&mut buf);
-
-//     let buf = &buf.get_ref()[..buf.position() as usize];
-//     genesis[&key] = Value::String(format!("0x{}", hex::encode(buf)));
-// }
+/// Sets the free balance of an account from an `<SS58 address>=<amount>` override, keeping
+/// the total issuance consistent.
+fn balance(genesis: &mut Value, balance_override: &str) {
+    let (address, amount) = balance_override
+        .split_once('=')
+        .expect("balance overrides must look like <SS58 address>=<amount>");
+    let account = AccountId32::from_ss58check(address).expect("invalid SS58 balance address");
+    let amount: Balance = amount.parse().expect("invalid balance amount");
+
+    let account: &[u8] = account.as_ref();
+    let key = format!(
+        "{}{}",
+        key_name(b"System", b"Account"),
+        hex::encode([&sp_crypto_hashing::blake2_128(account)[..], account].concat())
+    );
+
+    let mut info = read_value::<AccountInfo>(genesis, &key).unwrap_or(AccountInfo {
+        nonce: 0,
+        consumers: 0,
+        providers: 1,
+        sufficients: 0,
+        data: AccountData {
+            free: 0,
+            reserved: 0,
+            frozen: 0,
+            flags: ExtraFlags(IS_NEW_LOGIC),
+        },
+    });
+
+    let issuance_key = key_name(b"Balances", b"TotalIssuance");
+    let issuance = read_value::<Balance>(genesis, &issuance_key).unwrap_or_default();
+    let issuance = issuance.saturating_sub(info.data.free).saturating_add(amount);
+
+    info.data.free = amount;
+    info.providers = info.providers.max(1);
+
+    genesis[&key] = Value::String(format!("0x{}", hex::encode(info.encode())));
+    genesis[&issuance_key] = Value::String(format!("0x{}", hex::encode(issuance.encode())));
+}
+
+fn read_value<T: Decode>(genesis: &Value, key: &str) -> Option<T> {
+    let value = genesis.get(key)?.as_str()?;
+    let bytes = hex::decode(value.strip_prefix("0x").unwrap_or(value)).ok()?;
+    T::decode(&mut &bytes[..]).ok()
+}
 
 fn key_name(pallet: &[u8], key: &[u8]) -> String {
     let mut res = [0; 32];
@@ -141,27 +169,57 @@ fn key_name(pallet: &[u8], key: &[u8]) -> String {
 
 type OpaqueBlock = Block<Header<u32, BlakeTwo256>, OpaqueExtrinsic>;
 
-// const IS_NEW_LOGIC: u128 = 0x80000000_00000000_00000000_00000000u128;
+const IS_NEW_LOGIC: u128 = 0x80000000_00000000_00000000_00000000u128;
+
+/// Downloads the whole state of the chain at `uri`, optionally saving it to a snapshot.
+fn online_config(
+    uri: String,
+    at: Option<H256>,
+    state_snapshot: Option<SnapshotConfig>,
+) -> OnlineConfig<H256> {
+    OnlineConfig {
+        at,
+        state_snapshot,
+        pallets: vec![],
+        transport: frame_remote_externalities::Transport::Uri(uri),
+        child_trie: true,
+        hashed_prefixes: vec![],
+        hashed_keys: vec![],
+    }
+}
+
+/// Saves the chain state into a snapshot file that `xtask run replica --snapshot` can load
+/// without network access.
+pub fn snapshot(flags: &crate::flags::Snapshot) {
+    let uri = flags.uri.clone().unwrap_or_else(|| MAINNET_API.to_string());
+    let at = flags.at.as_ref().map(|at| {
+        let hash = hex::decode(at.strip_prefix("0x").unwrap_or(at)).expect("invalid block hash");
+        H256::decode(&mut &hash[..]).expect("invalid block hash")
+    });
+
+    let mode = Mode::Online(online_config(
+        uri,
+        at,
+        Some(SnapshotConfig::new(&flags.output)),
+    ));
+
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(frame_remote_externalities::Builder::<OpaqueBlock>::new().mode(mode).build())
+        .expect("failed to create state snapshot");
 
-async fn create_mainnet_spec() -> Box<dyn ChainSpec> {
+    println!("State snapshot written to {}", flags.output.display());
+}
+
+async fn create_mainnet_spec(mode: Mode<H256>) -> Box<dyn ChainSpec> {
     let mut chain_spec = sc_service::GenericChainSpec::<sc_service::NoExtension>::from_json_bytes(
         include_bytes!("../../node/chain-specs/main.json"),
     )
     .unwrap();
 
-    let api = "wss://api.communeai.net".to_string();
-
-    let mode = frame_remote_externalities::Builder::<OpaqueBlock>::new().mode(
-        frame_remote_externalities::Mode::Online(OnlineConfig {
-            at: None,
-            state_snapshot: None,
-            pallets: vec![],
-            transport: frame_remote_externalities::Transport::Uri(api),
-            child_trie: true,
-            hashed_prefixes: vec![],
-            hashed_keys: vec![],
-        }),
-    );
+    let mode = frame_remote_externalities::Builder::<OpaqueBlock>::new().mode(mode);
 
     let ext = mode.build().await.unwrap();
 